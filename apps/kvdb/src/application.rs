@@ -29,7 +29,19 @@ impl<S: RaftStorage<Self>> Clone for KeyValueDatabase<S> {
     }
 }
 
+impl<S: RaftStorage<Self>> KeyValueDatabase<S> {
+    /// Magic bytes encoded snapshots start with.
+    pub const SNAPSHOT_MAGIC: &'static [u8; 4] = b"KVDB";
+
+    /// Version of the binary format encoded snapshots are in, which follows the magic bytes.
+    ///
+    /// It must be bumped whenever the layout changes, as decoding rejects other versions.
+    pub const SNAPSHOT_FORMAT_VERSION: u8 = 1;
+}
+
 impl<S: RaftStorage<Self>> RaftApplication for KeyValueDatabase<S> {
+    const NAME: &'static str = "KeyValueDatabase";
+
     type Machine = Machine;
 
     type Command = Command;
@@ -40,4 +52,99 @@ impl<S: RaftStorage<Self>> RaftApplication for KeyValueDatabase<S> {
 
     type Storage = S;
     type StorageError = S::Error;
+
+    /// Encodes the snapshot in a compact binary format.
+    ///
+    /// Layout is the magic bytes, the format version byte, the last included index, the last
+    /// included term, the number of peers in the cluster followed by their identifiers, and the
+    /// number of entries followed by the length prefixed key and value of each entry.
+    /// All integers other than the format version are little-endian `u64`s.
+    ///
+    /// Clusters are never empty, so zero peers means the cluster isn't recorded.
+    fn encode_snapshot(snapshot: &Snapshot<Self>) -> Vec<u8> {
+        let machine = &snapshot.machine().0;
+
+        let mut bytes = Vec::new();
+        bytes.extend(Self::SNAPSHOT_MAGIC);
+        bytes.push(Self::SNAPSHOT_FORMAT_VERSION);
+        bytes.extend((snapshot.last_included_index().0 as u64).to_le_bytes());
+        bytes.extend((snapshot.last_included_term().0 as u64).to_le_bytes());
+        let peer_ids = snapshot.cluster().map(|cluster| cluster.iter()).into_iter().flatten();
+        bytes.extend((peer_ids.clone().count() as u64).to_le_bytes());
+        for peer_id in peer_ids {
+            bytes.extend((peer_id.0 as u64).to_le_bytes());
+        }
+        bytes.extend((machine.len() as u64).to_le_bytes());
+        for (key, value) in machine {
+            for string in [key, value] {
+                bytes.extend((string.len() as u64).to_le_bytes());
+                bytes.extend(string.as_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn decode_snapshot(mut bytes: &[u8]) -> Result<Snapshot<Self>, SnapshotDecodingError> {
+        fn read_number(bytes: &mut &[u8]) -> Result<usize, SnapshotDecodingError> {
+            let (number, rest) = bytes
+                .split_first_chunk::<8>()
+                .ok_or_else(|| SnapshotDecodingError("unexpected end of bytes".to_owned()))?;
+            *bytes = rest;
+
+            usize::try_from(u64::from_le_bytes(*number))
+                .map_err(|error| SnapshotDecodingError(error.to_string()))
+        }
+
+        fn read_string(bytes: &mut &[u8]) -> Result<String, SnapshotDecodingError> {
+            let length = read_number(bytes)?;
+            if bytes.len() < length {
+                return Err(SnapshotDecodingError("unexpected end of bytes".to_owned()));
+            }
+            let (string, rest) = bytes.split_at(length);
+            *bytes = rest;
+
+            String::from_utf8(string.to_vec())
+                .map_err(|error| SnapshotDecodingError(error.to_string()))
+        }
+
+        let Some(rest) = bytes.strip_prefix(Self::SNAPSHOT_MAGIC) else {
+            return Err(SnapshotDecodingError("missing magic bytes".to_owned()));
+        };
+        let Some((&version, rest)) = rest.split_first() else {
+            return Err(SnapshotDecodingError("missing format version".to_owned()));
+        };
+        if version != Self::SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotDecodingError(format!("unsupported format version {version}")));
+        }
+        bytes = rest;
+
+        let last_included_index = read_number(&mut bytes)?;
+        let last_included_term = read_number(&mut bytes)?;
+
+        let number_of_peers = read_number(&mut bytes)?;
+        let mut peer_ids = BTreeSet::new();
+        for _ in 0..number_of_peers {
+            peer_ids.insert(PeerId(read_number(&mut bytes)?));
+        }
+        let cluster = if peer_ids.is_empty() { None } else { Some(Cluster::from(peer_ids)) };
+
+        let number_of_entries = read_number(&mut bytes)?;
+        let mut machine = BTreeMap::new();
+        for _ in 0..number_of_entries {
+            let key = read_string(&mut bytes)?;
+            let value = read_string(&mut bytes)?;
+            machine.insert(key, value);
+        }
+
+        if !bytes.is_empty() {
+            return Err(SnapshotDecodingError(format!("{} trailing bytes", bytes.len())));
+        }
+
+        Ok(Snapshot::builder()
+            .last_included_index(last_included_index)
+            .last_included_term(last_included_term)
+            .maybe_cluster(cluster)
+            .machine(Machine(machine))
+            .build())
+    }
 }
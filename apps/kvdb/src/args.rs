@@ -0,0 +1,70 @@
+//! Command line arguments of the debugger.
+
+use {
+    crate::*,
+    clap::Parser as Clap,
+    std::path::PathBuf,
+};
+
+/// Command line arguments of the debugger.
+#[derive(Clap)]
+pub struct Args {
+    /// Sets the directory to store persistent peer data.
+    #[clap(long)]
+    pub data: Option<PathBuf>,
+
+    /// Sets the number of clients.
+    #[clap(long)]
+    pub clients: Option<usize>,
+
+    /// Sets the number of peers.
+    #[clap(long)]
+    pub peers: Option<usize>,
+
+    /// Sets the consistency mode (`strong`, `eventual`, `lease`, `read-index` or `log-read`) [default: strong].
+    #[clap(long, value_name = "MODE")]
+    pub consistency: Option<Consistency>,
+
+    /// Enables eventual consistency (deprecated, use `--consistency eventual` instead).
+    #[clap(long, hide = true, conflicts_with = "consistency")]
+    pub eventual: bool,
+
+    /// Resets the persistent peer data.
+    #[clap(long)]
+    pub reset: bool,
+
+    /// Keeps the persistent peer data read-only.
+    #[clap(long)]
+    pub readonly: bool,
+
+    /// Persists the buffered transmits of the peers to restore them in the next session.
+    #[clap(long)]
+    pub persist_transmits: bool,
+
+    /// Appends the logs of the session to a file alongside the logs panel.
+    #[clap(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Verifies that the machines of the peers agree on the applied state after the session.
+    #[clap(long)]
+    pub verify: bool,
+
+    /// Drives the simulation to the point of a built-in scenario before debugging it.
+    #[clap(long, value_name = "NAME", conflicts_with = "inspect")]
+    pub scenario: Option<Scenario>,
+
+    /// Loads the persistent data of every peer in the data directory read-only to inspect it.
+    #[clap(long, conflicts_with_all = ["peers", "reset"])]
+    pub inspect: bool,
+}
+
+impl Args {
+    /// Gets the consistency mode, taking the deprecated `--eventual` flag into account.
+    pub fn consistency(&self) -> Consistency {
+        match self.consistency {
+            Some(consistency) => consistency,
+            None if self.eventual => Consistency::Eventual,
+            None => Consistency::Strong,
+        }
+    }
+}
@@ -11,12 +11,63 @@ pub enum Command {
     Upsert { key: String, value: String },
     /// Clears a key.
     Clear { key: String },
+    /// Increments the numeric value of a key, treating a missing key as zero.
+    Increment { key: String, by: i64 },
+    /// Decrements the numeric value of a key, treating a missing key as zero.
+    Decrement { key: String, by: i64 },
+}
+
+impl Command {
+    /// Gets the key the command modifies, if any.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Command::NoOp => None,
+            Command::Insert { key, .. }
+            | Command::Upsert { key, .. }
+            | Command::Clear { key }
+            | Command::Increment { key, .. }
+            | Command::Decrement { key, .. } => Some(key),
+        }
+    }
 }
 
 impl RaftCommand for Command {
     fn no_op() -> Self {
         Command::NoOp
     }
+
+    fn conflicts_with(&self, other: &Self) -> bool {
+        match (self.key(), other.key()) {
+            (Some(key), Some(other_key)) => key == other_key,
+            _ => false,
+        }
+    }
+
+    fn schemas() -> &'static [InputSchema] {
+        const SCHEMAS: &[InputSchema] = &[
+            InputSchema::new("Insert", &["Key", "Value"]),
+            InputSchema::new("Upsert", &["Key", "Value"]),
+            InputSchema::new("Clear", &["Key"]),
+            InputSchema::new("Increment", &["Key", "Amount"]),
+            InputSchema::new("Decrement", &["Key", "Amount"]),
+        ];
+        SCHEMAS
+    }
+
+    fn from_inputs(name: &str, inputs: &[String]) -> Option<Self> {
+        Some(match (name, inputs) {
+            ("Insert", [key, value]) => Command::Insert { key: key.clone(), value: value.clone() },
+            ("Upsert", [key, value]) => Command::Upsert { key: key.clone(), value: value.clone() },
+            ("Clear", [key]) => Command::Clear { key: key.clone() },
+            ("Increment", [key, amount]) => {
+                Command::Increment { key: key.clone(), by: amount.parse().ok()? }
+            },
+            ("Decrement", [key, amount]) => {
+                Command::Decrement { key: key.clone(), by: amount.parse().ok()? }
+            },
+            _ => return None,
+        })
+    }
 }
 
 /// [RaftCommandResult] of a [Command].
@@ -26,6 +77,22 @@ pub enum CommandResult {
     Done,
     /// Key to be inserted already exists in the database.
     AlreadyExists,
+    /// Numeric value of the key after the increment or the decrement.
+    Value { value: i64 },
+    /// Value of the key to be incremented or decremented is not an integer.
+    NotNumeric,
+    /// Increment or decrement overflowed the integer range.
+    Overflow,
 }
 
-impl RaftCommandResult for CommandResult {}
+impl RaftCommandResult for CommandResult {
+    fn summary(&self) -> String {
+        match self {
+            CommandResult::Done => "Done".to_owned(),
+            CommandResult::AlreadyExists => "Not inserted (key already exists)".to_owned(),
+            CommandResult::Value { value } => format!("Value is now {value}"),
+            CommandResult::NotNumeric => "Not changed (value is not an integer)".to_owned(),
+            CommandResult::Overflow => "Not changed (value would overflow)".to_owned(),
+        }
+    }
+}
@@ -2,9 +2,20 @@
 #![doc = include_str!("../README.md")]
 
 mod application;
+#[cfg(feature = "cli")]
+mod args;
 mod command;
 mod machine;
 mod query;
+#[cfg(feature = "cli")]
+mod scenario;
+
+#[cfg(feature = "cli")]
+#[doc(inline)]
+pub use crate::{
+    args::Args,
+    scenario::Scenario,
+};
 
 #[doc(inline)]
 pub use crate::{
@@ -30,6 +41,7 @@ pub(crate) use {
         collections::{
             btree_map::Entry as BTreeMapEntry,
             BTreeMap,
+            BTreeSet,
         },
         fmt::{
             self,
@@ -5,6 +5,27 @@ use crate::*;
 #[debug("{_0:#?}")]
 pub struct Machine(pub BTreeMap<String, String>);
 
+impl Machine {
+    fn add(&mut self, key: &str, operation: impl FnOnce(i64) -> Option<i64>) -> CommandResult {
+        let current_value = match self.0.get(key) {
+            Some(value) => {
+                match value.parse::<i64>() {
+                    Ok(value) => value,
+                    Err(_) => return CommandResult::NotNumeric,
+                }
+            },
+            None => 0,
+        };
+        match operation(current_value) {
+            Some(new_value) => {
+                self.0.insert(key.to_owned(), new_value.to_string());
+                CommandResult::Value { value: new_value }
+            },
+            None => CommandResult::Overflow,
+        }
+    }
+}
+
 impl<S: RaftStorage<KeyValueDatabase<S>>> RaftMachine<KeyValueDatabase<S>> for Machine {
     fn apply(&mut self, command: &Command) -> CommandResult {
         match command {
@@ -26,6 +47,8 @@ impl<S: RaftStorage<KeyValueDatabase<S>>> RaftMachine<KeyValueDatabase<S>> for M
                 self.0.remove(key);
                 CommandResult::Done
             },
+            Command::Increment { key, by } => self.add(key, |value| value.checked_add(*by)),
+            Command::Decrement { key, by } => self.add(key, |value| value.checked_sub(*by)),
         }
     }
 
@@ -33,6 +56,22 @@ impl<S: RaftStorage<KeyValueDatabase<S>>> RaftMachine<KeyValueDatabase<S>> for M
         match query {
             Query::Length => QueryResult::Length { length: self.0.len() },
             Query::Entry { key } => QueryResult::Entry { value: self.0.get(key).cloned() },
+            Query::Dump => QueryResult::Dump { entries: self.0.clone() },
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn on_applied(&mut self, index: LogIndex, command: &Command) {
+        log::debug!("Applied `{:?}` at log index {}.", command, index);
+    }
+
+    fn check_invariants(&self) -> Result<(), String> {
+        if self.0.contains_key("") {
+            return Err("empty keys are not allowed".to_owned());
         }
+        Ok(())
     }
 }
@@ -3,7 +3,7 @@
 use {
     anyhow::Context,
     clap::Parser as Clap,
-    rafty::prelude::*,
+    rafty::prelude::RaftApplication,
     rafty_debugger::*,
     rafty_kvdb::*,
     rafty_simulator::*,
@@ -19,52 +19,63 @@ use widgets::{
     QuerySelectionWidget,
 };
 
-#[derive(Clap)]
-struct Args {
-    /// Sets the directory to store persistent peer data.
-    #[clap(long)]
-    data: Option<PathBuf>,
-
-    /// Sets the number of clients.
-    #[clap(long)]
-    clients: Option<usize>,
-
-    /// Sets the number of peers.
-    #[clap(long)]
-    peers: Option<usize>,
-
-    /// Enables eventual consistency instead of strong consistency.
-    #[clap(long)]
-    eventual: bool,
-
-    /// Resets the persistent peer data.
-    #[clap(long)]
-    reset: bool,
-
-    /// Keeps the persistent peer data read-only.
-    #[clap(long)]
-    readonly: bool,
-}
-
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+
+    init_logger(args.log_file.as_deref()).context("Failed to initialize the logger")?;
+    log::info!("<$> Starting {} v{}", KeyValueDatabase::<Storage>::NAME, env!("CARGO_PKG_VERSION"));
+
     let data_directory = args.data.clone().unwrap_or(PathBuf::from(".data"));
 
-    let consistency = if args.eventual { Consistency::Eventual } else { Consistency::Strong };
-    let peer_storages = (1..=args.peers.unwrap_or(5))
-        .map(|peer_id| {
-            Storage::new(data_directory.join(peer_id.to_string()), args.reset)
-                .map(|storage| storage.readonly(args.readonly))
-                .with_context(|| format!("Failed to initialize the storage of peer {peer_id}"))
-        })
-        .collect::<anyhow::Result<Vec<Storage>>>()?;
+    let consistency = args.consistency();
     let number_of_clients = args.clients.unwrap_or(2);
 
-    let simulation =
+    let mut simulation = if args.inspect {
+        let peer_data_directories = (1..)
+            .map(|peer_id: usize| data_directory.join(peer_id.to_string()))
+            .take_while(|peer_data_directory| peer_data_directory.is_dir())
+            .collect::<Vec<_>>();
+        Simulation::<KeyValueDatabase<Storage>>::from_snapshot_files(
+            consistency,
+            peer_data_directories,
+            number_of_clients,
+            |peer_data_directory| {
+                Ok(Storage::new(peer_data_directory, false)?
+                    .readonly(true)
+                    .persist_transmits(args.persist_transmits))
+            },
+        )
+        .context("Failed to load the simulation")?
+    } else {
+        let peer_storages = (1..=args.peers.unwrap_or(5))
+            .map(|peer_id| {
+                Storage::new(data_directory.join(peer_id.to_string()), args.reset)
+                    .map(|storage| {
+                        storage.readonly(args.readonly).persist_transmits(args.persist_transmits)
+                    })
+                    .with_context(|| format!("Failed to initialize the storage of peer {peer_id}"))
+            })
+            .collect::<anyhow::Result<Vec<Storage>>>()?;
         Simulation::<KeyValueDatabase<Storage>>::new(consistency, peer_storages, number_of_clients)
-            .context("Failed to initialize the simulation")?;
-    Debugger::<KeyValueDatabase<Storage>, CommandSelectionWidget, QuerySelectionWidget>::new(
-        simulation,
-    )?
-    .start()
+            .context("Failed to initialize the simulation")?
+    };
+    if let Some(scenario) = args.scenario {
+        scenario
+            .setup(&mut simulation)
+            .with_context(|| format!("Failed to set up the {scenario:?} scenario"))?;
+    }
+    let simulation = Debugger::<
+        KeyValueDatabase<Storage>,
+        CommandSelectionWidget,
+        QuerySelectionWidget,
+    >::builder()
+    .simulation(simulation)
+    .build()?
+    .start()?;
+
+    if args.verify {
+        simulation.verify_machines().context("Machines of the peers diverged")?;
+        println!("Machines of all {} peers agree.", simulation.number_of_peers());
+    }
+    Ok(())
 }
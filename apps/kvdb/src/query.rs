@@ -5,15 +5,52 @@ use crate::*;
 pub enum Query {
     Length,
     Entry { key: String },
+    Dump,
 }
 
-impl RaftQuery for Query {}
+impl RaftQuery for Query {
+    fn schemas() -> &'static [InputSchema] {
+        const SCHEMAS: &[InputSchema] = &[
+            InputSchema::new("Length", &[]),
+            InputSchema::new("Entry", &["Key"]),
+            InputSchema::new("Dump", &[]),
+        ];
+        SCHEMAS
+    }
+
+    fn from_inputs(name: &str, inputs: &[String]) -> Option<Self> {
+        Some(match (name, inputs) {
+            ("Length", []) => Query::Length,
+            ("Entry", [key]) => Query::Entry { key: key.clone() },
+            ("Dump", []) => Query::Dump,
+            _ => return None,
+        })
+    }
+}
 
 /// [RaftQueryResult] of a [Query].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum QueryResult {
     Length { length: usize },
     Entry { value: Option<String> },
+    Dump { entries: BTreeMap<String, String> },
 }
 
-impl RaftQueryResult for QueryResult {}
+impl RaftQueryResult for QueryResult {
+    fn summary(&self) -> String {
+        match self {
+            QueryResult::Length { length: 1 } => "1 entry".to_owned(),
+            QueryResult::Length { length } => format!("{length} entries"),
+            QueryResult::Entry { value: Some(value) } => format!("Value is `{value}`"),
+            QueryResult::Entry { value: None } => "Key is not found".to_owned(),
+            QueryResult::Dump { entries } if entries.is_empty() => "No entries".to_owned(),
+            QueryResult::Dump { entries } => {
+                entries
+                    .iter()
+                    .map(|(key, value)| format!("`{key}` is `{value}`"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+        }
+    }
+}
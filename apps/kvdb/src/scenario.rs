@@ -0,0 +1,161 @@
+//! Built-in scenarios of the debugger.
+
+use {
+    crate::*,
+    rafty_simulator::*,
+};
+
+/// A scripted scenario to drive a [Simulation] to an interesting point before debugging it.
+///
+/// Scenarios need at least 3 peers and a client.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum Scenario {
+    /// Peer 1 requests votes, and the votes are awaiting to be transmitted to it.
+    SingleCandidateElection,
+    /// Every peer requests votes at the same time, so none of them gets the majority.
+    SplitVote,
+    /// Peer 1 is the leader and the last peer is partitioned away while a command is replicated,
+    /// and the append entries request to the last peer is awaiting to heal the partition.
+    PartitionAndHeal,
+    /// Peer 1 appends a command it cannot replicate and steps down before peer 2 is elected,
+    /// and the append entries request of peer 2 is awaiting to repair the diverged log of peer 1.
+    LogDivergenceRepair,
+}
+
+impl Scenario {
+    /// Drives a simulation to the interesting point of the scenario.
+    pub fn setup<S: RaftStorage<KeyValueDatabase<S>>>(
+        self,
+        simulation: &mut Simulation<KeyValueDatabase<S>>,
+    ) -> anyhow::Result<()> {
+        if simulation.number_of_peers() < 3 {
+            return Err(anyhow::anyhow!(
+                "Scenario {:?} needs at least 3 peers but there are {}",
+                self,
+                simulation.number_of_peers(),
+            ));
+        }
+
+        match self {
+            Scenario::SingleCandidateElection => {
+                simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+                transmit_requests(simulation, PeerId(1), |_| true)?;
+            },
+            Scenario::SplitVote => {
+                let peer_ids = (1..=simulation.number_of_peers()).map(PeerId).collect();
+                simulation.perform(Action::TimeoutElections { peer_ids })?;
+
+                let max_steps = 2 * simulation.number_of_peers() * simulation.number_of_peers();
+                simulation.transmit_all_pending(false, max_steps)?;
+            },
+            Scenario::PartitionAndHeal => {
+                let partitioned_peer_id = PeerId(simulation.number_of_peers());
+
+                simulation.elect(PeerId(1))?;
+                send_command(simulation, PeerId(1))?;
+
+                let request_ids = transmit_requests(simulation, PeerId(1), |transmit| {
+                    transmit.peer_id() != partitioned_peer_id
+                })?;
+                transmit_replies(simulation, PeerId(1), request_ids)?;
+            },
+            Scenario::LogDivergenceRepair => {
+                simulation.elect(PeerId(1))?;
+                send_command(simulation, PeerId(1))?;
+
+                let request_ids = buffered_requests(simulation, PeerId(1), |_| true);
+                simulation.perform(Action::DropPeerRequests {
+                    peer_id: PeerId(1),
+                    request_ids: request_ids
+                        .into_iter()
+                        .map(|(_, request_id)| request_id)
+                        .collect(),
+                })?;
+
+                simulation.perform(Action::StepDown { peer_id: PeerId(1) })?;
+
+                simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+                let request_ids = transmit_requests(simulation, PeerId(2), |_| true)?;
+                transmit_replies(simulation, PeerId(2), request_ids)?;
+                if !simulation.peer(PeerId(2)).is_some_and(|peer| peer.role().is_leader()) {
+                    return Err(anyhow::anyhow!("Peer 2 didn't receive the majority of the votes"));
+                }
+
+                let request_ids = transmit_requests(simulation, PeerId(2), |transmit| {
+                    transmit.peer_id() != PeerId(1)
+                })?;
+                transmit_replies(simulation, PeerId(2), request_ids)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Sends an insert command to a peer via the first client and transmits it.
+fn send_command<S: RaftStorage<KeyValueDatabase<S>>>(
+    simulation: &mut Simulation<KeyValueDatabase<S>>,
+    peer_id: PeerId,
+) -> anyhow::Result<()> {
+    let client_id = ClientId(1);
+    simulation.perform(Action::SendCommand {
+        client_id,
+        peer_id: Some(peer_id),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+
+    let request_id = simulation
+        .client(client_id)
+        .and_then(|client| client.buffered_client_transmits().back())
+        .map(|transmit| transmit.request_id())
+        .ok_or_else(|| anyhow::anyhow!("Client {} didn't send the command", client_id))?;
+    simulation.perform(Action::TransmitClientRequest { client_id, request_id })?;
+
+    Ok(())
+}
+
+/// Gets the buffered requests of a peer matching a filter alongside the peers they are sent to.
+fn buffered_requests<S: RaftStorage<KeyValueDatabase<S>>>(
+    simulation: &Simulation<KeyValueDatabase<S>>,
+    peer_id: PeerId,
+    filter: impl Fn(&PeerTransmit<KeyValueDatabase<S>>) -> bool,
+) -> Vec<(PeerId, RequestId)> {
+    simulation
+        .peer(peer_id)
+        .into_iter()
+        .flat_map(|peer| peer.buffered_peer_transmits())
+        .filter(|transmit| transmit.message().is_request() && filter(transmit))
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect()
+}
+
+/// Transmits the buffered requests of a peer matching a filter.
+///
+/// Returns the transmitted requests alongside the peers they are sent to.
+fn transmit_requests<S: RaftStorage<KeyValueDatabase<S>>>(
+    simulation: &mut Simulation<KeyValueDatabase<S>>,
+    peer_id: PeerId,
+    filter: impl Fn(&PeerTransmit<KeyValueDatabase<S>>) -> bool,
+) -> anyhow::Result<Vec<(PeerId, RequestId)>> {
+    let requests = buffered_requests(simulation, peer_id, filter);
+    simulation.perform(Action::TransmitPeerRequests {
+        peer_id,
+        request_ids: requests.iter().map(|(_, request_id)| *request_id).collect(),
+    })?;
+    Ok(requests)
+}
+
+/// Transmits the replies to the requests of a peer.
+fn transmit_replies<S: RaftStorage<KeyValueDatabase<S>>>(
+    simulation: &mut Simulation<KeyValueDatabase<S>>,
+    peer_id: PeerId,
+    requests: Vec<(PeerId, RequestId)>,
+) -> anyhow::Result<()> {
+    for (replying_peer_id, request_id) in requests {
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: replying_peer_id,
+            replied_peer_id_and_request_id: (peer_id, request_id),
+        })?;
+    }
+    Ok(())
+}
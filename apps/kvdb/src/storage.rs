@@ -6,33 +6,59 @@ use {
         Serialize,
     },
     std::{
+        collections::{
+            BTreeMap,
+            VecDeque,
+        },
         fs::{
             File,
             OpenOptions,
         },
         io::{
-            BufRead,
-            BufReader,
             Read,
             Seek,
             SeekFrom,
             Write,
         },
-        path::Path,
+        path::{
+            Path,
+            PathBuf,
+        },
     },
 };
 
 /// A [File] based [RaftStorage] for [KeyValueDatabase].
+///
+/// Transactions are first written to a temporary file, which is renamed to the transaction
+/// file once it's complete. Then, the state file is replaced the same way, the log file is
+/// truncated to the length recorded in the transaction if it's longer, the entries are appended
+/// to the log file in place and the transaction file is removed. Completing a transaction is
+/// idempotent, so a transaction file found upon creation is completed again before the storage
+/// is used, which also drops the entries that were partially appended before a crash.
+///
+/// Log file has a log entry per line. The whole log is kept in memory as [RaftStorage::log]
+/// exposes it, and the byte offsets of the lines are indexed by their log indices, so the log
+/// file is truncated and appended to in place without being read again.
+///
+/// Snapshot file is in the binary format of [KeyValueDatabase], and JSON snapshot files written
+/// by earlier versions are migrated to it upon creation.
+///
+/// Buffered peer transmits are only persisted, to the transmits file, if it's enabled.
 pub struct Storage {
+    directory: PathBuf,
+
     state_file: File,
     log_file: File,
     snapshot_file: File,
 
     state: State,
     log: Log<KeyValueDatabase<Self>>,
+    log_offsets: BTreeMap<LogIndex, u64>,
     snapshot: Snapshot<KeyValueDatabase<Self>>,
+    transmits: Vec<PeerTransmit<KeyValueDatabase<Self>>>,
 
     readonly: bool,
+    persist_transmits: bool,
 }
 
 impl Storage {
@@ -46,7 +72,7 @@ impl Storage {
 
         let state_path = directory.join("state.json");
         let log_path = directory.join("log");
-        let snapshot_path = directory.join("snapshot.json");
+        let snapshot_path = directory.join("snapshot");
 
         let mut state_file = OpenOptions::new()
             .create(true)
@@ -91,6 +117,27 @@ impl Storage {
             .read_to_string(&mut state_string)
             .map_err(|error| StorageError::ResettingStateFile(error.to_string()))?;
 
+        let transaction_path = directory.join("transaction.json");
+        if reset && transaction_path.exists() {
+            std::fs::remove_file(&transaction_path)
+                .map_err(|error| StorageError::RemovingTransaction(error.to_string()))?;
+        }
+        let pending_transaction = if transaction_path.exists() {
+            let transaction_string = std::fs::read_to_string(&transaction_path)
+                .map_err(|error| StorageError::ReadingTransaction(error.to_string()))?;
+            let pending_transaction =
+                serde_json::from_str::<PendingTransaction>(&transaction_string)
+                    .map_err(|error| StorageError::ParsingTransaction(error.to_string()))?;
+            // Entries appended before a crash might be incomplete, so they're dropped before
+            // parsing the log, and they're appended again when the transaction is completed.
+            log_file
+                .set_len(pending_transaction.log_length)
+                .map_err(|error| StorageError::TruncatingLogFile(error.to_string()))?;
+            Some(pending_transaction)
+        } else {
+            None
+        };
+
         let mut first_run = false;
         let state = if state_string.is_empty() {
             first_run = true;
@@ -101,13 +148,17 @@ impl Storage {
         };
 
         let mut storage = Storage {
+            directory: directory.to_path_buf(),
             state_file,
             log_file,
             snapshot_file,
             state,
             log: Log::default(),
+            log_offsets: BTreeMap::new(),
             snapshot: Snapshot::default(),
+            transmits: Vec::new(),
             readonly: false,
+            persist_transmits: false,
         };
         if first_run {
             storage
@@ -138,18 +189,49 @@ impl Storage {
                 })
                 .collect::<Result<Vec<_>, StorageError>>()?
                 .into();
+            storage.log_offsets = Storage::index_log(&log_string, &storage.log);
 
-            let mut snapshot_string = String::new();
+            let mut snapshot_bytes = Vec::new();
             storage
                 .snapshot_file
                 .seek(SeekFrom::Start(0))
                 .map_err(|error| StorageError::ReadingSnapshotFile(error.to_string()))?;
             storage
                 .snapshot_file
-                .read_to_string(&mut snapshot_string)
+                .read_to_end(&mut snapshot_bytes)
                 .map_err(|error| StorageError::ReadingSnapshotFile(error.to_string()))?;
-            storage.snapshot = serde_json::from_str(&snapshot_string)
-                .map_err(|error| StorageError::ParsingSnapshot(error.to_string()))?;
+            let legacy_snapshot_path = storage.legacy_snapshot_path();
+            if snapshot_bytes.is_empty() && legacy_snapshot_path.exists() {
+                // Snapshots used to be persisted as JSON, so they're migrated to the binary format.
+                let snapshot_string = std::fs::read_to_string(&legacy_snapshot_path)
+                    .map_err(|error| StorageError::ReadingSnapshotFile(error.to_string()))?;
+                let snapshot = serde_json::from_str(&snapshot_string)
+                    .map_err(|error| StorageError::ParsingSnapshot(error.to_string()))?;
+                storage
+                    .install_snapshot(snapshot)
+                    .map_err(|error| StorageError::MigratingSnapshot(Box::new(error)))?;
+                std::fs::remove_file(&legacy_snapshot_path)
+                    .map_err(|error| StorageError::RemovingLegacySnapshot(error.to_string()))?;
+            } else {
+                storage.snapshot = KeyValueDatabase::decode_snapshot(&snapshot_bytes)
+                    .map_err(|error| StorageError::ParsingSnapshot(error.to_string()))?;
+            }
+        }
+
+        if let Some(pending_transaction) = pending_transaction {
+            storage.complete_transaction(&pending_transaction)?;
+        }
+
+        let transmits_path = storage.transmits_path();
+        if reset && transmits_path.exists() {
+            std::fs::remove_file(&transmits_path)
+                .map_err(|error| StorageError::RemovingTransmits(error.to_string()))?;
+        }
+        if transmits_path.exists() {
+            let transmits_string = std::fs::read_to_string(&transmits_path)
+                .map_err(|error| StorageError::ReadingTransmits(error.to_string()))?;
+            storage.transmits = serde_json::from_str(&transmits_string)
+                .map_err(|error| StorageError::ParsingTransmits(error.to_string()))?;
         }
 
         Ok(storage)
@@ -160,19 +242,160 @@ impl Storage {
         self.readonly = readonly;
         self
     }
+
+    /// Sets whether buffered peer transmits are persisted and restored.
+    pub fn persist_transmits(mut self, persist_transmits: bool) -> Self {
+        self.persist_transmits = persist_transmits;
+        self
+    }
 }
 
 impl Storage {
-    fn overwrite(file: &mut File, content: &str) -> std::io::Result<()> {
+    fn overwrite(file: &mut File, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+        let content = content.as_ref();
+
         file.seek(SeekFrom::Start(0))?;
         file.set_len(0)?;
         if !content.is_empty() {
-            file.write_all(content.as_bytes())?;
+            file.write_all(content)?;
         }
         file.flush()?;
         Ok(())
     }
 
+    fn index_log(content: &str, log: &Log<KeyValueDatabase<Storage>>) -> BTreeMap<LogIndex, u64> {
+        let mut offset = 0;
+        let mut entries = log.iter();
+        let mut log_offsets = BTreeMap::new();
+        for line in content.split_inclusive("\n") {
+            if !line.trim().is_empty()
+                && let Some(entry) = entries.next()
+            {
+                log_offsets.insert(entry.index(), offset);
+            }
+            offset += line.len() as u64;
+        }
+        log_offsets
+    }
+
+    fn legacy_snapshot_path(&self) -> PathBuf {
+        self.directory.join("snapshot.json")
+    }
+
+    fn transaction_path(&self) -> PathBuf {
+        self.directory.join("transaction.json")
+    }
+
+    fn transmits_path(&self) -> PathBuf {
+        self.directory.join("transmits.json")
+    }
+
+    fn replace(path: &Path, content: impl AsRef<[u8]>) -> std::io::Result<File> {
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+
+        let mut temporary_file = File::create(&temporary_path)?;
+        temporary_file.write_all(content.as_ref())?;
+        temporary_file.sync_all()?;
+        drop(temporary_file);
+
+        std::fs::rename(&temporary_path, path)?;
+
+        let mut file =
+            OpenOptions::new().read(true).write(true).truncate(false).append(false).open(path)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(file)
+    }
+
+    pub(crate) fn begin_transaction(
+        &mut self,
+        transaction: StorageTransaction<KeyValueDatabase<Storage>>,
+    ) -> Result<PendingTransaction, StorageError> {
+        let first_replaced_index = transaction
+            .log_truncation()
+            .into_iter()
+            .chain(transaction.log_entries().first().map(|entry| entry.index()))
+            .min();
+        let log_length =
+            match first_replaced_index.and_then(|index| self.log_offsets.range(index..).next()) {
+                Some((_, offset)) => *offset,
+                None => {
+                    self.log_file
+                        .seek(SeekFrom::End(0))
+                        .map_err(|error| StorageError::ReadingLogFile(error.to_string()))?
+                },
+            };
+
+        let pending_transaction = PendingTransaction { log_length, transaction };
+        let transaction_string = serde_json::to_string(&pending_transaction)
+            .map_err(|error| StorageError::SerializingTransaction(error.to_string()))?;
+        Storage::replace(&self.transaction_path(), transaction_string)
+            .map_err(|error| StorageError::WritingTransaction(error.to_string()))?;
+
+        Ok(pending_transaction)
+    }
+
+    fn complete_transaction(
+        &mut self,
+        pending_transaction: &PendingTransaction,
+    ) -> Result<(), StorageError> {
+        let PendingTransaction { log_length, transaction } = pending_transaction;
+
+        if transaction.current_term().is_some() || transaction.voted_for().is_some() {
+            let mut state = self.state.clone();
+            if let Some(current_term) = transaction.current_term() {
+                state.current_term = current_term;
+            }
+            if let Some(voted_for) = transaction.voted_for() {
+                state.voted_for = voted_for;
+            }
+
+            let state_string = serde_json::to_string_pretty(&state)
+                .map_err(|error| StorageError::SerializingState(error.to_string()))?;
+            self.state_file = Storage::replace(&self.directory.join("state.json"), state_string)
+                .map_err(|error| StorageError::WritingState(error.to_string()))?;
+            self.state = state;
+        }
+
+        let log_file_length = self
+            .log_file
+            .seek(SeekFrom::End(0))
+            .map_err(|error| StorageError::ReadingLogFile(error.to_string()))?;
+        if log_file_length > *log_length {
+            self.log_file
+                .set_len(*log_length)
+                .map_err(|error| StorageError::TruncatingLogFile(error.to_string()))?;
+        }
+        if let Some((&first_removed_index, _)) =
+            self.log_offsets.iter().find(|(_, offset)| **offset >= *log_length)
+        {
+            self.log.retain(|entry| entry.index() < first_removed_index);
+            self.log_offsets.split_off(&first_removed_index);
+        }
+
+        let mut log_content = String::new();
+        let mut log_offsets = Vec::with_capacity(transaction.log_entries().len());
+        for entry in transaction.log_entries() {
+            log_offsets.push((entry.index(), log_length + log_content.len() as u64));
+            log_content += &serde_json::to_string(entry)
+                .map_err(|error| StorageError::SerializingLogEntry(error.to_string()))?;
+            log_content += "\n";
+        }
+        if !log_content.is_empty() {
+            self.log_file
+                .seek(SeekFrom::Start(*log_length))
+                .and_then(|_| self.log_file.write_all(log_content.as_bytes()))
+                .and_then(|_| self.log_file.sync_data())
+                .map_err(|error| StorageError::AppendingLogEntry(error.to_string()))?;
+        }
+
+        self.log_offsets.extend(log_offsets);
+        self.log.extend(transaction.log_entries().iter().cloned());
+
+        std::fs::remove_file(self.transaction_path())
+            .map_err(|error| StorageError::RemovingTransaction(error.to_string()))
+    }
+
     fn flush_state(&mut self) -> Result<(), StorageError> {
         let state_string = serde_json::to_string_pretty(&self.state)
             .map_err(|error| StorageError::SerializingState(error.to_string()))?;
@@ -253,6 +476,11 @@ impl RaftStorage<KeyValueDatabase<Storage>> for Storage {
             .map_err(|error| StorageError::SerializingLogEntry(error.to_string()))?;
         entry_string += "\n";
 
+        // Reading entries moves the cursor of the log file, so it's moved back to the end.
+        let offset = self
+            .log_file
+            .seek(SeekFrom::End(0))
+            .map_err(|error| StorageError::AppendingLogEntry(error.to_string()))?;
         self.log_file
             .write_all(entry_string.as_bytes())
             .map_err(|error| StorageError::AppendingLogEntry(error.to_string()))?;
@@ -262,6 +490,7 @@ impl RaftStorage<KeyValueDatabase<Storage>> for Storage {
             .map_err(|error| StorageError::AppendingLogEntry(error.to_string()));
 
         if result.is_ok() {
+            self.log_offsets.insert(entry.index(), offset);
             self.log.push(entry);
         }
         result
@@ -273,61 +502,28 @@ impl RaftStorage<KeyValueDatabase<Storage>> for Storage {
             return Ok(());
         }
 
-        let log_file = self
-            .log_file
-            .try_clone()
-            .map_err(|error| StorageError::OpeningLogFile(error.to_string()))?;
-        let mut reader = BufReader::new(log_file);
-
-        let mut line = 0;
-        let mut new_content = String::new();
-
-        let mut new_log = Log::default();
-
-        let mut buffer = String::new();
-        loop {
-            let read = reader
-                .read_line(&mut buffer)
-                .map_err(|error| StorageError::ReadingLogFile(error.to_string()))?;
-            if read == 0 {
-                break;
-            }
-
-            line += 1;
-
-            if buffer.trim().is_empty() {
-                new_content += &buffer;
-                buffer.clear();
-                continue;
-            }
-
-            match serde_json::from_str::<LogEntry<KeyValueDatabase<Storage>>>(&buffer) {
-                Ok(entry) => {
-                    if entry.index() >= down_to {
-                        break;
-                    }
-                    new_log.push(entry);
-                    new_content += &buffer;
-                    buffer.clear();
-                },
-                Err(error) => return Err(StorageError::ParsingLogEntry(line, error.to_string())),
-            }
-        }
-        drop(reader);
+        let Some((_, &offset)) = self.log_offsets.range(down_to..).next() else {
+            return Ok(());
+        };
 
-        let result = Storage::overwrite(&mut self.log_file, &new_content)
-            .map_err(|error| StorageError::TruncatingLogFile(error.to_string()));
+        self.log_file
+            .set_len(offset)
+            .and_then(|_| self.log_file.sync_data())
+            .map_err(|error| StorageError::TruncatingLogFile(error.to_string()))?;
 
-        if result.is_ok() {
-            self.log = new_log;
-        }
-        result
+        self.log.retain(|entry| entry.index() < down_to);
+        self.log_offsets.split_off(&down_to);
+        Ok(())
     }
 
     fn snapshot(&self) -> &Snapshot<KeyValueDatabase<Storage>> {
         &self.snapshot
     }
 
+    fn snapshot_metadata(&self) -> (LogIndex, Term) {
+        (self.snapshot.last_included_index(), self.snapshot.last_included_term())
+    }
+
     fn install_snapshot(
         &mut self,
         snapshot: Snapshot<KeyValueDatabase<Storage>>,
@@ -337,10 +533,9 @@ impl RaftStorage<KeyValueDatabase<Storage>> for Storage {
             return Ok(());
         }
 
-        let snapshot_string = serde_json::to_string_pretty(&snapshot)
-            .map_err(|error| StorageError::SerializingSnapshot(error.to_string()))?;
+        let snapshot_bytes = KeyValueDatabase::encode_snapshot(&snapshot);
 
-        let result = Storage::overwrite(&mut self.snapshot_file, &snapshot_string)
+        let result = Storage::overwrite(&mut self.snapshot_file, &snapshot_bytes)
             .map_err(|error| StorageError::WritingSnapshot(error.to_string()));
 
         if result.is_ok() {
@@ -348,6 +543,59 @@ impl RaftStorage<KeyValueDatabase<Storage>> for Storage {
         }
         result
     }
+
+    fn buffered_peer_transmits(&self) -> Vec<PeerTransmit<KeyValueDatabase<Storage>>> {
+        if !self.persist_transmits {
+            return Vec::new();
+        }
+        self.transmits.clone()
+    }
+
+    fn set_buffered_peer_transmits(
+        &mut self,
+        transmits: &VecDeque<PeerTransmit<KeyValueDatabase<Storage>>>,
+    ) -> Result<(), Self::Error> {
+        if !self.persist_transmits || self.transmits.iter().eq(transmits.iter()) {
+            return Ok(());
+        }
+
+        let transmits = transmits.iter().cloned().collect::<Vec<_>>();
+        if !self.readonly {
+            let transmits_string = serde_json::to_string(&transmits)
+                .map_err(|error| StorageError::SerializingTransmits(error.to_string()))?;
+            Storage::replace(&self.transmits_path(), transmits_string)
+                .map(drop)
+                .map_err(|error| StorageError::WritingTransmits(error.to_string()))?;
+        }
+        self.transmits = transmits;
+        Ok(())
+    }
+
+    fn apply_transaction(
+        &mut self,
+        transaction: StorageTransaction<KeyValueDatabase<Storage>>,
+    ) -> Result<(), Self::Error> {
+        if self.readonly {
+            if let Some(current_term) = transaction.current_term() {
+                self.state.current_term = current_term;
+            }
+            if let Some(voted_for) = transaction.voted_for() {
+                self.state.voted_for = voted_for;
+            }
+            if let Some(down_to) = transaction.log_truncation() {
+                self.log.retain(|entry| entry.index() < down_to);
+            }
+            self.log.extend(transaction.log_entries().iter().cloned());
+            return Ok(());
+        }
+
+        if transaction.is_empty() {
+            return Ok(());
+        }
+
+        let pending_transaction = self.begin_transaction(transaction)?;
+        self.complete_transaction(&pending_transaction)
+    }
 }
 
 /// Errors that can happen during persistent [Storage] updates.
@@ -411,6 +659,40 @@ pub enum StorageError {
     WritingSnapshot(#[error(not(source))] String),
     #[display("Unable to reset the persistent snapshot file: {_0}")]
     ResettingSnapshotFile(#[error(not(source))] String),
+    #[display("Unable to migrate the legacy JSON snapshot file: {_0}")]
+    MigratingSnapshot(Box<StorageError>),
+    #[display("Unable to remove the legacy JSON snapshot file: {_0}")]
+    RemovingLegacySnapshot(#[error(not(source))] String),
+
+    #[display("Unable to serialize the transaction: {_0}")]
+    SerializingTransaction(#[error(not(source))] String),
+    #[display("Unable to write the transaction to the transaction file persistently: {_0}")]
+    WritingTransaction(#[error(not(source))] String),
+    #[display("Unable to read the persistent transaction file: {_0}")]
+    ReadingTransaction(#[error(not(source))] String),
+    #[display("Unable to parse the persistent transaction file: {_0}")]
+    ParsingTransaction(#[error(not(source))] String),
+    #[display("Unable to remove the persistent transaction file: {_0}")]
+    RemovingTransaction(#[error(not(source))] String),
+
+    #[display("Unable to read the persistent transmits file: {_0}")]
+    ReadingTransmits(#[error(not(source))] String),
+    #[display("Unable to parse the persistent transmits file: {_0}")]
+    ParsingTransmits(#[error(not(source))] String),
+    #[display("Unable to serialize the buffered transmits: {_0}")]
+    SerializingTransmits(#[error(not(source))] String),
+    #[display("Unable to write the buffered transmits to the transmits file persistently: {_0}")]
+    WritingTransmits(#[error(not(source))] String),
+    #[display("Unable to remove the persistent transmits file: {_0}")]
+    RemovingTransmits(#[error(not(source))] String),
+}
+
+/// A transaction which is persisted, but might not be completed yet.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct PendingTransaction {
+    /// Length of the log file to keep before appending the entries of the transaction.
+    log_length: u64,
+    transaction: StorageTransaction<KeyValueDatabase<Storage>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
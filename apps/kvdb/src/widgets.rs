@@ -47,15 +47,21 @@ pub enum CommandSelectionWidget {
     EnteringKeyToInsert { key: String },
     EnteringKeyToUpsert { key: String },
     EnteringKeyToClear { key: String },
+    EnteringKeyToIncrement { key: String },
+    EnteringKeyToDecrement { key: String },
 
     EnteringValueToInsert { key: String, value: String },
     EnteringValueToUpsert { key: String, value: String },
 
+    EnteringAmountToIncrement { key: String, amount: String },
+    EnteringAmountToDecrement { key: String, amount: String },
+
     Finalized { command: Command },
 }
 
 impl CommandSelectionWidget {
-    const COMMANDS: &'static [&'static str] = &["Insert", "Upsert", "Clear"];
+    const COMMANDS: &'static [&'static str] =
+        &["Insert", "Upsert", "Clear", "Increment", "Decrement"];
 }
 
 impl Default for CommandSelectionWidget {
@@ -87,6 +93,16 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                                         key: "".to_string(),
                                     };
                                 },
+                                "Increment" => {
+                                    *self = CommandSelectionWidget::EnteringKeyToIncrement {
+                                        key: "".to_string(),
+                                    };
+                                },
+                                "Decrement" => {
+                                    *self = CommandSelectionWidget::EnteringKeyToDecrement {
+                                        key: "".to_string(),
+                                    };
+                                },
                                 _ => unreachable!(),
                             }
                         },
@@ -118,6 +134,16 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                             *self =
                                 CommandSelectionWidget::EnteringKeyToClear { key: "".to_string() };
                         },
+                        Key::Char('4') => {
+                            *self = CommandSelectionWidget::EnteringKeyToIncrement {
+                                key: "".to_string(),
+                            };
+                        },
+                        Key::Char('5') => {
+                            *self = CommandSelectionWidget::EnteringKeyToDecrement {
+                                key: "".to_string(),
+                            };
+                        },
 
                         _ => {},
                     }
@@ -126,8 +152,12 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                 CommandSelectionWidget::EnteringKeyToInsert { key: input }
                 | CommandSelectionWidget::EnteringKeyToUpsert { key: input }
                 | CommandSelectionWidget::EnteringKeyToClear { key: input }
+                | CommandSelectionWidget::EnteringKeyToIncrement { key: input }
+                | CommandSelectionWidget::EnteringKeyToDecrement { key: input }
                 | CommandSelectionWidget::EnteringValueToInsert { value: input, .. }
-                | CommandSelectionWidget::EnteringValueToUpsert { value: input, .. } => {
+                | CommandSelectionWidget::EnteringValueToUpsert { value: input, .. }
+                | CommandSelectionWidget::EnteringAmountToIncrement { amount: input, .. }
+                | CommandSelectionWidget::EnteringAmountToDecrement { amount: input, .. } => {
                     match event.code {
                         Key::Char(char) => {
                             input.push(char);
@@ -136,6 +166,17 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                             input.pop();
                         },
                         Key::Enter => {
+                            if let CommandSelectionWidget::EnteringAmountToIncrement {
+                                amount, ..
+                            }
+                            | CommandSelectionWidget::EnteringAmountToDecrement {
+                                amount, ..
+                            } = self
+                                && amount.parse::<i64>().is_err()
+                            {
+                                return;
+                            }
+
                             *self = match self {
                                 CommandSelectionWidget::EnteringKeyToInsert { key } => {
                                     CommandSelectionWidget::EnteringValueToInsert {
@@ -154,6 +195,18 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                                         command: Command::Clear { key: std::mem::take(key) },
                                     }
                                 },
+                                CommandSelectionWidget::EnteringKeyToIncrement { key } => {
+                                    CommandSelectionWidget::EnteringAmountToIncrement {
+                                        key: std::mem::take(key),
+                                        amount: String::new(),
+                                    }
+                                },
+                                CommandSelectionWidget::EnteringKeyToDecrement { key } => {
+                                    CommandSelectionWidget::EnteringAmountToDecrement {
+                                        key: std::mem::take(key),
+                                        amount: String::new(),
+                                    }
+                                },
 
                                 CommandSelectionWidget::EnteringValueToInsert { key, value } => {
                                     CommandSelectionWidget::Finalized {
@@ -172,6 +225,29 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                                     }
                                 },
 
+                                CommandSelectionWidget::EnteringAmountToIncrement {
+                                    key,
+                                    amount,
+                                } => {
+                                    CommandSelectionWidget::Finalized {
+                                        command: Command::Increment {
+                                            key: std::mem::take(key),
+                                            by: amount.parse().unwrap(),
+                                        },
+                                    }
+                                },
+                                CommandSelectionWidget::EnteringAmountToDecrement {
+                                    key,
+                                    amount,
+                                } => {
+                                    CommandSelectionWidget::Finalized {
+                                        command: Command::Decrement {
+                                            key: std::mem::take(key),
+                                            by: amount.parse().unwrap(),
+                                        },
+                                    }
+                                },
+
                                 _ => unreachable!(),
                             };
                         },
@@ -198,6 +274,12 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
             CommandSelectionWidget::EnteringKeyToClear { .. } => {
                 Some(CommandSelectionWidget::SelectingCommand { selection: 2 })
             },
+            CommandSelectionWidget::EnteringKeyToIncrement { .. } => {
+                Some(CommandSelectionWidget::SelectingCommand { selection: 3 })
+            },
+            CommandSelectionWidget::EnteringKeyToDecrement { .. } => {
+                Some(CommandSelectionWidget::SelectingCommand { selection: 4 })
+            },
 
             CommandSelectionWidget::EnteringValueToInsert { key, .. } => {
                 Some(CommandSelectionWidget::EnteringKeyToInsert { key: key.clone() })
@@ -206,6 +288,13 @@ impl CommandWidget<KeyValueDatabase<Storage>> for CommandSelectionWidget {
                 Some(CommandSelectionWidget::EnteringKeyToUpsert { key: key.clone() })
             },
 
+            CommandSelectionWidget::EnteringAmountToIncrement { key, .. } => {
+                Some(CommandSelectionWidget::EnteringKeyToIncrement { key: key.clone() })
+            },
+            CommandSelectionWidget::EnteringAmountToDecrement { key, .. } => {
+                Some(CommandSelectionWidget::EnteringKeyToDecrement { key: key.clone() })
+            },
+
             CommandSelectionWidget::Finalized { .. } => unreachable!(),
         }
     }
@@ -274,19 +363,30 @@ impl<'debugger> Widget for CommandSelectionWidgetRendered<'debugger> {
             CommandSelectionWidget::EnteringKeyToInsert { key: content }
             | CommandSelectionWidget::EnteringKeyToUpsert { key: content }
             | CommandSelectionWidget::EnteringKeyToClear { key: content }
+            | CommandSelectionWidget::EnteringKeyToIncrement { key: content }
+            | CommandSelectionWidget::EnteringKeyToDecrement { key: content }
             | CommandSelectionWidget::EnteringValueToInsert { value: content, .. }
-            | CommandSelectionWidget::EnteringValueToUpsert { value: content, .. } => {
+            | CommandSelectionWidget::EnteringValueToUpsert { value: content, .. }
+            | CommandSelectionWidget::EnteringAmountToIncrement { amount: content, .. }
+            | CommandSelectionWidget::EnteringAmountToDecrement { amount: content, .. } => {
                 Paragraph::new({
                     let spans = vec![
                         Span::styled(
                             match self.widget {
                                 CommandSelectionWidget::EnteringKeyToInsert { .. }
                                 | CommandSelectionWidget::EnteringKeyToUpsert { .. }
-                                | CommandSelectionWidget::EnteringKeyToClear { .. } => "Key: ",
+                                | CommandSelectionWidget::EnteringKeyToClear { .. }
+                                | CommandSelectionWidget::EnteringKeyToIncrement { .. }
+                                | CommandSelectionWidget::EnteringKeyToDecrement { .. } => "Key: ",
 
                                 CommandSelectionWidget::EnteringValueToInsert { .. }
                                 | CommandSelectionWidget::EnteringValueToUpsert { .. } => "Value: ",
 
+                                CommandSelectionWidget::EnteringAmountToIncrement { .. }
+                                | CommandSelectionWidget::EnteringAmountToDecrement { .. } => {
+                                    "Amount: "
+                                },
+
                                 _ => unreachable!(),
                             },
                             Style::default().magenta(),
@@ -312,6 +412,14 @@ impl<'debugger> Widget for CommandSelectionWidgetRendered<'debugger> {
                             CommandSelectionWidget::EnteringKeyToClear { .. } => {
                                 " Commanding Clear... "
                             },
+                            CommandSelectionWidget::EnteringKeyToIncrement { .. }
+                            | CommandSelectionWidget::EnteringAmountToIncrement { .. } => {
+                                " Commanding Increment... "
+                            },
+                            CommandSelectionWidget::EnteringKeyToDecrement { .. }
+                            | CommandSelectionWidget::EnteringAmountToDecrement { .. } => {
+                                " Commanding Decrement... "
+                            },
                             _ => unreachable!(),
                         })
                         .title_style(Style::default().fg(Color::Green))
@@ -335,7 +443,7 @@ pub enum QuerySelectionWidget {
 }
 
 impl QuerySelectionWidget {
-    const QUERIES: &'static [&'static str] = &["Length", "Entry"];
+    const QUERIES: &'static [&'static str] = &["Length", "Entry", "Dump"];
 }
 
 impl Default for QuerySelectionWidget {
@@ -361,6 +469,9 @@ impl QueryWidget<KeyValueDatabase<Storage>> for QuerySelectionWidget {
                                         key: "".to_string(),
                                     };
                                 },
+                                "Dump" => {
+                                    *self = QuerySelectionWidget::Finalized { query: Query::Dump };
+                                },
                                 _ => unreachable!(),
                             }
                         },
@@ -387,6 +498,9 @@ impl QueryWidget<KeyValueDatabase<Storage>> for QuerySelectionWidget {
                             *self =
                                 QuerySelectionWidget::EnteringKeyToEntry { key: "".to_string() };
                         },
+                        Key::Char('3') => {
+                            *self = QuerySelectionWidget::Finalized { query: Query::Dump };
+                        },
 
                         _ => {},
                     }
@@ -0,0 +1,78 @@
+//! Argument tests.
+
+use {
+    clap::Parser as Clap,
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+#[test]
+fn consistency_parses_each_mode() {
+    assert_eq!("strong".parse::<Consistency>(), Ok(Consistency::Strong));
+    assert_eq!("eventual".parse::<Consistency>(), Ok(Consistency::Eventual));
+    assert_eq!("lease".parse::<Consistency>(), Ok(Consistency::LeaseRead));
+    assert_eq!("read-index".parse::<Consistency>(), Ok(Consistency::ReadIndex));
+    assert_eq!("log-read".parse::<Consistency>(), Ok(Consistency::LogRead));
+    assert_eq!(
+        "linearizable".parse::<Consistency>(),
+        Err(ConsistencyParsingError("linearizable".to_owned())),
+    );
+
+    assert_eq!(Consistency::Strong.to_string(), "strong");
+    assert_eq!(Consistency::Eventual.to_string(), "eventual");
+    assert_eq!(Consistency::LeaseRead.to_string(), "lease");
+    assert_eq!(Consistency::ReadIndex.to_string(), "read-index");
+    assert_eq!(Consistency::LogRead.to_string(), "log-read");
+}
+
+#[test]
+fn consistency_argument_selects_the_mode() {
+    let args = Args::try_parse_from(["rafty-kvdb"]).unwrap();
+    assert_eq!(args.consistency(), Consistency::Strong);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--consistency", "strong"]).unwrap();
+    assert_eq!(args.consistency(), Consistency::Strong);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--consistency", "eventual"]).unwrap();
+    assert_eq!(args.consistency(), Consistency::Eventual);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--consistency", "read-index"]).unwrap();
+    assert_eq!(args.consistency(), Consistency::ReadIndex);
+
+    assert!(Args::try_parse_from(["rafty-kvdb", "--consistency", "linearizable"]).is_err());
+}
+
+#[test]
+fn eventual_flag_is_a_deprecated_alias() {
+    let args = Args::try_parse_from(["rafty-kvdb", "--eventual"]).unwrap();
+    assert_eq!(args.consistency(), Consistency::Eventual);
+
+    assert!(Args::try_parse_from(["rafty-kvdb", "--eventual", "--consistency", "strong"]).is_err());
+}
+
+#[test]
+fn log_file_argument_is_optional() {
+    let args = Args::try_parse_from(["rafty-kvdb"]).unwrap();
+    assert_eq!(args.log_file, None);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--log-file", "session.log"]).unwrap();
+    assert_eq!(args.log_file, Some("session.log".into()));
+}
+
+#[test]
+fn persisting_transmits_is_opt_in() {
+    let args = Args::try_parse_from(["rafty-kvdb"]).unwrap();
+    assert!(!args.persist_transmits);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--persist-transmits"]).unwrap();
+    assert!(args.persist_transmits);
+}
+
+#[test]
+fn verify_argument_is_a_flag() {
+    let args = Args::try_parse_from(["rafty-kvdb"]).unwrap();
+    assert!(!args.verify);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--verify"]).unwrap();
+    assert!(args.verify);
+}
@@ -0,0 +1,197 @@
+//! Client tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+mod storage;
+use storage::Storage;
+
+#[test]
+fn duplicate_command_reply_is_ignored() -> anyhow::Result<()> {
+    let cluster = Cluster::from([PeerId(1)].into_iter().collect::<std::collections::BTreeSet<_>>());
+    let mut client =
+        Client::<KeyValueDatabase<Storage>>::new(ClientId(1), cluster, Consistency::Strong);
+
+    let command = Command::Upsert { key: "x".to_owned(), value: "1".to_owned() };
+    let request_id = client.command(command, Some(PeerId(1)))?;
+    client.buffered_client_transmits_mut().clear();
+
+    let reply = CommandReply::builder().result(Ok(CommandResult::Done)).build();
+    client.receive_reply(PeerId(1), request_id, reply.clone().into());
+    assert_eq!(client.command_result(request_id), Some(&Ok(CommandResult::Done)));
+    let leader = client.leader();
+
+    let duplicate_reply = CommandReply::builder().result(Ok(CommandResult::AlreadyExists)).build();
+    client.receive_reply(PeerId(1), request_id, duplicate_reply.into());
+    client.receive_reply(PeerId(1), request_id, reply.into());
+
+    let redirect = CommandReply::builder()
+        .result(Err(ClientError::LeaderChanged { new_leader_id: PeerId(2) }))
+        .build();
+    client.receive_reply(PeerId(1), request_id, redirect.into());
+
+    assert_eq!(client.command_result(request_id), Some(&Ok(CommandResult::Done)));
+    assert!(client.outstanding_commands().is_empty());
+    assert!(client.buffered_client_transmits().is_empty());
+    assert_eq!(client.request_attempts(request_id), None);
+    assert_eq!(client.leader(), leader);
+
+    Ok(())
+}
+
+#[test]
+fn unreplied_command_is_outstanding_until_its_reply_is_delivered() -> anyhow::Result<()> {
+    let cluster = Cluster::from([PeerId(1)].into_iter().collect::<std::collections::BTreeSet<_>>());
+    let mut client =
+        Client::<KeyValueDatabase<Storage>>::new(ClientId(1), cluster, Consistency::Strong);
+
+    let command = Command::Upsert { key: "x".to_owned(), value: "1".to_owned() };
+    let request_id = client.command(command.clone(), Some(PeerId(1)))?;
+    assert_eq!(client.outstanding_commands().get(&request_id), Some(&command));
+    assert!(client.outstanding_queries().is_empty());
+
+    let query_request_id = client.query(Query::Length, Some(PeerId(1)))?;
+    assert_eq!(client.outstanding_queries().get(&query_request_id), Some(&Query::Length));
+
+    let reply = CommandReply::builder().result(Ok(CommandResult::Done)).build();
+    client.receive_reply(PeerId(1), request_id, reply.into());
+    assert!(client.outstanding_commands().is_empty());
+    assert_eq!(client.outstanding_queries().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn eventual_consistency_client_keeps_follower_answers_instead_of_redirecting() -> anyhow::Result<()>
+{
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+    let redirect = || {
+        QueryReply::<KeyValueDatabase<Storage>>::builder()
+            .result(Err(ClientError::LeaderChanged { new_leader_id: PeerId(1) }))
+            .build()
+    };
+
+    let mut client = Client::<KeyValueDatabase<Storage>>::new(
+        ClientId(1),
+        cluster.clone(),
+        Consistency::Eventual,
+    );
+    assert_eq!(client.consistency(), Consistency::Eventual);
+
+    // Answer of the follower is kept
+    let request_id = client.query(Query::Length, Some(PeerId(2)))?;
+    client.buffered_client_transmits_mut().clear();
+    let reply = QueryReply::builder().result(Ok(QueryResult::Length { length: 0 })).build();
+    client.receive_reply(PeerId(2), request_id, reply.into());
+    assert_eq!(client.query_result(request_id), Some(&Ok(QueryResult::Length { length: 0 })));
+    assert_eq!(client.leader(), None);
+
+    // Redirects are not followed
+    let request_id = client.query(Query::Length, Some(PeerId(2)))?;
+    client.buffered_client_transmits_mut().clear();
+    client.receive_reply(PeerId(2), request_id, redirect().into());
+    assert_eq!(client.leader(), None);
+    assert!(client.buffered_client_transmits().is_empty());
+    assert!(client.outstanding_queries().contains_key(&request_id));
+
+    // Redirects are followed with strong consistency
+    let mut client =
+        Client::<KeyValueDatabase<Storage>>::new(ClientId(1), cluster, Consistency::Strong);
+    let request_id = client.query(Query::Length, Some(PeerId(2)))?;
+    client.buffered_client_transmits_mut().clear();
+    client.receive_reply(PeerId(2), request_id, redirect().into());
+    assert_eq!(client.leader(), Some(PeerId(1)));
+    assert_eq!(client.buffered_client_transmits().len(), 1);
+    assert_eq!(client.buffered_client_transmits()[0].peer_id(), PeerId(1));
+
+    Ok(())
+}
+
+#[test]
+fn leader_unknown_reply_rediscovers_the_leader_and_retries() -> anyhow::Result<()> {
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+    let mut client =
+        Client::<KeyValueDatabase<Storage>>::new(ClientId(1), cluster, Consistency::Strong);
+
+    let command = Command::Upsert { key: "x".to_owned(), value: "1".to_owned() };
+    let request_id = client.command(command.clone(), Some(PeerId(2)))?;
+    client.buffered_client_transmits_mut().clear();
+
+    // Leader is discovered via all peers
+    let reply = CommandReply::<KeyValueDatabase<Storage>>::builder()
+        .result(Err(ClientError::LeaderUnknown))
+        .build();
+    client.receive_reply(PeerId(2), request_id, reply.into());
+    let discoveries = client.buffered_client_transmits_mut().drain(..).collect::<Vec<_>>();
+    assert_eq!(discoveries.len(), 3);
+    assert!(discoveries
+        .iter()
+        .all(|transmit| matches!(transmit.message(), ClientMessage::FindLeaderRequest(_))));
+    assert!(client.outstanding_commands().contains_key(&request_id));
+
+    // Command is sent again to the discovered leader
+    let discovery = discoveries.iter().find(|transmit| transmit.peer_id() == PeerId(1)).unwrap();
+    let reply = FindLeaderReply::builder().leader_id(PeerId(1)).build();
+    client.receive_reply(PeerId(1), discovery.request_id(), reply.into());
+    assert_eq!(client.leader(), Some(PeerId(1)));
+    assert_eq!(client.buffered_client_transmits().len(), 1);
+
+    let retry = &client.buffered_client_transmits()[0];
+    assert_eq!(retry.peer_id(), PeerId(1));
+    assert_eq!(retry.request_id(), request_id);
+    assert!(matches!(retry.message(), ClientMessage::CommandRequest(_)));
+
+    Ok(())
+}
+
+#[test]
+fn request_is_rerouted_after_repeatedly_timing_out_via_an_unresponsive_peer() -> anyhow::Result<()>
+{
+    let _ = env_logger::try_init();
+
+    let mut simulation = rafty_simulator::Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Eventual,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    simulation.perform(rafty_simulator::Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(2)),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    let request_id = RequestId(0);
+
+    // Requests to peer 2 are lost as it's unresponsive
+    let client = simulation.client_mut(ClientId(1)).unwrap();
+    for attempts in 1..Client::<KeyValueDatabase<Storage>>::MAX_ATTEMPTS_PER_PEER {
+        client.buffered_client_transmits_mut().clear();
+        client.trigger_request_timeout(request_id);
+        assert_eq!(client.request_attempts(request_id), Some((PeerId(2), attempts)));
+        assert_eq!(client.buffered_client_transmits().len(), 1);
+        assert_eq!(client.buffered_client_transmits()[0].peer_id(), PeerId(2));
+    }
+
+    // Request is rerouted to another peer after the last attempt times out
+    client.buffered_client_transmits_mut().clear();
+    client.trigger_request_timeout(request_id);
+    let (rerouted_peer_id, attempts) = client.request_attempts(request_id).unwrap();
+    assert_ne!(rerouted_peer_id, PeerId(2));
+    assert_eq!(attempts, 0);
+    assert_eq!(client.buffered_client_transmits().len(), 1);
+    assert_eq!(client.buffered_client_transmits()[0].peer_id(), rerouted_peer_id);
+
+    simulation.transmit_all_pending(true, 100)?;
+    let client = simulation.client(ClientId(1)).unwrap();
+    assert_eq!(client.query_result(request_id), Some(&Ok(QueryResult::Length { length: 0 })));
+    assert_eq!(client.request_attempts(request_id), None);
+
+    // Timeouts of replied requests are ignored
+    let client = simulation.client_mut(ClientId(1)).unwrap();
+    client.trigger_request_timeout(request_id);
+    assert!(client.buffered_client_transmits().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Command tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+#[test]
+fn writes_to_different_keys_do_not_conflict() {
+    let insert_a = Command::Insert { key: "a".to_owned(), value: "1".to_owned() };
+    let clear_b = Command::Clear { key: "b".to_owned() };
+    let increment_c = Command::Increment { key: "c".to_owned(), by: 1 };
+
+    assert!(!insert_a.conflicts_with(&clear_b));
+    assert!(!clear_b.conflicts_with(&increment_c));
+    assert!(Application::commands_commute(&insert_a, &increment_c));
+}
+
+#[test]
+fn writes_to_the_same_key_conflict() {
+    let insert = Command::Insert { key: "a".to_owned(), value: "1".to_owned() };
+    let upsert = Command::Upsert { key: "a".to_owned(), value: "2".to_owned() };
+    let clear = Command::Clear { key: "a".to_owned() };
+    let decrement = Command::Decrement { key: "a".to_owned(), by: 1 };
+
+    assert!(insert.conflicts_with(&upsert));
+    assert!(upsert.conflicts_with(&clear));
+    assert!(clear.conflicts_with(&decrement));
+    assert!(insert.conflicts_with(&insert));
+    assert!(!Application::commands_commute(&insert, &decrement));
+}
+
+#[test]
+fn no_op_does_not_conflict_with_anything() {
+    let insert = Command::Insert { key: "a".to_owned(), value: "1".to_owned() };
+
+    assert!(!Command::NoOp.conflicts_with(&insert));
+    assert!(!insert.conflicts_with(&Command::NoOp));
+    assert!(Application::commands_commute(&Command::NoOp, &Command::NoOp));
+}
+
+#[test]
+fn command_results_are_summarized() {
+    assert_eq!(CommandResult::Done.summary(), "Done");
+    assert_eq!(CommandResult::AlreadyExists.summary(), "Not inserted (key already exists)");
+    assert_eq!(CommandResult::Value { value: -3 }.summary(), "Value is now -3");
+    assert_eq!(CommandResult::NotNumeric.summary(), "Not changed (value is not an integer)");
+    assert_eq!(CommandResult::Overflow.summary(), "Not changed (value would overflow)");
+}
@@ -0,0 +1,458 @@
+//! Debugger tests.
+
+use {
+    crossterm::event::{
+        Event,
+        KeyCode,
+        KeyEvent,
+    },
+    rafty::prelude::*,
+    rafty_debugger::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+    ratatui::{
+        prelude::*,
+        widgets::Clear,
+    },
+    std::collections::BTreeMap,
+};
+
+mod storage;
+use storage::Storage;
+
+#[derive(Default)]
+struct NoCommandWidget;
+
+impl CommandWidget<KeyValueDatabase<Storage>> for NoCommandWidget {
+    fn on_user_event(&mut self, _event: Event) {}
+
+    fn back(&self) -> Option<Self> {
+        None
+    }
+
+    fn renderer(&self) -> impl Widget {
+        Clear
+    }
+
+    fn finalize(&mut self) -> Option<Command> {
+        None
+    }
+}
+
+#[derive(Default)]
+struct NoQueryWidget;
+
+impl QueryWidget<KeyValueDatabase<Storage>> for NoQueryWidget {
+    fn on_user_event(&mut self, _event: Event) {}
+
+    fn back(&self) -> Option<Self> {
+        None
+    }
+
+    fn renderer(&self) -> impl Widget {
+        Clear
+    }
+
+    fn finalize(&mut self) -> Option<Query> {
+        None
+    }
+}
+
+type KeyValueDatabaseDebugger = Debugger<KeyValueDatabase<Storage>, NoCommandWidget, NoQueryWidget>;
+
+fn simulation(number_of_peers: usize) -> anyhow::Result<Simulation<KeyValueDatabase<Storage>>> {
+    Simulation::new(Consistency::Strong, vec![Storage::default(); number_of_peers], 2)
+}
+
+#[test]
+fn builder_selects_initial_peer() -> anyhow::Result<()> {
+    let debugger = KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(5)?)
+        .initial_peer_id(PeerId(3))
+        .logs_percentage(30)
+        .info_percentage(60)
+        .log_file("session.log")
+        .build()?;
+    assert_eq!(debugger.selected_peer_id(), PeerId(3));
+    assert_eq!(debugger.logs_percentage(), 30);
+    assert_eq!(debugger.info_percentage(), 60);
+    assert_eq!(debugger.log_file(), Some(std::path::Path::new("session.log")));
+
+    let debugger = KeyValueDatabaseDebugger::new(simulation(5)?)?;
+    assert_eq!(debugger.selected_peer_id(), PeerId(1));
+    assert_eq!(debugger.log_file(), None);
+
+    Ok(())
+}
+
+#[test]
+fn builder_rejects_invalid_configuration() -> anyhow::Result<()> {
+    assert!(KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(5)?)
+        .initial_peer_id(PeerId(6))
+        .build()
+        .is_err());
+    assert!(KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(5)?)
+        .logs_percentage(100)
+        .build()
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn watched_query_is_rendered_for_selected_peer() -> anyhow::Result<()> {
+    let mut simulation = simulation(3)?;
+    simulation
+        .peer_mut(PeerId(2))
+        .unwrap()
+        .set_machine(Machine(BTreeMap::from([("x".to_owned(), "42".to_owned())])));
+
+    let watched_query = Query::Entry { key: "x".to_owned() };
+    let mut debugger = KeyValueDatabaseDebugger::builder()
+        .simulation(simulation)
+        .initial_peer_id(PeerId(2))
+        .watched_query(watched_query.clone())
+        .build()?;
+    assert_eq!(debugger.watched_query(), Some(&watched_query));
+
+    let area = Rect::new(0, 0, 200, 60);
+    let mut buffer = Buffer::empty(area);
+    (&mut debugger).render(area, &mut buffer);
+
+    let content = buffer.content().iter().map(|cell| cell.symbol()).collect::<String>();
+    assert!(content.contains("Watch (Peer 2)"));
+    assert!(content.contains(r#"Entry { value: Some("42") }"#));
+
+    Ok(())
+}
+
+#[test]
+fn application_name_is_rendered_in_the_title() -> anyhow::Result<()> {
+    let mut debugger = KeyValueDatabaseDebugger::new(simulation(3)?)?;
+
+    let area = Rect::new(0, 0, 200, 60);
+    let mut buffer = Buffer::empty(area);
+    (&mut debugger).render(area, &mut buffer);
+
+    let title = (0..area.width).map(|x| buffer[(x, 0)].symbol()).collect::<String>();
+    assert!(title.contains(" KeyValueDatabase "));
+
+    Ok(())
+}
+
+#[test]
+fn semi_automatic_mode_is_indicated() -> anyhow::Result<()> {
+    let render = |debugger: &mut KeyValueDatabaseDebugger| {
+        let area = Rect::new(0, 0, 200, 60);
+        let mut buffer = Buffer::empty(area);
+        debugger.render(area, &mut buffer);
+        buffer.content().iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+
+    let mut debugger = KeyValueDatabaseDebugger::new(simulation(3)?)?;
+    assert!(!debugger.is_semi_automatic());
+    assert!(!render(&mut debugger).contains(" Auto Replies "));
+
+    let mut debugger = KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(3)?)
+        .semi_automatic(true)
+        .build()?;
+    assert!(debugger.is_semi_automatic());
+    assert!(render(&mut debugger).contains(" Auto Replies "));
+
+    Ok(())
+}
+
+#[test]
+fn simulation_loaded_from_data_directories_is_rendered() -> anyhow::Result<()> {
+    let data_directory = std::env::temp_dir()
+        .join(format!("rafty-kvdb-debugger-test-{}-from-snapshot-files", std::process::id()));
+    for peer_id in 1..=3 {
+        let peer_data_directory = data_directory.join(peer_id.to_string());
+        std::fs::create_dir_all(&peer_data_directory)?;
+
+        let log = (1..=peer_id)
+            .map(|index| {
+                LogEntry::<KeyValueDatabase<Storage>>::builder()
+                    .index(index)
+                    .term(2)
+                    .command(Command::Insert {
+                        key: format!("key-{index}"),
+                        value: format!("value-{index}"),
+                    })
+                    .build()
+            })
+            .collect::<Vec<_>>();
+        std::fs::write(peer_data_directory.join("log.json"), serde_json::to_string(&log)?)?;
+    }
+
+    let simulation = Simulation::from_snapshot_files(
+        Consistency::Strong,
+        (1..=3).map(|peer_id| data_directory.join(peer_id.to_string())),
+        2,
+        |peer_data_directory| {
+            let log = std::fs::read_to_string(peer_data_directory.join("log.json"))?;
+            Ok(Storage {
+                current_term: Term(2),
+                log: Log::from(serde_json::from_str::<Vec<_>>(&log)?),
+                ..Storage::default()
+            })
+        },
+    )?;
+    std::fs::remove_dir_all(&data_directory)?;
+
+    assert_eq!(simulation.number_of_peers(), 3);
+    for peer_id in 1..=3 {
+        let peer = simulation.peer(PeerId(peer_id)).unwrap();
+        assert_eq!(peer.current_term(), Term(2));
+        assert_eq!(peer.log().len(), peer_id);
+        assert!(peer.role().is_follower());
+    }
+
+    let mut debugger = KeyValueDatabaseDebugger::builder()
+        .simulation(simulation)
+        .initial_peer_id(PeerId(3))
+        .build()?;
+
+    let area = Rect::new(0, 0, 200, 60);
+    let mut buffer = Buffer::empty(area);
+    (&mut debugger).render(area, &mut buffer);
+
+    let content = buffer.content().iter().map(|cell| cell.symbol()).collect::<String>();
+    assert!(content.contains(r#"Insert { key: "key-1", value: "value-1" }"#));
+    assert!(content.contains(r#"Insert { key: "key-3", value: "value-3" }"#));
+
+    Ok(())
+}
+
+#[test]
+fn every_message_variant_can_be_inspected_as_json() -> anyhow::Result<()> {
+    type Application = KeyValueDatabase<Storage>;
+
+    let peer_messages: Vec<PeerMessage<Application>> = vec![
+        RequestVoteRequest::builder()
+            .term(2)
+            .candidate_id(PeerId(1))
+            .last_log_index(1)
+            .last_log_term(1)
+            .build()
+            .into(),
+        RequestVoteReply::builder().term(2).vote(Vote::Granted).build().into(),
+        AppendEntriesRequest::builder()
+            .term(2)
+            .leader_id(PeerId(1))
+            .prev_log_index(0)
+            .prev_log_term(0)
+            .entries([LogEntry::builder()
+                .index(1)
+                .term(2)
+                .command(Command::Insert { key: "x".to_owned(), value: "1".to_owned() })
+                .build()])
+            .leader_commit(0)
+            .build()
+            .into(),
+        AppendEntriesReply::builder().term(2).success(true).build().into(),
+    ];
+    for message in peer_messages {
+        let json = serde_json::to_string_pretty(&message)?;
+        assert_eq!(serde_json::from_str::<PeerMessage<Application>>(&json)?, message);
+    }
+
+    let client_messages: Vec<ClientMessage<Application>> = vec![
+        CommandRequest::builder()
+            .client_id(ClientId(1))
+            .command(Command::Clear { key: "x".to_owned() })
+            .build()
+            .into(),
+        CommandReply::builder()
+            .result(Err(ClientError::LeaderChanged { new_leader_id: PeerId(2) }))
+            .build()
+            .into(),
+        QueryRequest::builder().client_id(ClientId(1)).query(Query::Length).build().into(),
+        QueryReply::builder().result(Ok(QueryResult::Length { length: 1 })).build().into(),
+        FindLeaderRequest::builder().client_id(ClientId(1)).build().into(),
+        FindLeaderReply::builder().leader_id(PeerId(2)).build().into(),
+    ];
+    for message in client_messages {
+        let json = serde_json::to_string_pretty(&message)?;
+        assert_eq!(serde_json::from_str::<ClientMessage<Application>>(&json)?, message);
+    }
+
+    Ok(())
+}
+
+fn press(key: KeyCode) -> Event {
+    Event::Key(KeyEvent::from(key))
+}
+
+fn type_text(text: &str) -> impl Iterator<Item = Event> {
+    text.chars().map(|char| press(KeyCode::Char(char))).chain([press(KeyCode::Enter)])
+}
+
+#[test]
+fn generic_command_widget_builds_commands_from_schemas() {
+    let mut widget = GenericCommandWidget::<KeyValueDatabase<Storage>>::default();
+
+    widget.on_user_event(press(KeyCode::Char('4')));
+    for event in type_text("x").chain(type_text("nan")) {
+        widget.on_user_event(event);
+    }
+    assert_eq!(widget.finalize(), None);
+
+    widget.on_user_event(press(KeyCode::Backspace));
+    widget.on_user_event(press(KeyCode::Backspace));
+    widget.on_user_event(press(KeyCode::Backspace));
+    for event in type_text("5") {
+        widget.on_user_event(event);
+    }
+    assert_eq!(widget.finalize(), Some(Command::Increment { key: "x".to_owned(), by: 5 }));
+
+    let mut widget = GenericCommandWidget::<KeyValueDatabase<Storage>>::default();
+    widget.on_user_event(press(KeyCode::Down));
+    widget.on_user_event(press(KeyCode::Enter));
+    for event in type_text("x") {
+        widget.on_user_event(event);
+    }
+
+    let mut widget = widget.back().unwrap().back().unwrap();
+    assert!(widget.back().is_none());
+    widget.on_user_event(press(KeyCode::Down));
+    widget.on_user_event(press(KeyCode::Enter));
+    for event in type_text("y") {
+        widget.on_user_event(event);
+    }
+    assert_eq!(widget.finalize(), Some(Command::Clear { key: "y".to_owned() }));
+}
+
+#[test]
+fn generic_query_widget_builds_queries_from_schemas() {
+    let mut widget = GenericQueryWidget::<KeyValueDatabase<Storage>>::default();
+    widget.on_user_event(press(KeyCode::Char('3')));
+    assert_eq!(widget.finalize(), Some(Query::Dump));
+
+    let mut widget = GenericQueryWidget::<KeyValueDatabase<Storage>>::default();
+    widget.on_user_event(press(KeyCode::Char('2')));
+    for event in type_text("x") {
+        widget.on_user_event(event);
+    }
+    assert_eq!(widget.finalize(), Some(Query::Entry { key: "x".to_owned() }));
+
+    let area = Rect::new(0, 0, 40, 5);
+    let mut buffer = Buffer::empty(area);
+    GenericQueryWidget::<KeyValueDatabase<Storage>>::default().renderer().render(area, &mut buffer);
+
+    let content = buffer.content().iter().map(|cell| cell.symbol()).collect::<String>();
+    for schema in Query::schemas() {
+        assert!(content.contains(schema.name()));
+    }
+}
+
+#[test]
+fn machine_of_a_peer_can_be_exported_as_json() -> anyhow::Result<()> {
+    let mut simulation = simulation(3)?;
+    simulation.peer_mut(PeerId(2)).unwrap().set_machine(Machine(BTreeMap::from([
+        ("x".to_owned(), "1".to_owned()),
+        ("y".to_owned(), "2".to_owned()),
+    ])));
+    let debugger = KeyValueDatabaseDebugger::new(simulation)?;
+
+    let directory = std::env::temp_dir()
+        .join(format!("rafty-kvdb-debugger-test-{}-export-machine", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+
+    let path = debugger.export_machine(PeerId(2), &directory)?;
+    assert_eq!(path, directory.join("machine-2.json"));
+
+    let exported = serde_json::from_str::<Machine>(&std::fs::read_to_string(&path)?)?;
+    assert_eq!(&exported, debugger.simulation().peer(PeerId(2)).unwrap().machine());
+
+    assert!(debugger.export_machine(PeerId(4), &directory).is_err());
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn playback_performs_recorded_actions_then_pending_transmits() -> anyhow::Result<()> {
+    let render = |debugger: &mut KeyValueDatabaseDebugger| {
+        let area = Rect::new(0, 0, 200, 60);
+        let mut buffer = Buffer::empty(area);
+        debugger.render(area, &mut buffer);
+        buffer.content().iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+
+    let mut debugger = KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(3)?)
+        .playback(vec![Action::TimeoutElection { peer_id: PeerId(1) }])
+        .playback_interval(std::time::Duration::from_millis(500))
+        .build()?;
+    assert!(!debugger.is_playing());
+    assert!(!render(&mut debugger).contains(" Playing Every "));
+
+    debugger.toggle_playback();
+    assert!(debugger.is_playing());
+    assert!(render(&mut debugger).contains(" Playing Every 500ms "));
+
+    // Recorded action is performed first
+    assert!(debugger.auto_step()?);
+    assert!(debugger.remaining_playback().is_empty());
+    assert!(debugger.simulation().peer(PeerId(1)).unwrap().role().is_candidate());
+
+    // Pending transmits are performed until the cluster settles
+    let mut steps = 0;
+    while debugger.auto_step()? {
+        steps += 1;
+        assert!(steps < 100);
+    }
+    assert!(debugger.simulation().peer(PeerId(1)).unwrap().role().is_leader());
+
+    // Speed is bounded
+    for _ in 0..10 {
+        debugger.speed_up_playback();
+    }
+    assert_eq!(debugger.playback_interval(), KeyValueDatabaseDebugger::MIN_PLAYBACK_INTERVAL);
+    for _ in 0..10 {
+        debugger.slow_down_playback();
+    }
+    assert_eq!(debugger.playback_interval(), KeyValueDatabaseDebugger::MAX_PLAYBACK_INTERVAL);
+
+    debugger.toggle_playback();
+    assert!(!debugger.is_playing());
+
+    assert!(KeyValueDatabaseDebugger::builder()
+        .simulation(simulation(3)?)
+        .playback_interval(std::time::Duration::ZERO)
+        .build()
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn help_overlay_lists_the_keybindings() -> anyhow::Result<()> {
+    let render = |debugger: &mut KeyValueDatabaseDebugger| {
+        let area = Rect::new(0, 0, 200, 60);
+        let mut buffer = Buffer::empty(area);
+        debugger.render(area, &mut buffer);
+        buffer.content().iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+
+    let mut debugger = KeyValueDatabaseDebugger::new(simulation(3)?)?;
+    assert!(!debugger.is_showing_help());
+    assert!(!render(&mut debugger).contains(" Help "));
+
+    debugger.toggle_help();
+    assert!(debugger.is_showing_help());
+    let content = render(&mut debugger);
+    assert!(content.contains(" Help "));
+    assert!(content.contains("Toggle this help"));
+    assert!(content.contains("Export the machine of the selected peer"));
+    assert!(content.contains("Toggle showing the selected message as JSON"));
+
+    debugger.toggle_help();
+    assert!(!debugger.is_showing_help());
+    assert!(!render(&mut debugger).contains(" Help "));
+
+    Ok(())
+}
@@ -11,7 +11,7 @@ use storage::Storage;
 
 #[test]
 fn single_candidate_election() -> anyhow::Result<()> {
-    env_logger::init();
+    let _ = env_logger::try_init();
 
     let consistency = Consistency::Strong;
 
@@ -75,8 +75,11 @@ fn single_candidate_election() -> anyhow::Result<()> {
                     .set_voted_for(Some(PeerId(2)))
                     .set_role(Role::Candidate(
                         CandidateState::builder()
+                            .term(1)
                             .votes_granted(1)
-                            .vote_request_ids([0, 1, 2, 3].into_iter().map(RequestId))
+                            .vote_requests([(0, 1), (1, 3), (2, 4), (3, 5)].map(|(request_id, peer_id)| {
+                                (RequestId(request_id), PeerId(peer_id))
+                            }))
                             .build(),
                     ))
                     .set_buffered_peer_transmits(
@@ -143,8 +146,11 @@ fn single_candidate_election() -> anyhow::Result<()> {
                             .set_role(
                                 Role::Candidate(
                                     CandidateState::builder()
+                                        .term(1)
                                         .votes_granted(2)
-                                        .vote_request_ids([1, 2, 3].into_iter().map(RequestId))
+                                        .vote_requests([(1, 3), (2, 4), (3, 5)].map(|(request_id, peer_id)| {
+                                            (RequestId(request_id), PeerId(peer_id))
+                                        }))
                                         .build()
                                 )
                             )
@@ -373,6 +379,7 @@ fn single_candidate_election() -> anyhow::Result<()> {
                                                     }
                                                 })
                                         )
+                                        .initial_noop_committed(true)
                                         .build()
                                 )
                             )
@@ -434,6 +441,7 @@ fn single_candidate_election() -> anyhow::Result<()> {
                                                     }
                                                 })
                                         )
+                                        .initial_noop_committed(true)
                                         .build()
                                 )
                             )
@@ -473,6 +481,7 @@ fn single_candidate_election() -> anyhow::Result<()> {
                                                 (PeerId(5), LogIndex(1)),
                                             ]
                                         )
+                                        .initial_noop_committed(true)
                                         .build()
                                 )
                             )
@@ -518,6 +527,7 @@ fn single_candidate_election() -> anyhow::Result<()> {
                                                     )
                                                 })
                                         )
+                                        .initial_noop_committed(true)
                                         .build()
                                 )
                             )
@@ -577,3 +587,646 @@ fn single_candidate_election() -> anyhow::Result<()> {
         .into_iter(),
     )
 }
+
+#[test]
+fn candidate_steps_down_on_higher_term_vote_in_current_term() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    assert!(matches!(simulation.peer(PeerId(1)).unwrap().role(), Role::Candidate(_)));
+
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+
+    // Peer 2 claims to be in a higher term while replying with the term of the candidate.
+    simulation.peer_mut(PeerId(2)).unwrap().set_buffered_peer_transmits(
+        [PeerTransmit::builder()
+            .peer_id(1)
+            .request_id(0)
+            .message(
+                RequestVoteReply::builder()
+                    .term(1)
+                    .vote(Vote::NotGrantedDueToBeingInHigherTerm)
+                    .build(),
+            )
+            .build()]
+        .into(),
+    );
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+
+    let candidate = simulation.peer(PeerId(1)).unwrap();
+    assert_eq!(candidate.current_term(), Term(1));
+    assert!(matches!(candidate.role(), Role::Follower(_)));
+    assert!(candidate
+        .buffered_peer_transmits()
+        .iter()
+        .all(|transmit| { !matches!(transmit.message(), PeerMessage::RequestVoteRequest(..)) }));
+
+    Ok(())
+}
+
+#[test]
+fn candidate_steps_down_on_higher_term_vote_request() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_candidate());
+
+    // Peer 2 starts the election of a higher term
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+    let request_id = simulation
+        .peer(PeerId(2))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .filter(|transmit| transmit.peer_id() == PeerId(1))
+        .map(|transmit| transmit.request_id())
+        .max()
+        .unwrap();
+    simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(2), request_id })?;
+
+    let candidate = simulation.peer(PeerId(1)).unwrap();
+    assert_eq!(candidate.current_term(), Term(2));
+    assert_eq!(candidate.voted_for(), Some(PeerId(2)));
+    assert!(candidate.role().is_follower());
+    assert!(candidate
+        .buffered_peer_transmits()
+        .iter()
+        .all(|transmit| !matches!(transmit.message(), PeerMessage::RequestVoteRequest(..))));
+
+    Ok(())
+}
+
+#[test]
+fn leader_confirms_leadership_once_initial_noop_is_committed() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    let initial_noop_committed = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        match simulation.peer(PeerId(1)).unwrap().role() {
+            Role::Leader(leader_state) => leader_state.initial_noop_committed(),
+            role => panic!("Peer 1 is not the leader ({:?})", role),
+        }
+    };
+
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation.perform(Action::TransmitPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![RequestId(0), RequestId(1)],
+    })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    assert!(!initial_noop_committed(&simulation));
+    assert!(!simulation.peer(PeerId(1)).unwrap().can_serve_linearizable_read());
+
+    // Request #2 is the `AppendEntries` request with the no-op entry to Peer 2.
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(2) })?;
+    assert!(!initial_noop_committed(&simulation));
+
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(2)),
+    })?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(1));
+    assert!(initial_noop_committed(&simulation));
+    assert!(simulation.peer(PeerId(1)).unwrap().can_serve_linearizable_read());
+
+    Ok(())
+}
+
+#[test]
+fn candidate_completes_election_after_retransmitting_dropped_vote_requests() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    // Vote requests of Peer 1 are lost
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation.perform(Action::DropPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![RequestId(0), RequestId(1)],
+    })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits().is_empty());
+
+    // Retransmitting doesn't duplicate vote requests which are still buffered
+    simulation.perform(Action::RetransmitVoteRequests { peer_id: PeerId(1) })?;
+    simulation.perform(Action::RetransmitVoteRequests { peer_id: PeerId(1) })?;
+    let retransmits = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.request_id(), transmit.peer_id()))
+        .collect::<Vec<_>>();
+    assert_eq!(retransmits, vec![(RequestId(0), PeerId(2)), (RequestId(1), PeerId(3))]);
+
+    // Peer 2 grants its vote upon the retransmitted request
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().current_term(), Term(1));
+
+    Ok(())
+}
+
+#[test]
+fn candidate_does_not_retransmit_vote_requests_once_its_term_moves_on() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation.perform(Action::DropPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![RequestId(0), RequestId(1)],
+    })?;
+
+    simulation.peer_mut(PeerId(1)).unwrap().set_current_term(Term(2))?;
+    simulation.perform(Action::RetransmitVoteRequests { peer_id: PeerId(1) })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn retransmitted_vote_requests_do_not_elect_a_second_leader() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+
+    let vote_request_to =
+        |simulation: &Simulation<KeyValueDatabase<Storage>>, candidate_id, voter_id| {
+            simulation
+                .peer(candidate_id)
+                .unwrap()
+                .buffered_peer_transmits()
+                .iter()
+                .filter(|transmit| {
+                    transmit.peer_id() == voter_id
+                        && matches!(transmit.message(), PeerMessage::RequestVoteRequest(_))
+                })
+                .map(|transmit| transmit.request_id())
+                .max()
+                .unwrap()
+        };
+    let collect_vote = |simulation: &mut Simulation<KeyValueDatabase<Storage>>,
+                        candidate_id,
+                        voter_id|
+     -> anyhow::Result<()> {
+        let request_id = vote_request_to(simulation, candidate_id, voter_id);
+        simulation.perform(Action::TransmitPeerRequest { peer_id: candidate_id, request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: voter_id,
+            replied_peer_id_and_request_id: (candidate_id, request_id),
+        })?;
+        Ok(())
+    };
+
+    // Peer 1 gets the vote of Peer 2 in term 1, and its other vote requests are lost
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    collect_vote(&mut simulation, PeerId(1), PeerId(2))?;
+    simulation.perform(Action::DropPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: [PeerId(3), PeerId(4), PeerId(5)]
+            .into_iter()
+            .map(|voter_id| vote_request_to(&simulation, PeerId(1), voter_id))
+            .collect(),
+    })?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().current_term(), Term(1));
+
+    // Peer 3 gets elected in term 2 with the votes of Peer 1 and Peer 5
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(3) })?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(3) })?;
+    collect_vote(&mut simulation, PeerId(3), PeerId(1))?;
+    collect_vote(&mut simulation, PeerId(3), PeerId(5))?;
+    assert!(simulation.peer(PeerId(3)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().current_term(), Term(2));
+
+    // Peer 1 retransmitting the vote requests of its election in term 1 doesn't get it elected
+    simulation.perform(Action::RetransmitVoteRequests { peer_id: PeerId(1) })?;
+    let retransmitted_request_ids = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .filter(|transmit| transmit.peer_id() == PeerId(4))
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+    for request_id in retransmitted_request_ids {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: PeerId(4),
+            replied_peer_id_and_request_id: (PeerId(1), request_id),
+        })?;
+    }
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_follower());
+    assert!(simulation.peer(PeerId(3)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer_role_counts().2, 1);
+
+    Ok(())
+}
+
+#[test]
+fn role_history_records_transitions_of_elections() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role_history().is_empty());
+
+    // Peer 1 gets elected in term 1
+    simulation.elect(PeerId(1))?;
+
+    // Peer 2 starts an election for term 2 without reaching the others
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+
+    // Peer 1 learns about term 2 from the reply to its heartbeat and steps down
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    let request_id = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .find(|transmit| transmit.peer_id() == PeerId(2))
+        .map(|transmit| transmit.request_id())
+        .unwrap();
+    simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), request_id),
+    })?;
+
+    let transition =
+        |term, from, to| RoleTransition::builder().term(term).from(from).to(to).build();
+    assert_eq!(
+        simulation.peer(PeerId(1)).unwrap().role_history().iter().copied().collect::<Vec<_>>(),
+        vec![
+            transition(1, RoleKind::Follower, RoleKind::Candidate),
+            transition(1, RoleKind::Candidate, RoleKind::Leader),
+            transition(2, RoleKind::Leader, RoleKind::Follower),
+        ],
+    );
+    assert_eq!(
+        simulation.peer(PeerId(2)).unwrap().role_history().iter().copied().collect::<Vec<_>>(),
+        vec![transition(2, RoleKind::Follower, RoleKind::Candidate)],
+    );
+    assert!(simulation.peer(PeerId(3)).unwrap().role_history().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn role_history_records_overwritten_roles() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    simulation.peer_mut(PeerId(1)).unwrap().set_current_term(Term(3))?;
+    simulation.peer_mut(PeerId(1)).unwrap().set_role(Role::Candidate(
+        CandidateState::builder().term(3).votes_granted(1).vote_requests([]).build(),
+    ));
+
+    assert_eq!(
+        simulation.peer(PeerId(1)).unwrap().role_history().iter().copied().collect::<Vec<_>>(),
+        vec![RoleTransition::builder()
+            .term(3)
+            .from(RoleKind::Follower)
+            .to(RoleKind::Candidate)
+            .build(),],
+    );
+
+    Ok(())
+}
+
+#[test]
+fn candidate_requests_vote_again_after_persistence_failure() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let failing_storage = Storage { failing_writes: 1, ..Storage::default() };
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(), failing_storage, Storage::default()],
+        1,
+    )?;
+
+    // Peer 2 fails to persist the vote
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+    let reply =
+        simulation.peer(PeerId(2)).unwrap().buffered_peer_transmits().front().unwrap().clone();
+    assert_eq!(
+        reply.message(),
+        &PeerMessage::from(
+            RequestVoteReply::builder().term(0).vote(Vote::NotGrantedDueToStorageError).build(),
+        ),
+    );
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().current_term(), Term(0));
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().voted_for(), None);
+
+    // Peer 1 requests the vote again with the same request id
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_candidate());
+    assert!(simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits().iter().any(|transmit| {
+        transmit.peer_id() == PeerId(2) && transmit.request_id() == RequestId(0)
+    }));
+
+    // Peer 2 grants its vote as the storage recovered
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().voted_for(), Some(PeerId(1)));
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+
+    Ok(())
+}
+
+#[test]
+fn follower_denies_vote_while_it_recently_heard_from_the_leader() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    for peer_id in (1..=3).map(PeerId) {
+        simulation.peer_mut(peer_id).unwrap().set_leader_stickiness(true);
+    }
+    simulation.elect(PeerId(1))?;
+
+    let request_vote_from_peer_3 =
+        |simulation: &mut Simulation<KeyValueDatabase<Storage>>| -> anyhow::Result<PeerMessage<KeyValueDatabase<Storage>>> {
+            simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+            let request_id = simulation
+                .peer(PeerId(2)).unwrap()
+                .buffered_peer_transmits()
+                .iter()
+                .rev()
+                .find(|transmit| {
+                    transmit.peer_id() == PeerId(3)
+                        && matches!(transmit.message(), PeerMessage::RequestVoteRequest(_))
+                })
+                .map(|transmit| transmit.request_id())
+                .unwrap();
+            simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(2), request_id })?;
+
+            let reply = simulation
+                .peer(PeerId(3)).unwrap()
+                .buffered_peer_transmits()
+                .iter()
+                .find(|transmit| transmit.request_id() == request_id)
+                .unwrap();
+            Ok(reply.message().clone())
+        };
+
+    assert_eq!(
+        request_vote_from_peer_3(&mut simulation)?,
+        RequestVoteReply::builder()
+            .term(1)
+            .vote(Vote::NotGrantedDueToActiveLeaderLease)
+            .build()
+            .into(),
+    );
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().current_term(), Term(1));
+
+    simulation.perform(Action::AdvanceTime {
+        ticks: Peer::<KeyValueDatabase<Storage>>::LEASE_DURATION,
+    })?;
+    assert_eq!(
+        request_vote_from_peer_3(&mut simulation)?,
+        RequestVoteReply::builder().term(3).vote(Vote::Granted).build().into(),
+    );
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().current_term(), Term(3));
+
+    Ok(())
+}
+
+#[test]
+fn new_leader_is_elected_after_the_leader_steps_down_without_advancing_time() -> anyhow::Result<()>
+{
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+    simulation.elect(PeerId(1))?;
+
+    // Followers hear from the leader
+    let append_entries_requests = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    for (follower_id, request_id) in append_entries_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: follower_id,
+            replied_peer_id_and_request_id: (PeerId(1), request_id),
+        })?;
+    }
+    for peer_id in (2..=5).map(PeerId) {
+        assert_eq!(simulation.peer(peer_id).unwrap().since_last_valid_leader_contact(), Some(0));
+    }
+
+    simulation.perform(Action::StepDown { peer_id: PeerId(1) })?;
+    simulation.elect(PeerId(2))?;
+    assert!(simulation.peer(PeerId(2)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().current_term(), Term(2));
+    assert_eq!(simulation.peer_role_counts().2, 1);
+
+    Ok(())
+}
+
+#[test]
+fn simulated_votes_can_only_be_granted_to_candidates() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    // Followers cannot be granted simulated votes
+    let action = Action::GrantSimulatedVote { peer_id: PeerId(1) };
+    assert_eq!(simulation.validate_action(&action), Err("Peer 1 is not a candidate".to_owned()));
+    assert!(simulation.perform(action).is_err());
+
+    // Peer 1 becomes the leader after a single simulated vote without any replies
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation.perform(Action::GrantSimulatedVote { peer_id: PeerId(1) })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().current_term(), Term(1));
+    assert!(simulation.peer(PeerId(2)).unwrap().buffered_peer_transmits().is_empty());
+
+    // Leaders cannot be granted simulated votes either
+    let action = Action::GrantSimulatedVote { peer_id: PeerId(1) };
+    assert_eq!(simulation.validate_action(&action), Err("Peer 1 is not a candidate".to_owned()));
+    assert!(simulation.perform(action).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn vote_reply_of_a_previous_election_is_ignored_after_a_restart() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+
+    // Peer 2 grants its vote to peer 1 in term 1
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+    simulation.perform(Action::DropPeerRequest { peer_id: PeerId(1), request_id: RequestId(1) })?;
+
+    // Peer 1 restarts and reuses the request ids in the election of term 2
+    simulation.perform(Action::RestartPeer { peer_id: PeerId(1) })?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+
+    let candidate = simulation.peer(PeerId(1)).unwrap();
+    assert_eq!(candidate.current_term(), Term(2));
+    match candidate.role() {
+        Role::Candidate(candidate_state) => {
+            assert!(candidate_state.vote_requests().contains_key(&RequestId(0)));
+        },
+        role => panic!("Peer 1 is expected to be a candidate but it's {role:?}"),
+    }
+
+    // Vote of term 1 is not counted for term 2
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    match simulation.peer(PeerId(1)).unwrap().role() {
+        Role::Candidate(candidate_state) => {
+            assert_eq!(candidate_state.votes_granted(), 1);
+            assert!(candidate_state.vote_requests().contains_key(&RequestId(0)));
+        },
+        role => panic!("Peer 1 is expected to be a candidate but it's {role:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn leader_forgets_its_append_entries_requests_after_stepping_down() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    let heartbeats = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    assert_eq!(heartbeats.len(), 2);
+
+    let leader = simulation.peer(PeerId(1)).unwrap();
+    match leader.role() {
+        Role::Leader(leader_state) => {
+            assert_eq!(leader_state.append_entries_requests().len(), 2);
+        },
+        role => panic!("Peer 1 is expected to be the leader but it's {role:?}"),
+    }
+    assert_eq!(leader.inflight_append_entries(PeerId(2)), 1);
+    assert_eq!(leader.inflight_append_entries(PeerId(3)), 1);
+
+    // Peer 2 is in a higher term, so its reply makes the leader step down
+    simulation.peer_mut(PeerId(2)).unwrap().set_current_term(Term(5))?;
+    for (follower_id, request_id) in heartbeats.iter().copied() {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        if follower_id == PeerId(2) {
+            simulation.perform(Action::TransmitPeerReply {
+                peer_id: follower_id,
+                replied_peer_id_and_request_id: (PeerId(1), request_id),
+            })?;
+        }
+    }
+
+    let former_leader = simulation.peer(PeerId(1)).unwrap();
+    assert!(former_leader.role().is_follower());
+    assert_eq!(former_leader.current_term(), Term(5));
+    assert_eq!(former_leader.inflight_append_entries(PeerId(2)), 0);
+    assert_eq!(former_leader.inflight_append_entries(PeerId(3)), 0);
+
+    // Late reply of peer 3 is not processed against the defunct leader state
+    let (_, late_request_id) = heartbeats[1];
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(3),
+        replied_peer_id_and_request_id: (PeerId(1), late_request_id),
+    })?;
+    let former_leader = simulation.peer(PeerId(1)).unwrap();
+    assert!(former_leader.role().is_follower());
+    assert_eq!(former_leader.inflight_append_entries(PeerId(3)), 0);
+
+    Ok(())
+}
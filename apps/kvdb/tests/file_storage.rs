@@ -0,0 +1,186 @@
+//! File storage tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    std::{
+        collections::BTreeMap,
+        io::Write,
+        path::{
+            Path,
+            PathBuf,
+        },
+    },
+};
+
+#[allow(dead_code)]
+#[path = "../src/storage.rs"]
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+fn data_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!(
+        "rafty-kvdb-file-storage-test-{}-{}",
+        std::process::id(),
+        name
+    ));
+    if directory.exists() {
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+    directory
+}
+
+fn entry(index: usize, term: usize) -> LogEntry<Application> {
+    LogEntry::builder().index(index).term(term).command(Command::NoOp).build()
+}
+
+fn read_log(directory: &Path) -> anyhow::Result<Vec<LogEntry<Application>>> {
+    let content = std::fs::read_to_string(directory.join("log"))?;
+    Ok(content.lines().map(serde_json::from_str).collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn legacy_json_snapshot_is_migrated() -> anyhow::Result<()> {
+    let directory = data_directory("legacy-snapshot");
+    drop(Storage::new(&directory, true)?);
+
+    std::fs::remove_file(directory.join("snapshot"))?;
+    std::fs::write(
+        directory.join("snapshot.json"),
+        r#"{"last_included_index":3,"last_included_term":2,"machine":{"a":"1"}}"#,
+    )?;
+
+    let expected_snapshot = Snapshot::<Application>::builder()
+        .last_included_index(3)
+        .last_included_term(2)
+        .machine(Machine(BTreeMap::from([("a".to_owned(), "1".to_owned())])))
+        .build();
+
+    let storage = Storage::new(&directory, false)?;
+    assert_eq!(storage.snapshot(), &expected_snapshot);
+    assert!(!directory.join("snapshot.json").exists());
+    drop(storage);
+
+    let bytes = std::fs::read(directory.join("snapshot"))?;
+    assert_eq!(Application::decode_snapshot(&bytes)?, expected_snapshot);
+    assert_eq!(Storage::new(&directory, false)?.snapshot(), &expected_snapshot);
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn transactions_append_to_the_log_file_in_place() -> anyhow::Result<()> {
+    let directory = data_directory("append-in-place");
+    let mut storage = Storage::new(&directory, true)?;
+
+    storage.transaction(|transaction| {
+        transaction.set_current_term(Term(1)).append_log_entry(entry(1, 1));
+    })?;
+    let content = std::fs::read(directory.join("log"))?;
+
+    storage.transaction(|transaction| {
+        transaction.append_log_entry(entry(2, 1)).append_log_entry(entry(3, 1));
+    })?;
+    assert!(std::fs::read(directory.join("log"))?.starts_with(&content));
+    assert_eq!(read_log(&directory)?, vec![entry(1, 1), entry(2, 1), entry(3, 1)]);
+
+    storage.transaction(|transaction| {
+        transaction
+            .set_current_term(Term(2))
+            .truncate_log(LogIndex(2))
+            .append_log_entry(entry(2, 2));
+    })?;
+    assert!(std::fs::read(directory.join("log"))?.starts_with(&content));
+    assert_eq!(read_log(&directory)?, vec![entry(1, 1), entry(2, 2)]);
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), vec![entry(1, 1), entry(2, 2)]);
+    assert!(!directory.join("transaction.json").exists());
+    drop(storage);
+
+    let storage = Storage::new(&directory, false)?;
+    assert_eq!(storage.current_term(), Term(2));
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), vec![entry(1, 1), entry(2, 2)]);
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn transaction_left_behind_is_completed_upon_reopening() -> anyhow::Result<()> {
+    let directory = data_directory("transaction-left-behind");
+    let mut storage = Storage::new(&directory, true)?;
+    storage.transaction(|transaction| {
+        transaction
+            .set_current_term(Term(1))
+            .append_log_entry(entry(1, 1))
+            .append_log_entry(entry(2, 1))
+            .append_log_entry(entry(3, 1));
+    })?;
+
+    // Crash after persisting the transaction, while appending its first entry
+    let mut transaction = StorageTransaction::default();
+    transaction
+        .set_current_term(Term(2))
+        .set_voted_for(Some(PeerId(2)))
+        .truncate_log(LogIndex(2))
+        .append_log_entry(entry(2, 2))
+        .append_log_entry(entry(3, 2));
+    storage.begin_transaction(transaction)?;
+    drop(storage);
+
+    let mut log_file = std::fs::OpenOptions::new().append(true).open(directory.join("log"))?;
+    log_file.write_all(br#"{"index":2,"te"#)?;
+    drop(log_file);
+    assert!(directory.join("transaction.json").exists());
+
+    let expected_log = vec![entry(1, 1), entry(2, 2), entry(3, 2)];
+
+    let storage = Storage::new(&directory, false)?;
+    assert_eq!(storage.current_term(), Term(2));
+    assert_eq!(storage.voted_for(), Some(PeerId(2)));
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), expected_log);
+    assert!(!directory.join("transaction.json").exists());
+    drop(storage);
+
+    assert_eq!(read_log(&directory)?, expected_log);
+    let storage = Storage::new(&directory, false)?;
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), expected_log);
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
+
+#[test]
+fn truncating_the_log_cuts_the_log_file_at_the_offset_of_the_entry() -> anyhow::Result<()> {
+    let directory = data_directory("truncate-at-offset");
+    let mut storage = Storage::new(&directory, true)?;
+    for index in 1..=1000 {
+        storage.append_log_entry(entry(index, 1 + index / 100))?;
+    }
+    let content = std::fs::read(directory.join("log"))?;
+
+    storage.truncate_log(LogIndex(500))?;
+    let expected_log = (1..500).map(|index| entry(index, 1 + index / 100)).collect::<Vec<_>>();
+    let truncated_content = std::fs::read(directory.join("log"))?;
+    assert!(content.starts_with(&truncated_content));
+    assert_eq!(read_log(&directory)?, expected_log);
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), expected_log);
+
+    storage.truncate_log(LogIndex(2000))?;
+    assert_eq!(std::fs::read(directory.join("log"))?, truncated_content);
+
+    storage.append_log_entry(entry(500, 9))?;
+    storage.truncate_log(LogIndex(500))?;
+    storage.append_log_entry(entry(500, 10))?;
+    drop(storage);
+
+    let expected_log = expected_log.into_iter().chain([entry(500, 10)]).collect::<Vec<_>>();
+    assert_eq!(read_log(&directory)?, expected_log);
+    let storage = Storage::new(&directory, false)?;
+    assert_eq!(storage.log().iter().cloned().collect::<Vec<_>>(), expected_log);
+
+    std::fs::remove_dir_all(&directory)?;
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Apply hook tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+    std::sync::Mutex,
+};
+
+mod storage;
+use storage::Storage;
+
+static APPLIED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// A [log::Log] which records the commands applied to the machine.
+struct AppliedRecorder;
+
+impl log::Log for AppliedRecorder {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "rafty_kvdb::machine"
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            APPLIED.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn applied_commands_are_reported_with_their_log_indices() -> anyhow::Result<()> {
+    log::set_logger(&AppliedRecorder).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.transmit_all_pending(true, 100)?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(2));
+
+    simulation.perform(Action::ApplyCommitted { peer_id: Some(PeerId(1)) })?;
+    assert_eq!(
+        *APPLIED.lock().unwrap(),
+        [
+            "Applied `NoOp` at log index 1.",
+            r#"Applied `Insert { key: "x", value: "1" }` at log index 2."#,
+        ],
+    );
+
+    Ok(())
+}
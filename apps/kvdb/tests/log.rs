@@ -0,0 +1,133 @@
+//! Log tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+};
+
+mod storage;
+use storage::Storage;
+
+#[test]
+fn log_entry_equality_ignores_sequence() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    simulation.elect(PeerId(1))?;
+
+    let command = Command::Insert { key: "x".to_owned(), value: "1".to_owned() };
+    for _ in 0..2 {
+        simulation.perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(1)),
+            request_id: None,
+            command: command.clone(),
+        })?;
+    }
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(1),
+    })?;
+
+    let log = simulation.peer(PeerId(1)).unwrap().log();
+    assert_eq!(
+        log.iter().map(|entry| entry.sequence()).collect::<Vec<_>>(),
+        [Some(0), Some(1), Some(2)]
+    );
+
+    let expected_log = Log::from(vec![
+        LogEntry::builder().index(1).term(1).command(Command::NoOp).build(),
+        LogEntry::builder().index(2).term(1).command(command.clone()).build(),
+        LogEntry::builder().index(3).term(1).command(command.clone()).sequence(42).build(),
+    ]);
+    assert_eq!(log, &expected_log);
+
+    let different_entry = LogEntry::builder().index(2).term(2).command(command).sequence(1).build();
+    assert_ne!(log[1], different_entry);
+
+    Ok(())
+}
+
+#[test]
+fn annotated_log_classifies_entries_at_boundaries() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+    let mut peer = Peer::<KeyValueDatabase<Storage>>::new(
+        PeerId(1),
+        cluster,
+        Consistency::Strong,
+        Storage::default(),
+    );
+    peer.set_log(
+        (1..=4)
+            .map(|index| LogEntry::builder().index(index).term(1).command(Command::NoOp).build())
+            .collect(),
+    )?;
+
+    let statuses = |peer: &Peer<KeyValueDatabase<Storage>>| {
+        peer.annotated_log().map(|(entry, status)| (entry.index().0, status)).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        statuses(&peer),
+        vec![
+            (1, EntryStatus::Uncommitted),
+            (2, EntryStatus::Uncommitted),
+            (3, EntryStatus::Uncommitted),
+            (4, EntryStatus::Uncommitted),
+        ],
+    );
+
+    peer.set_commit_index(LogIndex(3));
+    peer.set_last_applied(LogIndex(1));
+    assert_eq!(
+        statuses(&peer),
+        vec![
+            (1, EntryStatus::Applied),
+            (2, EntryStatus::Committed),
+            (3, EntryStatus::Committed),
+            (4, EntryStatus::Uncommitted),
+        ],
+    );
+
+    peer.set_commit_index(LogIndex(4));
+    peer.set_last_applied(LogIndex(4));
+    assert!(statuses(&peer).iter().all(|(_, status)| *status == EntryStatus::Applied));
+
+    Ok(())
+}
+
+#[test]
+fn log_entry_validation_rejects_commands_at_index_zero() {
+    let _ = env_logger::try_init();
+
+    let entry = LogEntry::<KeyValueDatabase<Storage>>::builder()
+        .index(LogIndex(0))
+        .term(Term(1))
+        .command(Command::Insert { key: "x".to_owned(), value: "1".to_owned() })
+        .build();
+    assert!(entry.validate().is_err());
+
+    let entry = LogEntry::<KeyValueDatabase<Storage>>::builder()
+        .index(LogIndex(0))
+        .term(Term(1))
+        .command(Command::NoOp)
+        .build();
+    assert_eq!(entry.validate(), Ok(()));
+
+    let entry = LogEntry::<KeyValueDatabase<Storage>>::builder()
+        .index(LogIndex(1))
+        .term(Term(1))
+        .command(Command::Insert { key: "x".to_owned(), value: "1".to_owned() })
+        .build();
+    assert_eq!(entry.validate(), Ok(()));
+}
@@ -0,0 +1,171 @@
+//! Machine tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+    std::collections::BTreeMap,
+};
+
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+#[test]
+fn clearing_then_reapplying_produces_the_same_machine() {
+    let commands = [
+        Command::Insert { key: "a".to_owned(), value: "1".to_owned() },
+        Command::Upsert { key: "b".to_owned(), value: "2".to_owned() },
+        Command::Clear { key: "a".to_owned() },
+        Command::Insert { key: "c".to_owned(), value: "3".to_owned() },
+    ];
+
+    let mut machine = Machine::default();
+    for command in commands.iter() {
+        RaftMachine::<Application>::apply(&mut machine, command);
+    }
+    let expected = Machine(BTreeMap::from([
+        ("b".to_owned(), "2".to_owned()),
+        ("c".to_owned(), "3".to_owned()),
+    ]));
+    assert_eq!(machine, expected);
+
+    RaftMachine::<Application>::clear(&mut machine);
+    assert_eq!(machine, Machine::default());
+    assert_eq!(
+        RaftMachine::<Application>::query(&machine, &Query::Length),
+        QueryResult::Length { length: 0 },
+    );
+
+    for command in commands.iter() {
+        RaftMachine::<Application>::apply(&mut machine, command);
+    }
+    assert_eq!(machine, expected);
+}
+
+#[test]
+fn increment_and_decrement_update_numeric_values() {
+    let mut machine = Machine::default();
+
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Increment { key: "counter".to_owned(), by: 5 },
+        ),
+        CommandResult::Value { value: 5 },
+    );
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Decrement { key: "counter".to_owned(), by: 7 },
+        ),
+        CommandResult::Value { value: -2 },
+    );
+    assert_eq!(
+        RaftMachine::<Application>::query(&machine, &Query::Entry { key: "counter".to_owned() }),
+        QueryResult::Entry { value: Some("-2".to_owned()) },
+    );
+}
+
+#[test]
+fn increment_and_decrement_reject_overflows() {
+    let mut machine = Machine(BTreeMap::from([
+        ("max".to_owned(), i64::MAX.to_string()),
+        ("min".to_owned(), i64::MIN.to_string()),
+    ]));
+    let expected = machine.clone();
+
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Increment { key: "max".to_owned(), by: 1 },
+        ),
+        CommandResult::Overflow,
+    );
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Decrement { key: "min".to_owned(), by: 1 },
+        ),
+        CommandResult::Overflow,
+    );
+    assert_eq!(machine, expected);
+}
+
+#[test]
+fn increment_and_decrement_reject_non_numeric_values() {
+    let mut machine = Machine(BTreeMap::from([("name".to_owned(), "rafty".to_owned())]));
+    let expected = machine.clone();
+
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Increment { key: "name".to_owned(), by: 1 },
+        ),
+        CommandResult::NotNumeric,
+    );
+    assert_eq!(
+        RaftMachine::<Application>::apply(
+            &mut machine,
+            &Command::Decrement { key: "name".to_owned(), by: 1 },
+        ),
+        CommandResult::NotNumeric,
+    );
+    assert_eq!(machine, expected);
+}
+
+#[test]
+fn empty_keys_violate_the_invariants() {
+    let mut machine = Machine::default();
+    assert_eq!(RaftMachine::<Application>::check_invariants(&machine), Ok(()));
+
+    RaftMachine::<Application>::apply(
+        &mut machine,
+        &Command::Upsert { key: "".to_owned(), value: "1".to_owned() },
+    );
+    assert!(RaftMachine::<Application>::check_invariants(&machine).is_err());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "violated the invariants of the machine")]
+fn applying_a_command_that_violates_the_invariants_panics() {
+    let _ = env_logger::try_init();
+
+    let mut simulation =
+        Simulation::<Application>::new(Consistency::Strong, vec![Storage::default(); 3], 1)
+            .unwrap();
+    simulation.elect(PeerId(1)).unwrap();
+
+    simulation
+        .perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(1)),
+            request_id: None,
+            command: Command::Insert { key: "".to_owned(), value: "1".to_owned() },
+        })
+        .unwrap();
+    simulation
+        .perform(Action::TransmitClientRequest { client_id: ClientId(1), request_id: RequestId(0) })
+        .unwrap();
+
+    let append_entries_requests = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    for (follower_id, request_id) in append_entries_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id }).unwrap();
+        simulation
+            .perform(Action::TransmitPeerReply {
+                peer_id: follower_id,
+                replied_peer_id_and_request_id: (PeerId(1), request_id),
+            })
+            .unwrap();
+    }
+
+    let _ = simulation.perform(Action::ApplyCommitted { peer_id: Some(PeerId(1)) });
+}
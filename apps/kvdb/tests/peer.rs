@@ -0,0 +1,345 @@
+//! Peer tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+mod storage;
+use storage::Storage;
+
+fn peer(peer_id: PeerId) -> Peer<KeyValueDatabase<Storage>> {
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+    Peer::new(peer_id, cluster, Consistency::Strong, Storage::default())
+}
+
+#[test]
+fn handle_message_returns_buffered_transmits() {
+    let _ = env_logger::try_init();
+
+    let mut candidate = peer(PeerId(1));
+    candidate.trigger_election_timeout();
+
+    let request = candidate.buffered_peer_transmits().front().unwrap().clone();
+    assert!(request.message().is_request());
+
+    let mut buffering_peer = peer(PeerId(2));
+    buffering_peer.receive_peer_message(PeerId(1), request.request_id(), request.message().clone());
+    let buffered_transmits =
+        buffering_peer.buffered_peer_transmits().iter().cloned().collect::<Vec<_>>();
+
+    let mut handling_peer = peer(PeerId(2));
+    let returned_transmits =
+        handling_peer.handle_message(PeerId(1), request.request_id(), request.message().clone());
+
+    assert_eq!(returned_transmits.len(), 1);
+    assert_eq!(returned_transmits, buffered_transmits);
+    assert!(handling_peer.buffered_peer_transmits().is_empty());
+
+    assert_eq!(handling_peer.current_term(), buffering_peer.current_term());
+    assert_eq!(handling_peer.voted_for(), buffering_peer.voted_for());
+}
+
+#[test]
+fn follower_records_leader_of_accepted_append_entries_request() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut follower = peer(PeerId(1));
+    follower.set_current_term(Term(1))?;
+    follower.set_role(Role::Follower(FollowerState::builder().leader_id(PeerId(3)).build()));
+
+    let request = AppendEntriesRequest::builder()
+        .term(1)
+        .leader_id(PeerId(2))
+        .prev_log_index(0)
+        .prev_log_term(0)
+        .entries(vec![])
+        .leader_commit(0)
+        .build();
+    let replies = follower.handle_message(
+        PeerId(2),
+        RequestId(0),
+        PeerMessage::AppendEntriesRequest(request),
+    );
+
+    assert_eq!(
+        replies.iter().map(|transmit| transmit.message().clone()).collect::<Vec<_>>(),
+        vec![PeerMessage::AppendEntriesReply(
+            AppendEntriesReply::builder().term(1).success(true).build(),
+        )],
+    );
+    assert_eq!(
+        follower.role(),
+        &Role::Follower(FollowerState::builder().leader_id(PeerId(2)).build()),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn append_entries_request_with_malformed_entry_is_rejected() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut follower = peer(PeerId(1));
+    follower.set_current_term(Term(1))?;
+
+    let request = AppendEntriesRequest::builder()
+        .term(2)
+        .leader_id(PeerId(2))
+        .prev_log_index(0)
+        .prev_log_term(0)
+        .entries(vec![LogEntry::builder()
+            .index(0)
+            .term(2)
+            .command(Command::Insert { key: "x".to_owned(), value: "1".to_owned() })
+            .build()])
+        .leader_commit(0)
+        .build();
+    let replies = follower.handle_message(
+        PeerId(2),
+        RequestId(0),
+        PeerMessage::AppendEntriesRequest(request),
+    );
+
+    assert_eq!(
+        replies.iter().map(|transmit| transmit.message().clone()).collect::<Vec<_>>(),
+        vec![PeerMessage::AppendEntriesReply(
+            AppendEntriesReply::builder().term(1).success(false).build(),
+        )],
+    );
+    assert_eq!(follower.current_term(), Term(1));
+    assert!(follower.log().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn append_entries_request_replaces_conflicting_entries() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let entry = |index: usize, term: usize| {
+        LogEntry::builder().index(index).term(term).command(Command::NoOp).build()
+    };
+
+    let mut follower = peer(PeerId(1));
+    follower.set_current_term(Term(2))?;
+    follower.set_log(vec![entry(1, 1), entry(2, 1), entry(3, 1)])?;
+
+    let request = AppendEntriesRequest::builder()
+        .term(2)
+        .leader_id(PeerId(2))
+        .prev_log_index(1)
+        .prev_log_term(1)
+        .entries(vec![entry(2, 2)])
+        .leader_commit(0)
+        .build();
+    let replies = follower.handle_message(
+        PeerId(2),
+        RequestId(0),
+        PeerMessage::AppendEntriesRequest(request),
+    );
+
+    assert_eq!(
+        replies.iter().map(|transmit| transmit.message().clone()).collect::<Vec<_>>(),
+        vec![PeerMessage::AppendEntriesReply(
+            AppendEntriesReply::builder().term(2).success(true).build(),
+        )],
+    );
+    assert_eq!(follower.log().iter().cloned().collect::<Vec<_>>(), vec![entry(1, 1), entry(2, 2)]);
+
+    Ok(())
+}
+
+#[test]
+fn client_request_with_mismatched_client_id_is_rejected() {
+    let _ = env_logger::try_init();
+
+    let mut follower = peer(PeerId(1));
+
+    let command = Command::Upsert { key: "x".to_owned(), value: "1".to_owned() };
+    let request = CommandRequest::builder().client_id(ClientId(2)).command(command).build();
+    follower.receive_client_message(ClientId(1), RequestId(0), request.into());
+
+    let query = Query::Entry { key: "x".to_owned() };
+    let request = QueryRequest::builder().client_id(ClientId(2)).query(query.clone()).build();
+    follower.receive_client_message(ClientId(1), RequestId(1), request.into());
+
+    assert!(follower.buffered_client_transmits().is_empty());
+
+    let request = QueryRequest::builder().client_id(ClientId(1)).query(query).build();
+    follower.receive_client_message(ClientId(1), RequestId(2), request.into());
+
+    let replies = follower.buffered_client_transmits();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].client_id(), ClientId(1));
+    assert_eq!(replies[0].request_id(), RequestId(2));
+}
+
+#[test]
+fn only_candidate_rejections_carry_backoff_guidance() {
+    let _ = env_logger::try_init();
+
+    let command = Command::Upsert { key: "x".to_owned(), value: "1".to_owned() };
+    let request = CommandRequest::builder().client_id(ClientId(1)).command(command).build();
+
+    let mut candidate = peer(PeerId(1));
+    candidate.trigger_election_timeout();
+    assert!(candidate.role().is_candidate());
+    candidate.receive_client_message(ClientId(1), RequestId(0), request.clone().into());
+
+    let retry_after = Peer::<KeyValueDatabase<Storage>>::CANDIDATE_RETRY_AFTER;
+    assert_eq!(
+        candidate.buffered_client_transmits()[0].message(),
+        &ClientMessage::CommandReply(
+            CommandReply::builder()
+                .result(Err(ClientError::NotLeaderRetryAfter { retry_after }))
+                .build(),
+        ),
+    );
+
+    let mut follower = peer(PeerId(2));
+    follower.set_role(Role::Follower(FollowerState::builder().leader_id(PeerId(3)).build()));
+    follower.receive_client_message(ClientId(1), RequestId(0), request.into());
+
+    assert_eq!(
+        follower.buffered_client_transmits()[0].message(),
+        &ClientMessage::CommandReply(
+            CommandReply::builder()
+                .result(Err(ClientError::LeaderChanged { new_leader_id: PeerId(3) }))
+                .build(),
+        ),
+    );
+}
+
+#[test]
+fn debug_summary_describes_each_role() {
+    let _ = env_logger::try_init();
+
+    let mut candidate = peer(PeerId(1));
+    assert_eq!(
+        candidate.debug_summary(),
+        "Peer 1 | Follower | term=0 | commit=0 | applied=0 | log=0",
+    );
+
+    candidate.trigger_election_timeout();
+    assert_eq!(
+        candidate.debug_summary(),
+        "Peer 1 | Candidate | term=1 | commit=0 | applied=0 | log=0",
+    );
+
+    let request = candidate.buffered_peer_transmits().front().unwrap().clone();
+    let mut voter = peer(request.peer_id());
+    let reply = voter
+        .handle_message(PeerId(1), request.request_id(), request.message().clone())
+        .pop()
+        .unwrap();
+    candidate.receive_peer_message(voter.id(), reply.request_id(), reply.into_message());
+    assert_eq!(
+        candidate.debug_summary(),
+        "Peer 1 | Leader | term=1 | commit=0 | applied=0 | log=1",
+    );
+}
+
+#[test]
+fn follower_forgets_leader_of_older_term_on_vote_request() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut follower = peer(PeerId(1));
+    follower.set_current_term(Term(1))?;
+    follower.set_role(Role::Follower(FollowerState::builder().leader_id(PeerId(2)).build()));
+
+    let request = RequestVoteRequest::builder()
+        .term(2)
+        .candidate_id(PeerId(3))
+        .last_log_index(0)
+        .last_log_term(0)
+        .build();
+    follower.receive_peer_message(PeerId(3), RequestId(0), request.into());
+
+    assert_eq!(follower.current_term(), Term(2));
+    match follower.role() {
+        Role::Follower(follower_state) => assert_eq!(follower_state.leader_id(), None),
+        role => panic!("expected a follower, got {role:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn peeking_next_request_id_follows_buffered_requests() {
+    let _ = env_logger::try_init();
+
+    let mut candidate = peer(PeerId(1));
+    assert_eq!(candidate.peek_next_request_id(), RequestId(0));
+    assert_eq!(candidate.peek_next_request_id(), RequestId(0));
+
+    candidate.trigger_election_timeout();
+    let request_ids = candidate
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+    assert_eq!(request_ids, [RequestId(0), RequestId(1)]);
+    assert_eq!(candidate.peek_next_request_id(), RequestId(2));
+
+    candidate.trigger_election_timeout();
+    let next_request_id = candidate.buffered_peer_transmits().back().unwrap().request_id();
+    assert_eq!(next_request_id, RequestId(3));
+    assert_eq!(candidate.peek_next_request_id(), RequestId(4));
+}
+
+#[test]
+fn out_of_order_log_stops_applying_committed_entries() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let entry = |index: usize, key: &str| {
+        LogEntry::builder()
+            .index(LogIndex(index))
+            .term(Term(1))
+            .command(Command::Insert { key: key.to_owned(), value: "1".to_owned() })
+            .build()
+    };
+
+    let mut follower = peer(PeerId(2));
+    follower.set_log(vec![entry(1, "a"), entry(3, "c"), entry(2, "b")])?;
+    follower.set_commit_index(LogIndex(3));
+
+    let results = follower.apply_committed();
+    assert_eq!(results.len(), 1);
+    assert_eq!(follower.last_applied(), LogIndex(1));
+    assert_eq!(follower.machine().0.keys().collect::<Vec<_>>(), ["a"]);
+
+    Ok(())
+}
+
+#[test]
+fn log_up_to_date_comparison_prefers_terms_then_indices() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut voter = peer(PeerId(1));
+    assert!(voter.log_is_at_least_as_up_to_date(LogIndex(0), Term(0)));
+
+    voter.set_log(
+        [(1, 1), (2, 2), (3, 2)]
+            .into_iter()
+            .map(|(index, term)| {
+                LogEntry::builder()
+                    .index(LogIndex(index))
+                    .term(Term(term))
+                    .command(Command::NoOp)
+                    .build()
+            })
+            .collect(),
+    )?;
+
+    // Later last term wins regardless of the length
+    assert!(voter.log_is_at_least_as_up_to_date(LogIndex(1), Term(3)));
+    assert!(!voter.log_is_at_least_as_up_to_date(LogIndex(10), Term(1)));
+
+    // Same last term is decided by the length
+    assert!(voter.log_is_at_least_as_up_to_date(LogIndex(4), Term(2)));
+    assert!(voter.log_is_at_least_as_up_to_date(LogIndex(3), Term(2)));
+    assert!(!voter.log_is_at_least_as_up_to_date(LogIndex(2), Term(2)));
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Primitive tests.
+
+use rafty::prelude::*;
+
+#[test]
+fn identifiers_display_as_bare_numbers() {
+    assert_eq!(PeerId(3).to_string(), "3");
+    assert_eq!(ClientId(2).to_string(), "2");
+    assert_eq!(RequestId(7).to_string(), "7");
+    assert_eq!(Term(5).to_string(), "5");
+    assert_eq!(LogIndex(11).to_string(), "11");
+
+    assert_eq!(format!("peer {}", PeerId(3)), "peer 3");
+    assert_eq!(format!("{:?}", PeerId(3)), "PeerId(3)");
+}
+
+#[test]
+fn identifiers_respect_width_and_alignment() {
+    assert_eq!(format!("[{:>4}]", PeerId(3)), "[   3]");
+    assert_eq!(format!("[{:<4}]", ClientId(12)), "[12  ]");
+    assert_eq!(format!("[{:^5}]", Term(7)), "[  7  ]");
+    assert_eq!(format!("[{:03}]", LogIndex(9)), "[009]");
+    assert_eq!(format!("[{:>2}]", RequestId(123)), "[123]");
+}
+
+#[test]
+fn cluster_diff_can_be_applied() {
+    let three_peers = Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+    let five_peers = Cluster::from((1..=5).map(PeerId).collect::<std::collections::BTreeSet<_>>());
+
+    let (added, removed) = three_peers.diff(&five_peers);
+    assert_eq!(added, [PeerId(4), PeerId(5)].into());
+    assert!(removed.is_empty());
+
+    let mut cluster = three_peers.clone();
+    cluster.apply_change(&added, &removed);
+    assert_eq!(cluster, five_peers);
+
+    let (added, removed) = five_peers.diff(&three_peers);
+    assert!(added.is_empty());
+    assert_eq!(removed, [PeerId(4), PeerId(5)].into());
+
+    cluster.apply_change(&added, &removed);
+    assert_eq!(cluster, three_peers);
+
+    let (added, removed) = cluster.diff(&cluster.clone());
+    assert!(added.is_empty() && removed.is_empty());
+}
+
+#[test]
+fn clusters_can_be_built_by_adding_and_removing_peers() {
+    let cluster = Cluster::from([PeerId(1), PeerId(2), PeerId(3)]);
+    assert_eq!(
+        cluster,
+        Cluster::from((1..=3).map(PeerId).collect::<std::collections::BTreeSet<_>>()),
+    );
+    assert_eq!(
+        Cluster::from([PeerId(2), PeerId(1), PeerId(2)]),
+        Cluster::from([PeerId(1), PeerId(2)])
+    );
+
+    let added = cluster.clone().with_added(PeerId(4));
+    assert_eq!(added, Cluster::from([PeerId(1), PeerId(2), PeerId(3), PeerId(4)]));
+    assert_eq!(added.clone().with_added(PeerId(4)), added);
+
+    let removed = added.with_removed(PeerId(2));
+    assert_eq!(removed, Cluster::from([PeerId(1), PeerId(3), PeerId(4)]));
+    assert_eq!(removed.clone().with_removed(PeerId(2)), removed);
+
+    assert_eq!(cluster.clone().with_added(PeerId(5)).with_removed(PeerId(5)), cluster);
+}
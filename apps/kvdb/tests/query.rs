@@ -0,0 +1,533 @@
+//! Query tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+    std::collections::BTreeMap,
+};
+
+mod storage;
+use storage::Storage;
+
+#[test]
+fn new_leader_refuses_linearizable_reads_until_no_op_commits() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    let leader_id = PeerId(1);
+
+    // Peer 1 gets elected
+    simulation.perform(Action::TimeoutElection { peer_id: leader_id })?;
+    simulation.perform(Action::TransmitPeerRequests {
+        peer_id: leader_id,
+        request_ids: vec![RequestId(0), RequestId(1)],
+    })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (leader_id, RequestId(0)),
+    })?;
+    assert!(simulation.peer(leader_id).unwrap().role().is_leader());
+    assert!(!simulation.peer(leader_id).unwrap().can_serve_linearizable_read());
+
+    // Client queries the leader before the no-op is committed
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+
+    let replies = simulation.peer(leader_id).unwrap().buffered_client_transmits();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(
+        replies[0].message(),
+        &ClientMessage::QueryReply(
+            QueryReply::builder().result(Err(ClientError::LeaderNotReady)).build(),
+        ),
+    );
+
+    // Peer 2 accepts the no-op, which commits it
+    let request_id = simulation
+        .peer(leader_id)
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .find(|transmit| {
+            transmit.peer_id() == PeerId(2)
+                && matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_))
+        })
+        .map(|transmit| transmit.request_id())
+        .unwrap();
+    simulation.perform(Action::TransmitPeerRequest { peer_id: leader_id, request_id })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (leader_id, request_id),
+    })?;
+
+    assert_eq!(simulation.peer(leader_id).unwrap().commit_index(), LogIndex(1));
+    assert!(simulation.peer(leader_id).unwrap().can_serve_linearizable_read());
+    assert!(!simulation.peer(PeerId(2)).unwrap().can_serve_linearizable_read());
+
+    Ok(())
+}
+
+#[test]
+fn querying_all_peers_detects_lagging_follower() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Eventual,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    let leader_id = PeerId(1);
+    let lagging_follower_id = PeerId(3);
+    simulation.elect(leader_id)?;
+
+    let find_append_entries_request = |simulation: &Simulation<_>, follower_id| {
+        simulation
+            .peer(leader_id)
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .find(|transmit| {
+                transmit.peer_id() == follower_id
+                    && matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_))
+            })
+            .map(|transmit| transmit.request_id())
+            .unwrap()
+    };
+
+    // Client inserts an entry, which is replicated to and committed with both followers
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        command: Command::Upsert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+
+    for follower_id in [PeerId(2), lagging_follower_id] {
+        let request_id = find_append_entries_request(&simulation, follower_id);
+        simulation.perform(Action::TransmitPeerRequest { peer_id: leader_id, request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: follower_id,
+            replied_peer_id_and_request_id: (leader_id, request_id),
+        })?;
+    }
+    assert_eq!(simulation.peer(leader_id).unwrap().commit_index(), LogIndex(2));
+
+    // Peer 2 learns the new commit index from the heartbeat, peer 3 never receives it
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: leader_id })?;
+
+    let request_id = find_append_entries_request(&simulation, PeerId(2));
+    simulation.perform(Action::TransmitPeerRequest { peer_id: leader_id, request_id })?;
+
+    let request_id = find_append_entries_request(&simulation, lagging_follower_id);
+    simulation.perform(Action::DropPeerRequest { peer_id: leader_id, request_id })?;
+
+    // Client queries the entry from all of the peers
+    let request_ids = simulation
+        .client_mut(ClientId(1))
+        .unwrap()
+        .query_all(Query::Entry { key: "x".to_owned() })?;
+    assert_eq!(request_ids.len(), 3);
+
+    for (&peer_id, &request_id) in &request_ids {
+        simulation.perform(Action::TransmitClientRequest { client_id: ClientId(1), request_id })?;
+        simulation.perform(Action::TransmitClientReply {
+            peer_id,
+            replied_client_id_and_request_id: (ClientId(1), request_id),
+        })?;
+    }
+
+    let reconciliation =
+        simulation.client(ClientId(1)).unwrap().reconcile_query_results(&request_ids);
+    assert!(reconciliation.pending().is_empty());
+    assert!(reconciliation.errors().is_empty());
+
+    let up_to_date_result = QueryResult::Entry { value: Some("1".to_owned()) };
+    let stale_result = QueryResult::Entry { value: None };
+    assert_eq!(reconciliation.results().get(&PeerId(1)), Some(&up_to_date_result));
+    assert_eq!(reconciliation.results().get(&PeerId(2)), Some(&up_to_date_result));
+    assert_eq!(reconciliation.results().get(&lagging_follower_id), Some(&stale_result));
+
+    assert!(reconciliation.is_divergent());
+    assert_eq!(reconciliation.agreed_result(), None);
+    assert_eq!(reconciliation.divergent_peers(), [lagging_follower_id].into());
+
+    Ok(())
+}
+
+fn replicate_to(
+    simulation: &mut Simulation<KeyValueDatabase<Storage>>,
+    leader_id: PeerId,
+    follower_id: PeerId,
+) -> anyhow::Result<()> {
+    let request_ids = simulation
+        .peer(leader_id)
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .filter(|transmit| transmit.peer_id() == follower_id && transmit.message().is_request())
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+    for request_id in request_ids {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: leader_id, request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: follower_id,
+            replied_peer_id_and_request_id: (leader_id, request_id),
+        })?;
+    }
+    Ok(())
+}
+
+#[test]
+fn heartbeats_awaited_by_reads_are_not_forgotten_when_considered_lost() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::ReadIndex,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    let leader_id = PeerId(1);
+    simulation.elect(leader_id)?;
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+    replicate_to(&mut simulation, leader_id, PeerId(3))?;
+
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    let read_heartbeat_request_ids = simulation
+        .peer(leader_id)
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+
+    // Heartbeats of the read and the following ones are in flight for a whole heartbeat interval
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: leader_id })?;
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: leader_id })?;
+    let heartbeat_request_ids = simulation
+        .peer(leader_id)
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| transmit.request_id())
+        .filter(|request_id| !read_heartbeat_request_ids.contains(request_id))
+        .take(2)
+        .collect::<Vec<_>>();
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: leader_id })?;
+
+    let Role::Leader(leader_state) = simulation.peer(leader_id).unwrap().role() else {
+        unreachable!()
+    };
+    for request_id in &read_heartbeat_request_ids {
+        assert!(leader_state.append_entries_requests().contains_key(request_id));
+    }
+    for request_id in &heartbeat_request_ids {
+        assert!(!leader_state.append_entries_requests().contains_key(request_id));
+    }
+    assert_eq!(simulation.peer(leader_id).unwrap().inflight_append_entries(PeerId(2)), 2);
+
+    // Late acknowledgement of the heartbeats of the read still serves it
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+    let replies = simulation.peer(leader_id).unwrap().buffered_client_transmits();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].request_id(), RequestId(0));
+
+    Ok(())
+}
+
+#[test]
+fn read_index_reads_wait_for_a_heartbeat_round() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::ReadIndex,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    let leader_id = PeerId(1);
+    simulation.elect(leader_id)?;
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+    replicate_to(&mut simulation, leader_id, PeerId(3))?;
+    assert_eq!(simulation.peer(leader_id).unwrap().commit_index(), LogIndex(2));
+
+    // Leader doesn't reply before confirming its leadership
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(1),
+    })?;
+    assert!(simulation.peer(leader_id).unwrap().buffered_client_transmits().is_empty());
+
+    let Role::Leader(leader_state) = simulation.peer(leader_id).unwrap().role() else {
+        unreachable!()
+    };
+    assert_eq!(leader_state.pending_reads().len(), 1);
+    assert_eq!(leader_state.pending_reads()[0].read_index(), LogIndex(2));
+
+    // Majority acknowledges the heartbeat round, which serves the read
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+
+    let replies = simulation.peer(leader_id).unwrap().buffered_client_transmits();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].request_id(), RequestId(1));
+    assert_eq!(
+        replies[0].message(),
+        &ClientMessage::QueryReply(
+            QueryReply::builder().result(Ok(QueryResult::Length { length: 1 })).build(),
+        ),
+    );
+    assert_eq!(simulation.peer(leader_id).unwrap().last_applied(), LogIndex(2));
+
+    let Role::Leader(leader_state) = simulation.peer(leader_id).unwrap().role() else {
+        unreachable!()
+    };
+    assert!(leader_state.pending_reads().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn lease_reads_are_served_right_away_until_the_lease_expires() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::LeaseRead,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    for peer_id in (1..=3).map(PeerId) {
+        simulation.peer_mut(peer_id).unwrap().set_leader_stickiness(true);
+    }
+    let leader_id = PeerId(1);
+    simulation.elect(leader_id)?;
+    assert!(simulation.peer(leader_id).unwrap().holds_lease());
+
+    // Leader replies right away while holding the lease
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    assert_eq!(simulation.peer(leader_id).unwrap().buffered_client_transmits().len(), 1);
+    simulation.perform(Action::TransmitClientReply {
+        peer_id: leader_id,
+        replied_client_id_and_request_id: (ClientId(1), RequestId(0)),
+    })?;
+
+    // Lease expires
+    simulation.perform(Action::AdvanceTime {
+        ticks: Peer::<KeyValueDatabase<Storage>>::LEASE_DURATION,
+    })?;
+    assert!(!simulation.peer(leader_id).unwrap().holds_lease());
+
+    // Leader falls back to confirming its leadership with a heartbeat round
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Length,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(1),
+    })?;
+    assert!(simulation.peer(leader_id).unwrap().buffered_client_transmits().is_empty());
+
+    replicate_to(&mut simulation, leader_id, PeerId(3))?;
+    assert_eq!(simulation.peer(leader_id).unwrap().buffered_client_transmits().len(), 1);
+
+    // Acknowledged heartbeat round renews the lease
+    assert!(simulation.peer(leader_id).unwrap().holds_lease());
+
+    Ok(())
+}
+
+#[test]
+fn dump_query_returns_the_whole_machine() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::LeaseRead,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    for peer_id in (1..=3).map(PeerId) {
+        simulation.peer_mut(peer_id).unwrap().set_leader_stickiness(true);
+    }
+    let leader_id = PeerId(1);
+    simulation.elect(leader_id)?;
+
+    for (request_id, key) in ["x", "y"].into_iter().enumerate() {
+        simulation.perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(leader_id),
+            request_id: None,
+            command: Command::Insert { key: key.to_owned(), value: request_id.to_string() },
+        })?;
+        simulation.perform(Action::TransmitClientRequest {
+            client_id: ClientId(1),
+            request_id: RequestId(request_id),
+        })?;
+    }
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+    replicate_to(&mut simulation, leader_id, PeerId(3))?;
+    simulation.perform(Action::ApplyCommitted { peer_id: Some(leader_id) })?;
+
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Dump,
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(2),
+    })?;
+    simulation.perform(Action::TransmitClientReply {
+        peer_id: leader_id,
+        replied_client_id_and_request_id: (ClientId(1), RequestId(2)),
+    })?;
+
+    let expected_entries =
+        BTreeMap::from([("x".to_owned(), "0".to_owned()), ("y".to_owned(), "1".to_owned())]);
+    assert_eq!(simulation.peer(leader_id).unwrap().machine(), &Machine(expected_entries.clone()));
+    assert_eq!(
+        simulation.client(ClientId(1)).unwrap().query_result(RequestId(2)),
+        Some(&Ok(QueryResult::Dump { entries: expected_entries })),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn log_reads_are_ordered_with_the_commands_around_them() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::LogRead,
+        vec![Storage::default(); 3],
+        1,
+    )?;
+    let leader_id = PeerId(1);
+    simulation.elect(leader_id)?;
+
+    // Query is sent between two writes to the same key
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::SendQuery {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        query: Query::Entry { key: "x".to_owned() },
+    })?;
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        command: Command::Upsert { key: "x".to_owned(), value: "2".to_owned() },
+    })?;
+    for request_id in [RequestId(0), RequestId(1), RequestId(2)] {
+        simulation.perform(Action::TransmitClientRequest { client_id: ClientId(1), request_id })?;
+    }
+
+    // Query waits for its no-op entry to be applied
+    let leader = simulation.peer(leader_id).unwrap();
+    assert!(leader.buffered_client_transmits().is_empty());
+    assert_eq!(leader.log()[2].command(), &Command::NoOp);
+    let Role::Leader(leader_state) = leader.role() else { unreachable!() };
+    assert_eq!(leader_state.log_reads()[&LogIndex(3)][0].request_id(), RequestId(1));
+
+    replicate_to(&mut simulation, leader_id, PeerId(2))?;
+    replicate_to(&mut simulation, leader_id, PeerId(3))?;
+    assert_eq!(simulation.peer(leader_id).unwrap().commit_index(), LogIndex(4));
+    simulation.perform(Action::ApplyCommitted { peer_id: Some(leader_id) })?;
+
+    // Query sees the first write but not the second one
+    let leader = simulation.peer(leader_id).unwrap();
+    let replies = leader.buffered_client_transmits();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].request_id(), RequestId(1));
+    assert_eq!(
+        replies[0].message(),
+        &ClientMessage::QueryReply(
+            QueryReply::builder()
+                .result(Ok(QueryResult::Entry { value: Some("1".to_owned()) }))
+                .build(),
+        ),
+    );
+    assert_eq!(leader.machine().0.get("x"), Some(&"2".to_owned()));
+
+    let Role::Leader(leader_state) = leader.role() else { unreachable!() };
+    assert!(leader_state.log_reads().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn query_results_are_summarized() {
+    assert_eq!(QueryResult::Length { length: 1 }.summary(), "1 entry");
+    assert_eq!(QueryResult::Length { length: 0 }.summary(), "0 entries");
+    assert_eq!(QueryResult::Entry { value: Some("1".to_owned()) }.summary(), "Value is `1`");
+    assert_eq!(QueryResult::Entry { value: None }.summary(), "Key is not found");
+    assert_eq!(QueryResult::Dump { entries: BTreeMap::new() }.summary(), "No entries");
+    assert_eq!(
+        QueryResult::Dump {
+            entries: BTreeMap::from([
+                ("a".to_owned(), "1".to_owned()),
+                ("b".to_owned(), "2".to_owned()),
+            ]),
+        }
+        .summary(),
+        "`a` is `1`\n`b` is `2`",
+    );
+}
@@ -0,0 +1,109 @@
+//! Scenario tests.
+
+use {
+    clap::{
+        Parser as Clap,
+        ValueEnum,
+    },
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+};
+
+mod storage;
+use storage::Storage;
+
+fn simulation_of(number_of_peers: usize) -> anyhow::Result<Simulation<KeyValueDatabase<Storage>>> {
+    Simulation::new(Consistency::Strong, vec![Storage::default(); number_of_peers], 2)
+}
+
+#[test]
+fn every_scenario_is_set_up_without_errors() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    for number_of_peers in [3, 5] {
+        for scenario in Scenario::value_variants() {
+            let mut simulation = simulation_of(number_of_peers)?;
+            scenario.setup(&mut simulation)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn scenarios_are_set_up_to_their_interesting_points() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation_of(3)?;
+    Scenario::SingleCandidateElection.setup(&mut simulation)?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_candidate());
+    assert!(simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits().is_empty());
+    for peer_id in [PeerId(2), PeerId(3)] {
+        let replies = simulation.peer(peer_id).unwrap().buffered_peer_transmits();
+        assert_eq!(replies.len(), 1);
+        assert!(matches!(replies[0].message(), PeerMessage::RequestVoteReply(_)));
+    }
+
+    let mut simulation = simulation_of(3)?;
+    Scenario::SplitVote.setup(&mut simulation)?;
+    for peer_id in (1..=3).map(PeerId) {
+        let peer = simulation.peer(peer_id).unwrap();
+        assert!(peer.role().is_candidate());
+        assert_eq!(peer.current_term(), Term(1));
+        assert!(peer.buffered_peer_transmits().is_empty());
+    }
+
+    let mut simulation = simulation_of(3)?;
+    Scenario::PartitionAndHeal.setup(&mut simulation)?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+    assert_eq!(
+        simulation.peer(PeerId(2)).unwrap().log().len(),
+        simulation.peer(PeerId(1)).unwrap().log().len()
+    );
+    assert!(
+        simulation.peer(PeerId(3)).unwrap().log().len()
+            < simulation.peer(PeerId(1)).unwrap().log().len()
+    );
+    let transmits = simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits();
+    assert_eq!(transmits.len(), 1);
+    assert_eq!(transmits[0].peer_id(), PeerId(3));
+
+    let mut simulation = simulation_of(3)?;
+    Scenario::LogDivergenceRepair.setup(&mut simulation)?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_follower());
+    assert!(simulation.peer(PeerId(2)).unwrap().role().is_leader());
+    let diverged_index = simulation.peer(PeerId(1)).unwrap().log().last().unwrap().index();
+    assert_ne!(
+        simulation.peer(PeerId(1)).unwrap().log().entry(diverged_index).map(|entry| entry.term()),
+        simulation.peer(PeerId(2)).unwrap().log().entry(diverged_index).map(|entry| entry.term()),
+    );
+    let transmits = simulation.peer(PeerId(2)).unwrap().buffered_peer_transmits();
+    assert_eq!(transmits.len(), 1);
+    assert_eq!(transmits[0].peer_id(), PeerId(1));
+
+    Ok(())
+}
+
+#[test]
+fn scenarios_need_at_least_three_peers() -> anyhow::Result<()> {
+    let mut simulation = simulation_of(2)?;
+    assert!(Scenario::SplitVote.setup(&mut simulation).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn scenario_argument_selects_the_scenario() {
+    let args = Args::try_parse_from(["rafty-kvdb"]).unwrap();
+    assert_eq!(args.scenario, None);
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--scenario", "split-vote"]).unwrap();
+    assert_eq!(args.scenario, Some(Scenario::SplitVote));
+
+    let args = Args::try_parse_from(["rafty-kvdb", "--scenario", "log-divergence-repair"]).unwrap();
+    assert_eq!(args.scenario, Some(Scenario::LogDivergenceRepair));
+
+    assert!(Args::try_parse_from(["rafty-kvdb", "--scenario", "unknown"]).is_err());
+    assert!(Args::try_parse_from(["rafty-kvdb", "--scenario", "split-vote", "--inspect"]).is_err());
+}
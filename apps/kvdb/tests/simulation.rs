@@ -0,0 +1,1457 @@
+//! Simulation tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    rafty_simulator::*,
+    std::collections::{
+        BTreeSet,
+        VecDeque,
+    },
+};
+
+mod storage;
+use storage::Storage;
+
+fn simulation() -> anyhow::Result<Simulation<KeyValueDatabase<Storage>>> {
+    Simulation::new(Consistency::Strong, vec![Storage::default(); 3], 1)
+}
+
+#[test]
+fn validate_action_accepts_existing_transmits() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+
+    let action = Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) };
+    assert_eq!(simulation.validate_action(&action), Ok(()));
+    simulation.perform(action)?;
+
+    let action = Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    };
+    assert_eq!(simulation.validate_action(&action), Ok(()));
+
+    Ok(())
+}
+
+#[test]
+fn validate_action_rejects_nonexistent_transmits() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+
+    let action = Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(42) };
+    assert!(simulation.validate_action(&action).is_err());
+    assert!(simulation.perform(action).is_err());
+
+    let action = Action::TransmitPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![RequestId(0), RequestId(42)],
+    };
+    assert!(simulation.validate_action(&action).is_err());
+
+    let action = Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    };
+    assert!(simulation.validate_action(&action).is_err());
+
+    let action = Action::TimeoutElection { peer_id: PeerId(4) };
+    assert!(simulation.validate_action(&action).is_err());
+
+    // Validation doesn't change the simulation.
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().buffered_peer_transmits().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn elect_makes_peer_the_committed_leader() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+    simulation.elect(PeerId(3))?;
+
+    let leader = simulation.peer(PeerId(3)).unwrap();
+    assert!(leader.role().is_leader());
+    assert_eq!(leader.current_term(), Term(1));
+    assert_eq!(leader.commit_index(), LogIndex(1));
+    assert!(leader.can_serve_linearizable_read());
+    assert!(leader.buffered_peer_transmits().is_empty());
+
+    for peer_id in [1, 2, 4, 5].map(PeerId) {
+        let follower = simulation.peer(peer_id).unwrap();
+        assert_eq!(follower.current_term(), Term(1));
+        assert_eq!(follower.log().len(), 1);
+        assert_eq!(
+            follower.role(),
+            &Role::Follower(FollowerState::builder().leader_id(PeerId(3)).build()),
+        );
+        assert!(follower.buffered_peer_transmits().is_empty());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn elect_fails_when_another_peer_is_in_a_higher_term() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.peer_mut(PeerId(2)).unwrap().set_current_term(Term(5))?;
+
+    assert!(simulation.elect(PeerId(1)).is_err());
+
+    // Candidate is left untouched
+    let peer = simulation.peer(PeerId(1)).unwrap();
+    assert!(peer.role().is_follower());
+    assert_eq!(peer.current_term(), Term(0));
+    assert_eq!(peer.voted_for(), None);
+    assert!(peer.buffered_peer_transmits().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn apply_committed_captures_results_per_peer() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+
+    let append_entries_requests = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    for (follower_id, request_id) in append_entries_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: follower_id,
+            replied_peer_id_and_request_id: (PeerId(1), request_id),
+        })?;
+    }
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(2));
+
+    let outcome = simulation.perform(Action::ApplyCommitted { peer_id: None })?;
+    assert_eq!(
+        simulation.applied_results(PeerId(1)).unwrap(),
+        &[(LogIndex(1), CommandResult::Done), (LogIndex(2), CommandResult::Done)],
+    );
+    assert_eq!(
+        outcome.applied_results(),
+        &[
+            (PeerId(1), LogIndex(1), CommandResult::Done),
+            (PeerId(1), LogIndex(2), CommandResult::Done),
+            (PeerId(2), LogIndex(1), CommandResult::Done),
+            (PeerId(3), LogIndex(1), CommandResult::Done),
+        ],
+    );
+    // Followers learn about the new commit index with the next append entries request.
+    assert_eq!(
+        simulation.applied_results(PeerId(2)).unwrap(),
+        &[(LogIndex(1), CommandResult::Done)]
+    );
+
+    // Applying again doesn't apply anything new.
+    let outcome = simulation.perform(Action::ApplyCommitted { peer_id: Some(PeerId(1)) })?;
+    assert_eq!(simulation.applied_results(PeerId(1)).unwrap().len(), 2);
+    assert!(outcome.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn step_down_lets_another_peer_get_elected() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    assert!(simulation.validate_action(&Action::StepDown { peer_id: PeerId(1) }).is_err());
+    assert!(simulation.perform(Action::StepDown { peer_id: PeerId(1) }).is_err());
+
+    simulation.elect(PeerId(1))?;
+    simulation.perform(Action::StepDown { peer_id: PeerId(1) })?;
+    assert_eq!(
+        simulation.peer(PeerId(1)).unwrap().role(),
+        &Role::Follower(FollowerState::builder().leader_id(None).build()),
+    );
+    assert_eq!(
+        simulation.peer(PeerId(1)).unwrap().role_history().back(),
+        Some(
+            &RoleTransition::builder()
+                .term(1)
+                .from(RoleKind::Leader)
+                .to(RoleKind::Follower)
+                .build()
+        ),
+    );
+
+    simulation.elect(PeerId(2))?;
+    assert!(simulation.peer(PeerId(2)).unwrap().role().is_leader());
+    assert_eq!(
+        simulation.peer(PeerId(1)).unwrap().role(),
+        &Role::Follower(FollowerState::builder().leader_id(PeerId(2)).build()),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn forked_simulations_diverge_independently() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    let mut fork = simulation.clone_for_fork();
+    assert_eq!(fork.peer(PeerId(1)).unwrap().role(), simulation.peer(PeerId(1)).unwrap().role());
+    assert_eq!(fork.peer(PeerId(2)).unwrap().log(), simulation.peer(PeerId(2)).unwrap().log());
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+
+    fork.perform(Action::StepDown { peer_id: PeerId(1) })?;
+    fork.elect(PeerId(2))?;
+
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().current_term(), Term(1));
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().log().len(), 2);
+
+    assert!(fork.peer(PeerId(2)).unwrap().role().is_leader());
+    assert!(fork.peer(PeerId(1)).unwrap().role().is_follower());
+    assert_eq!(fork.peer(PeerId(1)).unwrap().current_term(), Term(2));
+    assert_eq!(fork.peer(PeerId(1)).unwrap().log().len(), 2);
+    assert!(fork
+        .peer(PeerId(1))
+        .unwrap()
+        .log()
+        .iter()
+        .all(|entry| entry.command() == &Command::NoOp));
+
+    Ok(())
+}
+
+#[test]
+fn quorum_status_tracks_replication_of_last_log_entry() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    let leader_id = PeerId(1);
+
+    assert_eq!(simulation.peer(leader_id).unwrap().quorum_status(), None);
+
+    simulation.elect(leader_id)?;
+    assert_eq!(simulation.peer(leader_id).unwrap().quorum_status(), Some((3, 2)));
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().quorum_status(), None);
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(leader_id),
+        request_id: None,
+        command: Command::Upsert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    assert_eq!(simulation.peer(leader_id).unwrap().quorum_status(), Some((1, 2)));
+
+    let request_id = simulation
+        .peer(leader_id)
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .find(|transmit| transmit.peer_id() == PeerId(2))
+        .map(|transmit| transmit.request_id())
+        .unwrap();
+    simulation.perform(Action::TransmitPeerRequest { peer_id: leader_id, request_id })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (leader_id, request_id),
+    })?;
+    assert_eq!(simulation.peer(leader_id).unwrap().quorum_status(), Some((2, 2)));
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_moves_logical_time_forward() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    assert_eq!(simulation.time(), 0);
+
+    simulation.perform(Action::AdvanceTime { ticks: 3 })?;
+    simulation.perform(Action::AdvanceTime { ticks: 2 })?;
+    assert_eq!(simulation.time(), 5);
+
+    assert!(simulation.validate_action(&Action::AdvanceTime { ticks: 0 }).is_err());
+    assert!(simulation.perform(Action::AdvanceTime { ticks: 0 }).is_err());
+    assert_eq!(simulation.time(), 5);
+
+    let fork = simulation.clone_for_fork();
+    simulation.perform(Action::AdvanceTime { ticks: 1 })?;
+    assert_eq!(fork.time(), 5);
+    assert_eq!(simulation.time(), 6);
+
+    Ok(())
+}
+
+#[test]
+fn check_detects_multiple_leaders_in_the_same_term() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?.enable_checks(vec![Storage::default(); 3])?;
+    simulation.elect(PeerId(1))?;
+
+    let leader_role = simulation.peer(PeerId(1)).unwrap().role().clone();
+    let leader_term = simulation.peer(PeerId(1)).unwrap().current_term();
+    simulation.peer_mut(PeerId(2)).unwrap().set_current_term(leader_term)?;
+    simulation.peer_mut(PeerId(2)).unwrap().set_role(leader_role);
+
+    let error = simulation.perform(Action::Check { updates: vec![] }).unwrap_err();
+    assert_eq!(error.to_string(), "Peer 1 and Peer 2 are both leaders of term 1");
+
+    Ok(())
+}
+
+#[test]
+fn explicit_request_ids_are_reproduced_across_replays() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let actions = || {
+        [
+            Action::SendCommand {
+                client_id: ClientId(1),
+                peer_id: Some(PeerId(1)),
+                request_id: Some(RequestId(7)),
+                command: Command::Upsert { key: "x".to_owned(), value: "1".to_owned() },
+            },
+            Action::SendQuery {
+                client_id: ClientId(1),
+                peer_id: Some(PeerId(2)),
+                request_id: Some(RequestId(3)),
+                query: Query::Entry { key: "x".to_owned() },
+            },
+            Action::SendQuery {
+                client_id: ClientId(1),
+                peer_id: Some(PeerId(3)),
+                request_id: None,
+                query: Query::Length,
+            },
+        ]
+    };
+    let request_ids = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        simulation
+            .client(ClientId(1))
+            .unwrap()
+            .buffered_client_transmits()
+            .iter()
+            .map(|transmit| transmit.request_id())
+            .collect::<Vec<_>>()
+    };
+
+    let mut first = simulation()?;
+    first.run(actions().into_iter())?;
+
+    let mut second = simulation()?;
+    second.run(actions().into_iter())?;
+
+    assert_eq!(request_ids(&first), vec![RequestId(7), RequestId(3), RequestId(0)]);
+    assert_eq!(request_ids(&first), request_ids(&second));
+
+    let reused = Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: None,
+        request_id: Some(RequestId(3)),
+        command: Command::Clear { key: "x".to_owned() },
+    };
+    assert!(first.validate_action(&reused).is_err());
+    assert!(first.perform(reused).is_err());
+    assert_eq!(
+        first.client_mut(ClientId(1)).unwrap().command_with_request_id(
+            RequestId(7),
+            Command::Clear { key: "x".to_owned() },
+            None
+        ),
+        Err(ClientError::RequestIdAlreadyUsed { request_id: RequestId(7) }),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn perform_reports_the_outcome_of_a_vote_granting_leadership() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+
+    let outcome = simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    assert_eq!(
+        outcome.role_changes(),
+        &[(
+            PeerId(1),
+            RoleTransition::builder()
+                .term(1)
+                .from(RoleKind::Follower)
+                .to(RoleKind::Candidate)
+                .build(),
+        )],
+    );
+    assert_eq!(outcome.created_peer_transmits().len(), 2);
+    assert!(outcome.consumed_peer_transmits().is_empty());
+
+    let outcome = simulation.perform(Action::TransmitPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![RequestId(0), RequestId(1)],
+    })?;
+    assert!(outcome.role_changes().is_empty());
+    assert_eq!(outcome.consumed_peer_transmits().len(), 2);
+    assert_eq!(
+        outcome
+            .created_peer_transmits()
+            .iter()
+            .map(|(sender, transmit)| (*sender, transmit.peer_id(), transmit.message().is_reply()))
+            .collect::<Vec<_>>(),
+        vec![(PeerId(2), PeerId(1), true), (PeerId(3), PeerId(1), true)],
+    );
+
+    let outcome = simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+    })?;
+    assert_eq!(
+        outcome.role_changes(),
+        &[(
+            PeerId(1),
+            RoleTransition::builder()
+                .term(1)
+                .from(RoleKind::Candidate)
+                .to(RoleKind::Leader)
+                .build(),
+        )],
+    );
+    assert_eq!(
+        outcome
+            .consumed_peer_transmits()
+            .iter()
+            .map(|(sender, transmit)| (*sender, transmit.peer_id(), transmit.request_id()))
+            .collect::<Vec<_>>(),
+        vec![(PeerId(2), PeerId(1), RequestId(0))],
+    );
+    assert_eq!(
+        outcome
+            .created_peer_transmits()
+            .iter()
+            .map(|(sender, transmit)| {
+                (
+                    *sender,
+                    transmit.peer_id(),
+                    matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_)),
+                )
+            })
+            .collect::<Vec<_>>(),
+        vec![(PeerId(1), PeerId(2), true), (PeerId(1), PeerId(3), true)],
+    );
+    assert!(outcome.created_client_transmits().is_empty());
+    assert!(outcome.consumed_client_transmits().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn burst_of_commands_respects_the_pipelining_limit() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+    simulation.peer_mut(PeerId(1)).unwrap().set_max_inflight_per_peer(Some(1));
+
+    for (request_id, key) in ["a", "b", "c"].into_iter().enumerate() {
+        simulation.perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(1)),
+            request_id: None,
+            command: Command::Insert { key: key.to_owned(), value: "1".to_owned() },
+        })?;
+        simulation.perform(Action::TransmitClientRequest {
+            client_id: ClientId(1),
+            request_id: RequestId(request_id),
+        })?;
+    }
+
+    let in_flight_to = |simulation: &Simulation<KeyValueDatabase<Storage>>, peer_id| {
+        simulation
+            .peer(PeerId(1))
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| transmit.peer_id() == peer_id && transmit.message().is_request())
+            .map(|transmit| transmit.request_id())
+            .collect::<Vec<_>>()
+    };
+    for follower_id in [PeerId(2), PeerId(3)] {
+        assert_eq!(in_flight_to(&simulation, follower_id).len(), 1);
+        assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(follower_id), 1);
+    }
+
+    // Acknowledging the only request in flight sends the held back entries at once
+    let request_id = in_flight_to(&simulation, PeerId(2))[0];
+    simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), request_id),
+    })?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().log().len(), 2);
+
+    let held_back_request_ids = in_flight_to(&simulation, PeerId(2));
+    assert_eq!(held_back_request_ids.len(), 1);
+    let request_id = held_back_request_ids[0];
+    simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), request_id),
+    })?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().log().len(), 4);
+
+    assert!(in_flight_to(&simulation, PeerId(2)).is_empty());
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 0);
+
+    Ok(())
+}
+
+#[test]
+fn heartbeat_timeouts_expire_only_requests_in_flight_for_a_whole_interval() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+    simulation.peer_mut(PeerId(1)).unwrap().set_max_inflight_per_peer(Some(1));
+
+    let latest_request_to = |simulation: &Simulation<KeyValueDatabase<Storage>>, peer_id| {
+        simulation
+            .peer(PeerId(1))
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| transmit.peer_id() == peer_id && transmit.message().is_request())
+            .map(|transmit| transmit.request_id())
+            .max()
+            .unwrap()
+    };
+    let acknowledge = |simulation: &mut Simulation<KeyValueDatabase<Storage>>, request_id| {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: PeerId(2),
+            replied_peer_id_and_request_id: (PeerId(1), request_id),
+        })
+    };
+
+    // Command is acknowledged by peer 2 but not by peer 3
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "a".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    let request_id = latest_request_to(&simulation, PeerId(2));
+    acknowledge(&mut simulation, request_id)?;
+    let lost_request_id = latest_request_to(&simulation, PeerId(3));
+
+    // Requests in flight are not considered lost at the first heartbeat timeout
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 1);
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(3)), 2);
+
+    // Command is considered lost at the next one, unlike the heartbeats sent in between
+    let heartbeat_request_id = latest_request_to(&simulation, PeerId(2));
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 2);
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(3)), 2);
+
+    // Lost requests are forgotten
+    let Role::Leader(leader_state) = simulation.peer(PeerId(1)).unwrap().role() else {
+        panic!("expected Peer 1 to be the leader");
+    };
+    assert!(!leader_state.append_entries_requests().contains_key(&lost_request_id));
+    assert!(leader_state.append_entries_requests().contains_key(&heartbeat_request_id));
+
+    acknowledge(&mut simulation, heartbeat_request_id)?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 1);
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(3)), 2);
+
+    Ok(())
+}
+
+#[test]
+fn check_names_the_first_differing_field() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?.enable_checks(vec![Storage::default(); 3])?;
+    simulation.elect(PeerId(1))?;
+
+    let leader = simulation.peer(PeerId(1)).unwrap();
+    let expected_leader = Update::peer(1)
+        .set_term(leader.current_term())
+        .set_voted_for(leader.voted_for())
+        .set_log(leader.log().iter().cloned().collect())
+        .set_commit_index(leader.commit_index())
+        .set_role(Role::Leader(
+            LeaderState::builder()
+                .next_index([(PeerId(2), LogIndex(2)), (PeerId(3), LogIndex(2))])
+                .match_index([
+                    (PeerId(1), LogIndex(1)),
+                    (PeerId(2), LogIndex(1)),
+                    (PeerId(3), LogIndex(0)),
+                ])
+                .initial_noop_committed(true)
+                .build(),
+        ));
+
+    let error = simulation.perform(Action::Check { updates: vec![expected_leader] }).unwrap_err();
+    assert_eq!(
+        error.to_string().trim(),
+        "Role of Peer 1 differs at `Leader > LeaderState > match_index > PeerId(3)`\n\
+        Expected: LogIndex(0)\n\
+        Actual:   LogIndex(1)",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn steady_heartbeats_prevent_elections() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    const ELECTION_TIMEOUT: usize = 15;
+    const HEARTBEAT_INTERVAL: usize = 5;
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    let followers = [PeerId(2), PeerId(3)];
+    let mut election_timer_started_at = followers.map(|_| simulation.time());
+
+    for _ in 0..20 {
+        let mut outcome = simulation.perform(Action::AdvanceTime { ticks: HEARTBEAT_INTERVAL })?;
+        assert!(outcome.role_changes().is_empty());
+
+        simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+        let request_ids = simulation
+            .peer(PeerId(1))
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .map(|transmit| transmit.request_id())
+            .collect();
+        outcome =
+            simulation.perform(Action::TransmitPeerRequests { peer_id: PeerId(1), request_ids })?;
+        assert!(outcome.role_changes().is_empty());
+
+        for (follower, started_at) in followers.iter().zip(&mut election_timer_started_at) {
+            let replied_peer_ids_and_request_ids = simulation
+                .peer(*follower)
+                .unwrap()
+                .buffered_peer_transmits()
+                .iter()
+                .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+                .collect();
+            simulation.perform(Action::TransmitPeerReplies {
+                peer_id: *follower,
+                replied_peer_ids_and_request_ids,
+            })?;
+
+            if let Some(contact) = simulation.peer(*follower).unwrap().last_valid_leader_contact() {
+                *started_at = contact;
+            }
+            assert_eq!(*started_at, simulation.time());
+
+            if simulation.time() - *started_at >= ELECTION_TIMEOUT {
+                simulation.perform(Action::TimeoutElection { peer_id: *follower })?;
+            }
+        }
+    }
+
+    for peer_id in [PeerId(1), PeerId(2), PeerId(3)] {
+        assert_eq!(simulation.peer(peer_id).unwrap().current_term(), Term(1));
+    }
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+
+    Ok(())
+}
+
+#[test]
+fn check_compares_clusters_set_by_updates() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?.enable_checks(vec![Storage::default(); 3])?;
+
+    let shrunk_cluster = Cluster::from([PeerId(1), PeerId(2)].into_iter().collect::<BTreeSet<_>>());
+    let error = simulation
+        .perform(Action::Check {
+            updates: vec![Update::peer(1).set_cluster(shrunk_cluster.clone())],
+        })
+        .unwrap_err();
+    assert!(error.to_string().contains("Cluster of Peer 1 differs"));
+
+    simulation.peer_mut(PeerId(1)).unwrap().set_cluster(shrunk_cluster.clone());
+    simulation.perform(Action::Check { updates: vec![] })?;
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().cluster(), &shrunk_cluster);
+
+    Ok(())
+}
+
+#[test]
+fn export_dot_describes_peers_and_buffered_transmits() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation
+        .perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id: RequestId(0) })?;
+
+    let dot = simulation.export_dot();
+    assert!(dot.starts_with("digraph Simulation {\n"));
+    assert!(dot.contains(r#"peer1 [label="Peer 1\nCandidate\nterm=1\ncommit=0"];"#));
+    assert!(dot.contains(r#"peer2 [label="Peer 2\nFollower\nterm=1\ncommit=0"];"#));
+    assert!(dot.contains(r#"peer1 -> peer3 [label="RequestVoteRequest #1"];"#));
+    assert!(dot.contains(r#"peer2 -> peer1 [label="RequestVoteReply #0"];"#));
+    assert!(!dot.contains("peer1 -> peer2"));
+
+    Ok(())
+}
+
+#[test]
+fn client_finds_the_leader_before_commanding() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(2))?;
+
+    simulation.perform(Action::FindLeader { client_id: ClientId(1) })?;
+    let request_ids = simulation
+        .client(ClientId(1))
+        .unwrap()
+        .buffered_client_transmits()
+        .iter()
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+    assert_eq!(request_ids.len(), 3);
+
+    for request_id in request_ids {
+        simulation.perform(Action::TransmitClientRequest { client_id: ClientId(1), request_id })?;
+    }
+    for peer_id in [PeerId(1), PeerId(2), PeerId(3)] {
+        let replied_client_id_and_request_id = simulation
+            .peer(peer_id)
+            .unwrap()
+            .buffered_client_transmits()
+            .iter()
+            .map(|transmit| {
+                assert_eq!(
+                    transmit.message(),
+                    &ClientMessage::from(FindLeaderReply::builder().leader_id(PeerId(2)).build()),
+                );
+                (transmit.client_id(), transmit.request_id())
+            })
+            .next()
+            .unwrap();
+        simulation
+            .perform(Action::TransmitClientReply { peer_id, replied_client_id_and_request_id })?;
+    }
+    assert_eq!(simulation.client(ClientId(1)).unwrap().leader(), Some(PeerId(2)));
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: None,
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    let transmit =
+        simulation.client(ClientId(1)).unwrap().buffered_client_transmits().front().unwrap();
+    assert_eq!(transmit.peer_id(), PeerId(2));
+
+    let request_id = transmit.request_id();
+    simulation.perform(Action::TransmitClientRequest { client_id: ClientId(1), request_id })?;
+    assert!(simulation.peer(PeerId(2)).unwrap().log().iter().any(|entry| {
+        entry.command() == &Command::Insert { key: "x".to_owned(), value: "1".to_owned() }
+    }));
+
+    Ok(())
+}
+
+#[test]
+fn reply_to_superseded_append_entries_request_is_ignored() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    for (request_id, key) in ["a", "b"].into_iter().enumerate() {
+        simulation.perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(1)),
+            request_id: None,
+            command: Command::Insert { key: key.to_owned(), value: "1".to_owned() },
+        })?;
+        simulation.perform(Action::TransmitClientRequest {
+            client_id: ClientId(1),
+            request_id: RequestId(request_id),
+        })?;
+    }
+
+    let request_ids = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .filter(|transmit| transmit.peer_id() == PeerId(2) && transmit.message().is_request())
+        .map(|transmit| transmit.request_id())
+        .collect::<Vec<_>>();
+    let [older_request_id, newer_request_id] = request_ids[..] else {
+        panic!("expected two requests in flight to peer 2, got {request_ids:?}");
+    };
+    simulation.perform(Action::TransmitPeerRequests {
+        peer_id: PeerId(1),
+        request_ids: vec![older_request_id, newer_request_id],
+    })?;
+
+    // Newer request is acknowledged first
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), newer_request_id),
+    })?;
+    let leader_state = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        match simulation.peer(PeerId(1)).unwrap().role() {
+            Role::Leader(leader_state) => {
+                (leader_state.next_index()[&PeerId(2)], leader_state.match_index()[&PeerId(2)])
+            },
+            role => panic!("expected a leader, got {role:?}"),
+        }
+    };
+    assert_eq!(leader_state(&simulation), (LogIndex(4), LogIndex(3)));
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 0);
+
+    // Reply to the superseded request doesn't move the indices back
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), older_request_id),
+    })?;
+    assert_eq!(leader_state(&simulation), (LogIndex(4), LogIndex(3)));
+
+    Ok(())
+}
+
+#[test]
+fn initial_append_entries_request_is_superseded_by_a_heartbeat() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+    simulation.perform(Action::GrantSimulatedVote { peer_id: PeerId(1) })?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+
+    let latest_request_to_peer_2 = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        simulation
+            .peer(PeerId(1))
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| {
+                transmit.peer_id() == PeerId(2)
+                    && matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_))
+            })
+            .map(|transmit| transmit.request_id())
+            .max()
+            .unwrap()
+    };
+
+    // Initial no-op entry is appended by peer 2, but its reply is delayed
+    let no_op_request_id = latest_request_to_peer_2(&simulation);
+    simulation.perform(Action::TransmitPeerRequest {
+        peer_id: PeerId(1),
+        request_id: no_op_request_id,
+    })?;
+
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    let heartbeat_request_id = latest_request_to_peer_2(&simulation);
+    simulation.perform(Action::TransmitPeerRequest {
+        peer_id: PeerId(1),
+        request_id: heartbeat_request_id,
+    })?;
+    simulation.perform(Action::TransmitPeerReply {
+        peer_id: PeerId(2),
+        replied_peer_id_and_request_id: (PeerId(1), heartbeat_request_id),
+    })?;
+
+    let Role::Leader(leader_state) = simulation.peer(PeerId(1)).unwrap().role() else {
+        panic!("expected Peer 1 to be the leader");
+    };
+    assert!(!leader_state.append_entries_requests().contains_key(&no_op_request_id));
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().inflight_append_entries(PeerId(2)), 0);
+
+    Ok(())
+}
+
+#[test]
+fn reordered_append_entries_request_does_not_move_the_commit_index_back() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+    simulation.elect(PeerId(1))?;
+
+    let requests = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        simulation
+            .peer(PeerId(1))
+            .unwrap()
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_)))
+            .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+            .collect::<Vec<_>>()
+    };
+
+    let latest_request_to_peer_2 = |simulation: &Simulation<KeyValueDatabase<Storage>>| {
+        requests(simulation)
+            .into_iter()
+            .filter(|(peer_id, _)| *peer_id == PeerId(2))
+            .map(|(_, request_id)| request_id)
+            .max()
+            .unwrap()
+    };
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    let command_requests = requests(&simulation);
+
+    // Heartbeat sent before the command is committed is delayed
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    let delayed_request_id = latest_request_to_peer_2(&simulation);
+
+    for (peer_id, request_id) in command_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id,
+            replied_peer_id_and_request_id: (PeerId(1), request_id),
+        })?;
+    }
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(2));
+
+    // Heartbeat carrying the new commit index overtakes the delayed one
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    simulation.perform(Action::TransmitPeerRequest {
+        peer_id: PeerId(1),
+        request_id: latest_request_to_peer_2(&simulation),
+    })?;
+    simulation.perform(Action::ApplyCommitted { peer_id: Some(PeerId(2)) })?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().commit_index(), LogIndex(2));
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().last_applied(), LogIndex(2));
+
+    simulation.perform(Action::TransmitPeerRequest {
+        peer_id: PeerId(1),
+        request_id: delayed_request_id,
+    })?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().commit_index(), LogIndex(2));
+
+    Ok(())
+}
+
+#[test]
+fn transmit_all_pending_drives_the_cluster_to_quiescence() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    for key in ["a", "b"] {
+        simulation.perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(1)),
+            request_id: None,
+            command: Command::Insert { key: key.to_owned(), value: "1".to_owned() },
+        })?;
+    }
+
+    let steps = simulation.transmit_all_pending(true, 100)?;
+    assert!(steps > 0);
+    assert_eq!(simulation.transmit_all_pending(true, 100)?, 0);
+
+    for peer_id in [PeerId(1), PeerId(2), PeerId(3)] {
+        let peer = simulation.peer(peer_id).unwrap();
+        assert_eq!(peer.log(), simulation.peer(PeerId(1)).unwrap().log());
+        assert!(peer.buffered_peer_transmits().is_empty());
+        assert!(peer.buffered_client_transmits().is_empty());
+    }
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(3));
+    assert!(simulation.client(ClientId(1)).unwrap().buffered_client_transmits().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn transmit_all_pending_stops_at_the_step_cap() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(1) })?;
+
+    assert!(simulation.transmit_all_pending(false, 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn actions_referencing_nonexistent_peers_or_clients_are_rejected() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+
+    let error = simulation.perform(Action::TimeoutElection { peer_id: PeerId(99) }).unwrap_err();
+    assert_eq!(error.to_string(), "Peer 99 doesn't exist");
+
+    let error = simulation
+        .perform(Action::SendCommand {
+            client_id: ClientId(1),
+            peer_id: Some(PeerId(99)),
+            request_id: None,
+            command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+        })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Peer 99 doesn't exist");
+
+    let error = simulation.perform(Action::FindLeader { client_id: ClientId(99) }).unwrap_err();
+    assert_eq!(error.to_string(), "Client 99 doesn't exist");
+
+    let error = simulation
+        .perform(Action::TransmitPeerReply {
+            peer_id: PeerId(1),
+            replied_peer_id_and_request_id: (PeerId(99), RequestId(0)),
+        })
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Peer 99 doesn't exist");
+
+    assert!(simulation.peer(PeerId(0)).is_none());
+    assert!(simulation.peer_mut(PeerId(99)).is_none());
+    assert!(simulation.applied_results(PeerId(99)).is_none());
+    assert!(simulation.client(ClientId(0)).is_none());
+    assert!(simulation.client_mut(ClientId(99)).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn plan_reports_legality_without_performing_actions() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.set_step_log_level(Some(log::Level::Info));
+
+    let election = || {
+        vec![
+            Action::TimeoutElection { peer_id: PeerId(1) },
+            Action::TransmitPeerRequests {
+                peer_id: PeerId(1),
+                request_ids: vec![RequestId(0), RequestId(1)],
+            },
+            Action::TransmitPeerReply {
+                peer_id: PeerId(2),
+                replied_peer_id_and_request_id: (PeerId(1), RequestId(0)),
+            },
+            Action::TransmitPeerReply {
+                peer_id: PeerId(3),
+                replied_peer_id_and_request_id: (PeerId(1), RequestId(1)),
+            },
+            Action::TransmitPeerRequests {
+                peer_id: PeerId(1),
+                request_ids: vec![RequestId(2), RequestId(3)],
+            },
+            Action::TransmitPeerReply {
+                peer_id: PeerId(2),
+                replied_peer_id_and_request_id: (PeerId(1), RequestId(2)),
+            },
+            Action::TransmitPeerReply {
+                peer_id: PeerId(3),
+                replied_peer_id_and_request_id: (PeerId(1), RequestId(3)),
+            },
+        ]
+    };
+
+    let plan = simulation.plan(election().into_iter());
+    assert_eq!(plan.len(), 7);
+    assert!(plan.iter().all(|planned_action| planned_action.is_legal()));
+    assert_eq!(plan[0].name(), "TimeoutElection");
+
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_follower());
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().current_term(), Term(0));
+
+    let plan =
+        simulation.plan(election().into_iter().chain([Action::StepDown { peer_id: PeerId(2) }]));
+    assert_eq!(plan[7].index(), 7);
+    assert_eq!(plan[7].legality(), &Err("Peer 2 is not the leader".to_owned()));
+
+    simulation.run(election().into_iter())?;
+    assert!(simulation.peer(PeerId(1)).unwrap().role().is_leader());
+
+    Ok(())
+}
+
+#[test]
+fn check_can_ignore_request_ids_of_buffered_transmits() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?.enable_checks(vec![Storage::default(); 3])?;
+
+    let transmit = |request_id: usize| {
+        PeerTransmit::builder()
+            .peer_id(PeerId(2))
+            .request_id(RequestId(request_id))
+            .message(
+                AppendEntriesRequest::builder()
+                    .term(0)
+                    .leader_id(PeerId(1))
+                    .prev_log_index(0)
+                    .prev_log_term(0)
+                    .entries([])
+                    .leader_commit(0)
+                    .build(),
+            )
+            .build()
+    };
+    simulation
+        .peer_mut(PeerId(1))
+        .unwrap()
+        .set_buffered_peer_transmits(VecDeque::from([transmit(5)]));
+
+    let error = simulation
+        .perform(Action::Check {
+            updates: vec![Update::peer(1).set_buffered_peer_transmits([transmit(7)].into_iter())],
+        })
+        .unwrap_err();
+    assert!(error.to_string().contains("Buffered Peer Transmits of Peer 1 differs"));
+
+    simulation.set_check_request_ids(false);
+    simulation.perform(Action::Check { updates: vec![] })?;
+
+    Ok(())
+}
+
+#[test]
+fn verify_machines_detects_divergent_machines() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.transmit_all_pending(true, 100)?;
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    simulation.transmit_all_pending(true, 100)?;
+    simulation.perform(Action::ApplyCommitted { peer_id: None })?;
+
+    // Consistent cluster passes
+    for peer_id in (1..=3).map(PeerId) {
+        assert_eq!(simulation.peer(peer_id).unwrap().last_applied(), LogIndex(2));
+    }
+    simulation.verify_machines()?;
+
+    // Divergent machine fails
+    simulation
+        .peer_mut(PeerId(2))
+        .unwrap()
+        .set_machine(Machine([("x".to_owned(), "2".to_owned())].into_iter().collect()));
+    let error = simulation.verify_machines().unwrap_err();
+    assert!(error.to_string().contains("Machines of Peer 1 and Peer 2 diverge at index 2"));
+
+    Ok(())
+}
+
+#[test]
+fn restarted_peer_recovers_its_corrupted_log_from_the_leader() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.transmit_all_pending(true, 100)?;
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().log().len(), 2);
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().voted_for(), Some(PeerId(1)));
+
+    simulation.perform(Action::CorruptStorage {
+        peer_id: PeerId(3),
+        kind: StorageCorruption::TruncateLog { down_to: LogIndex(2) },
+    })?;
+    simulation.perform(Action::CorruptStorage {
+        peer_id: PeerId(3),
+        kind: StorageCorruption::ClearVotedFor,
+    })?;
+    simulation.perform(Action::RestartPeer { peer_id: PeerId(3) })?;
+
+    let restarted_peer = simulation.peer(PeerId(3)).unwrap();
+    assert_eq!(restarted_peer.log().len(), 1);
+    assert_eq!(restarted_peer.voted_for(), None);
+    assert_eq!(restarted_peer.current_term(), simulation.peer(PeerId(1)).unwrap().current_term());
+    assert_eq!(restarted_peer.commit_index(), LogIndex(0));
+    assert!(restarted_peer.role().is_follower());
+
+    // Next heartbeat of the leader replicates the lost entries again.
+    simulation.perform(Action::TimeoutHeartbeat { peer_id: PeerId(1) })?;
+    simulation.transmit_all_pending(true, 100)?;
+    assert_eq!(
+        simulation.peer(PeerId(3)).unwrap().log(),
+        simulation.peer(PeerId(1)).unwrap().log()
+    );
+
+    assert!(simulation.validate_action(&Action::RestartPeer { peer_id: PeerId(4) }).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn peer_role_counts_summarize_an_election() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = Simulation::<KeyValueDatabase<Storage>>::new(
+        Consistency::Strong,
+        vec![Storage::default(); 5],
+        1,
+    )?;
+    assert_eq!(simulation.peer_role_counts(), (5, 0, 0));
+
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+    assert_eq!(simulation.peer_role_counts(), (4, 1, 0));
+
+    simulation.transmit_all_pending(false, 100)?;
+    assert_eq!(simulation.peer_role_counts(), (4, 0, 1));
+
+    Ok(())
+}
+
+#[test]
+fn new_leader_commits_inherited_entries_only_with_an_entry_of_its_own_term() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.elect(PeerId(1))?;
+
+    // Peer 1 replicates a command but only learns about peer 2 appending it before stepping down.
+    let inherited_command = Command::Insert { key: "x".to_owned(), value: "1".to_owned() };
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: inherited_command.clone(),
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+    let append_entries_requests = simulation
+        .peer(PeerId(1))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    for (follower_id, request_id) in append_entries_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(1), request_id })?;
+        if follower_id == PeerId(2) {
+            simulation.perform(Action::TransmitPeerReply {
+                peer_id: follower_id,
+                replied_peer_id_and_request_id: (PeerId(1), request_id),
+            })?;
+        } else {
+            simulation.perform(Action::DropPeerReply {
+                peer_id: follower_id,
+                replied_peer_id_and_request_id: (PeerId(1), request_id),
+            })?;
+        }
+    }
+    assert_eq!(simulation.peer(PeerId(1)).unwrap().commit_index(), LogIndex(1));
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().log().len(), 2);
+    assert_eq!(simulation.peer(PeerId(3)).unwrap().log().len(), 2);
+    simulation.perform(Action::StepDown { peer_id: PeerId(1) })?;
+    simulation.perform(Action::AdvanceTime {
+        ticks: Peer::<KeyValueDatabase<Storage>>::LEASE_DURATION,
+    })?;
+
+    // Peer 2 gets elected without replicating its initial no-op entry.
+    simulation.perform(Action::TimeoutElection { peer_id: PeerId(2) })?;
+    let vote_requests = simulation
+        .peer(PeerId(2))
+        .unwrap()
+        .buffered_peer_transmits()
+        .iter()
+        .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+        .collect::<Vec<_>>();
+    for (voter_id, request_id) in vote_requests {
+        simulation.perform(Action::TransmitPeerRequest { peer_id: PeerId(2), request_id })?;
+        simulation.perform(Action::TransmitPeerReply {
+            peer_id: voter_id,
+            replied_peer_id_and_request_id: (PeerId(2), request_id),
+        })?;
+    }
+    assert!(simulation.peer(PeerId(2)).unwrap().role().is_leader());
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().current_term(), Term(2));
+
+    // Replicas of the inherited entry alone don't commit it in the new term.
+    let mut fork = simulation.clone_for_fork();
+    let inherited_entry = fork.peer(PeerId(2)).unwrap().log().entry(LogIndex(2)).unwrap().clone();
+    let heartbeat_request = AppendEntriesRequest::builder()
+        .term(2)
+        .leader_id(PeerId(2))
+        .prev_log_index(1)
+        .prev_log_term(1)
+        .entries([inherited_entry])
+        .leader_commit(1)
+        .build();
+    fork.peer_mut(PeerId(2)).unwrap().set_role(Role::Leader(
+        LeaderState::builder()
+            .next_index([(PeerId(1), LogIndex(3)), (PeerId(3), LogIndex(3))])
+            .match_index([(PeerId(1), LogIndex(0)), (PeerId(3), LogIndex(0))])
+            .append_entries_requests([
+                (RequestId(100), heartbeat_request.clone()),
+                (RequestId(101), heartbeat_request),
+            ])
+            .build(),
+    ));
+    for (follower_id, request_id) in [(PeerId(1), RequestId(100)), (PeerId(3), RequestId(101))] {
+        fork.peer_mut(follower_id).unwrap().set_buffered_peer_transmits(
+            [PeerTransmit::builder()
+                .peer_id(2)
+                .request_id(request_id)
+                .message(AppendEntriesReply::builder().term(2).success(true).build())
+                .build()]
+            .into(),
+        );
+        fork.perform(Action::TransmitPeerReply {
+            peer_id: follower_id,
+            replied_peer_id_and_request_id: (PeerId(2), request_id),
+        })?;
+    }
+    let Role::Leader(leader_state) = fork.peer(PeerId(2)).unwrap().role() else { unreachable!() };
+    assert_eq!(leader_state.match_index()[&PeerId(1)], LogIndex(2));
+    assert_eq!(leader_state.match_index()[&PeerId(3)], LogIndex(2));
+    assert_eq!(fork.peer(PeerId(2)).unwrap().commit_index(), LogIndex(1));
+
+    // New commands are appended after the inherited entry with the current term.
+    let new_command = Command::Insert { key: "y".to_owned(), value: "2".to_owned() };
+    simulation.perform(Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(2)),
+        request_id: None,
+        command: new_command.clone(),
+    })?;
+    simulation.perform(Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(1),
+    })?;
+    let log = simulation.peer(PeerId(2)).unwrap().log();
+    assert_eq!(
+        log.iter().map(|entry| (entry.index(), entry.term())).collect::<Vec<_>>(),
+        [
+            (LogIndex(1), Term(1)),
+            (LogIndex(2), Term(1)),
+            (LogIndex(3), Term(2)),
+            (LogIndex(4), Term(2)),
+        ],
+    );
+    assert_eq!(log.entry(LogIndex(2)).unwrap().command(), &inherited_command);
+    assert_eq!(log.entry(LogIndex(3)).unwrap().command(), &Command::NoOp);
+    assert_eq!(log.entry(LogIndex(4)).unwrap().command(), &new_command);
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().commit_index(), LogIndex(1));
+
+    // Replicating the entries of the current term commits the inherited entry alongside them.
+    simulation.transmit_all_pending(false, 100)?;
+    for peer_id in [PeerId(1), PeerId(3)] {
+        assert_eq!(
+            simulation.peer(peer_id).unwrap().log(),
+            simulation.peer(PeerId(2)).unwrap().log()
+        );
+    }
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().commit_index(), LogIndex(4));
+
+    Ok(())
+}
+
+#[test]
+fn perform_fails_fast_when_a_peer_applies_past_its_commit_index() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut simulation = simulation()?;
+    simulation.set_check_invariants(true);
+    simulation.elect(PeerId(1))?;
+    assert_eq!(simulation.peer(PeerId(2)).unwrap().commit_index(), LogIndex(0));
+
+    // A bug advances the last applied of peer 2 past its commit index
+    simulation.peer_mut(PeerId(2)).unwrap().set_last_applied(LogIndex(1));
+
+    let error = simulation.perform(Action::AdvanceTime { ticks: 1 }).unwrap_err().to_string();
+    assert!(error.contains("Peer 2 violates `last_applied <= commit_index <= last_log_index`"));
+    assert!(error.contains("after AdvanceTime"));
+    assert!(error.contains(&simulation.peer(PeerId(2)).unwrap().debug_summary()));
+
+    simulation.set_check_invariants(false);
+    simulation.perform(Action::AdvanceTime { ticks: 1 })?;
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+//! Snapshot tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+    std::collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+};
+
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+#[test]
+fn snapshot_encoding_round_trips() -> anyhow::Result<()> {
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(7)
+        .last_included_term(3)
+        .cluster(Cluster::from(BTreeSet::from([PeerId(1), PeerId(2), PeerId(4)])))
+        .machine(Machine(BTreeMap::from([
+            ("a".to_owned(), "1".to_owned()),
+            ("key with spaces".to_owned(), "ünïcödé".to_owned()),
+            ("empty".to_owned(), "".to_owned()),
+        ])))
+        .build();
+
+    let bytes = Application::encode_snapshot(&snapshot);
+    assert_eq!(Application::decode_snapshot(&bytes)?, snapshot);
+
+    let default_snapshot = Snapshot::<Application>::default();
+    let bytes = Application::encode_snapshot(&default_snapshot);
+    assert_eq!(bytes.len(), 37);
+    assert_eq!(Application::decode_snapshot(&bytes)?, default_snapshot);
+
+    Ok(())
+}
+
+#[test]
+fn snapshot_decoding_rejects_malformed_bytes() {
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(1)
+        .last_included_term(1)
+        .machine(Machine(BTreeMap::from([("a".to_owned(), "1".to_owned())])))
+        .build();
+    let bytes = Application::encode_snapshot(&snapshot);
+
+    assert!(Application::decode_snapshot(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Application::decode_snapshot(&[bytes.as_slice(), &[0]].concat()).is_err());
+    assert!(Application::decode_snapshot(&[]).is_err());
+
+    let mut unsupported_version = bytes.clone();
+    unsupported_version[Application::SNAPSHOT_MAGIC.len()] += 1;
+    assert!(Application::decode_snapshot(&unsupported_version).is_err());
+    assert!(Application::decode_snapshot(&bytes[Application::SNAPSHOT_MAGIC.len()..]).is_err());
+}
+
+#[test]
+fn peer_restores_cluster_from_snapshot() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let snapshot_cluster = Cluster::from(BTreeSet::from([PeerId(1), PeerId(2), PeerId(3)]));
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(4)
+        .last_included_term(2)
+        .cluster(snapshot_cluster.clone())
+        .machine(Machine(BTreeMap::from([("a".to_owned(), "1".to_owned())])))
+        .build();
+
+    let mut storage = Storage::default();
+    storage.install_snapshot(Application::decode_snapshot(&Application::encode_snapshot(
+        &snapshot,
+    ))?)?;
+
+    let initial_cluster = Cluster::from((1..=5).map(PeerId).collect::<BTreeSet<_>>());
+    let peer =
+        Peer::<Application>::new(PeerId(1), initial_cluster.clone(), Consistency::Strong, storage);
+    assert_eq!(peer.cluster(), &snapshot_cluster);
+    assert_eq!(peer.majority(), 2);
+    assert_eq!(peer.commit_index(), LogIndex(4));
+
+    let peer = Peer::<Application>::new(
+        PeerId(1),
+        initial_cluster.clone(),
+        Consistency::Strong,
+        Storage::default(),
+    );
+    assert_eq!(peer.cluster(), &initial_cluster);
+
+    Ok(())
+}
+
+#[test]
+fn snapshot_metadata_is_used_when_the_log_is_empty() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(4)
+        .last_included_term(2)
+        .machine(Machine(BTreeMap::from([("a".to_owned(), "1".to_owned())])))
+        .build();
+
+    let mut storage = Storage::default();
+    storage.install_snapshot(snapshot)?;
+    assert_eq!(storage.snapshot_metadata(), (LogIndex(4), Term(2)));
+
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<BTreeSet<_>>());
+    let mut peer = Peer::<Application>::new(PeerId(1), cluster, Consistency::Strong, storage);
+    peer.trigger_election_timeout();
+
+    let expected_request = PeerMessage::from(
+        RequestVoteRequest::builder()
+            .term(1)
+            .candidate_id(PeerId(1))
+            .last_log_index(4)
+            .last_log_term(2)
+            .build(),
+    );
+    assert_eq!(peer.buffered_peer_transmits().len(), 2);
+    for transmit in peer.buffered_peer_transmits() {
+        assert_eq!(transmit.message(), &expected_request);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn entries_from_skips_entries_compacted_into_the_snapshot() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let entries = (1..=4)
+        .map(|index| LogEntry::builder().index(index).term(1).command(Command::NoOp).build())
+        .collect::<Vec<_>>();
+
+    let mut storage = Storage::default();
+    for entry in entries.iter().cloned() {
+        storage.append_log_entry(entry)?;
+    }
+    assert_eq!(storage.entries_from(LogIndex(1)), entries.as_slice());
+
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(2)
+        .last_included_term(1)
+        .machine(Machine::default())
+        .build();
+    storage.install_snapshot(snapshot)?;
+
+    // At the snapshot boundary.
+    assert_eq!(storage.entries_from(LogIndex(2)), &[]);
+
+    // Beyond the snapshot boundary.
+    assert_eq!(storage.entries_from(LogIndex(3)), &entries[2..]);
+    assert_eq!(storage.entries_from(LogIndex(4)), &entries[3..]);
+
+    // Beyond the last entry.
+    assert_eq!(storage.entries_from(LogIndex(5)), &[]);
+
+    Ok(())
+}
+
+#[test]
+fn append_entries_request_following_the_snapshot_boundary_is_accepted() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut storage = Storage::default();
+    storage.set_current_term(Term(1))?;
+    storage.install_snapshot(
+        Snapshot::<Application>::builder()
+            .last_included_index(2)
+            .last_included_term(1)
+            .machine(Machine::default())
+            .build(),
+    )?;
+
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<BTreeSet<_>>());
+    let mut follower = Peer::<Application>::new(PeerId(1), cluster, Consistency::Strong, storage);
+
+    let entry = LogEntry::builder().index(3).term(1).command(Command::NoOp).build();
+    let request = AppendEntriesRequest::builder()
+        .term(1)
+        .leader_id(PeerId(2))
+        .prev_log_index(2)
+        .prev_log_term(1)
+        .entries(vec![entry.clone()])
+        .leader_commit(0)
+        .build();
+    let replies = follower.handle_message(
+        PeerId(2),
+        RequestId(0),
+        PeerMessage::AppendEntriesRequest(request),
+    );
+
+    assert_eq!(
+        replies.iter().map(|transmit| transmit.message().clone()).collect::<Vec<_>>(),
+        vec![PeerMessage::AppendEntriesReply(
+            AppendEntriesReply::builder().term(1).success(true).build(),
+        )],
+    );
+    assert_eq!(follower.log().iter().cloned().collect::<Vec<_>>(), vec![entry]);
+
+    Ok(())
+}
+
+#[test]
+fn applying_below_snapshot_recovers_from_snapshot() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut storage = Storage::default();
+    storage.install_snapshot(
+        Snapshot::<Application>::builder()
+            .last_included_index(4)
+            .last_included_term(1)
+            .machine(Machine(BTreeMap::from([("a".to_owned(), "1".to_owned())])))
+            .build(),
+    )?;
+    storage.append_log_entry(
+        LogEntry::builder()
+            .index(5)
+            .term(1)
+            .command(Command::Insert { key: "b".to_owned(), value: "2".to_owned() })
+            .build(),
+    )?;
+
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<BTreeSet<_>>());
+    let mut peer = Peer::<Application>::new(PeerId(1), cluster, Consistency::Strong, storage);
+    peer.set_machine(Machine::default());
+    peer.set_last_applied(LogIndex(1));
+    peer.set_commit_index(LogIndex(5));
+
+    let results = peer.apply_committed();
+    assert_eq!(results, vec![(LogIndex(5), CommandResult::Done)]);
+    assert_eq!(peer.last_applied(), LogIndex(5));
+    assert_eq!(
+        peer.machine(),
+        &Machine(BTreeMap::from([
+            ("a".to_owned(), "1".to_owned()),
+            ("b".to_owned(), "2".to_owned()),
+        ])),
+    );
+
+    Ok(())
+}
+
+#[test]
+fn log_lookup_distinguishes_compacted_and_missing_entries() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let mut storage = Storage::default();
+    storage.install_snapshot(
+        Snapshot::<Application>::builder()
+            .last_included_index(4)
+            .last_included_term(2)
+            .machine(Machine::default())
+            .build(),
+    )?;
+    let entry = LogEntry::builder()
+        .index(5)
+        .term(3)
+        .command(Command::Clear { key: "a".to_owned() })
+        .build();
+    storage.append_log_entry(entry.clone())?;
+
+    let cluster = Cluster::from((1..=3).map(PeerId).collect::<BTreeSet<_>>());
+    let mut peer = Peer::<Application>::new(PeerId(1), cluster, Consistency::Strong, storage);
+
+    let compacted =
+        LogLookup::Compacted { last_included_index: LogIndex(4), last_included_term: Term(2) };
+    assert_eq!(peer.lookup_log_entry(LogIndex(3)), compacted);
+    assert_eq!(peer.lookup_log_entry(LogIndex(4)), compacted);
+    assert_eq!(peer.lookup_log_entry(LogIndex(5)), LogLookup::Present(&entry));
+    assert_eq!(peer.lookup_log_entry(LogIndex(6)), LogLookup::Missing);
+
+    // Applying stops before the missing entry instead of panicking
+    peer.set_commit_index(LogIndex(6));
+    let results = peer.apply_committed();
+    assert_eq!(results, vec![(LogIndex(5), CommandResult::Done)]);
+    assert_eq!(peer.last_applied(), LogIndex(5));
+
+    Ok(())
+}
+
+#[test]
+fn peer_reloads_externally_installed_snapshot() -> anyhow::Result<()> {
+    let cluster = Cluster::from(BTreeSet::from([PeerId(1), PeerId(2), PeerId(3)]));
+    let mut peer = Peer::<Application>::new(
+        PeerId(1),
+        cluster.clone(),
+        Consistency::Strong,
+        Storage::default(),
+    );
+
+    let machine = Machine(BTreeMap::from([("x".to_owned(), "1".to_owned())]));
+    let snapshot = Snapshot::<Application>::builder()
+        .last_included_index(5)
+        .last_included_term(2)
+        .cluster(cluster)
+        .machine(machine.clone())
+        .build();
+
+    // Installing the snapshot to the storage directly doesn't update the in-memory view
+    peer.set_snapshot(snapshot.clone())?;
+    assert_eq!(peer.snapshot(), &snapshot);
+    assert_eq!(peer.machine(), &Machine::default());
+    assert_eq!(peer.commit_index(), LogIndex(0));
+
+    peer.force_snapshot_from_storage();
+    assert_eq!(peer.machine(), &machine);
+    assert_eq!(peer.commit_index(), LogIndex(5));
+    assert_eq!(peer.last_applied(), LogIndex(5));
+
+    Ok(())
+}
@@ -16,6 +16,15 @@ pub struct Storage {
     pub(crate) voted_for: Option<PeerId>,
     pub(crate) log: Log<KeyValueDatabase<Storage>>,
     pub(crate) snapshot: Snapshot<KeyValueDatabase<Storage>>,
+
+    /// Index of the log entry to crash while appending in transactions.
+    pub(crate) crashing_log_index: Option<LogIndex>,
+
+    /// Number of upcoming writes to fail without persisting anything.
+    pub(crate) failing_writes: usize,
+
+    /// Persisted buffered peer transmits, if persisting them is enabled.
+    pub(crate) transmits: Option<Vec<PeerTransmit<KeyValueDatabase<Storage>>>>,
 }
 
 impl Default for Storage {
@@ -25,7 +34,21 @@ impl Default for Storage {
             voted_for: None,
             log: Log::default(),
             snapshot: Snapshot::default(),
+            crashing_log_index: None,
+            failing_writes: 0,
+            transmits: None,
+        }
+    }
+}
+
+impl Storage {
+    /// Fails the write if there are upcoming writes to fail.
+    fn inject_failure(&mut self) -> Result<(), StorageError> {
+        if self.failing_writes > 0 {
+            self.failing_writes -= 1;
+            return Err(StorageError::InjectedFailure);
         }
+        Ok(())
     }
 }
 
@@ -37,6 +60,7 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
     }
 
     fn set_current_term(&mut self, term: Term) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.current_term = term;
         Ok(())
     }
@@ -46,6 +70,7 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
     }
 
     fn set_voted_for(&mut self, voted_for: Option<PeerId>) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.voted_for = voted_for;
         Ok(())
     }
@@ -55,6 +80,7 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
         current_term: Term,
         voted_for: Option<PeerId>,
     ) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.current_term = current_term;
         self.voted_for = voted_for;
         Ok(())
@@ -68,11 +94,13 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
         &mut self,
         entry: LogEntry<KeyValueDatabase<Self>>,
     ) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.log.push(entry);
         Ok(())
     }
 
     fn truncate_log(&mut self, down_to: LogIndex) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.log.truncate(down_to.0);
         Ok(())
     }
@@ -85,9 +113,50 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
         &mut self,
         snapshot: Snapshot<KeyValueDatabase<Self>>,
     ) -> Result<(), Self::Error> {
+        self.inject_failure()?;
         self.snapshot = snapshot;
         Ok(())
     }
+
+    fn buffered_peer_transmits(&self) -> Vec<PeerTransmit<KeyValueDatabase<Self>>> {
+        self.transmits.clone().unwrap_or_default()
+    }
+
+    fn set_buffered_peer_transmits(
+        &mut self,
+        transmits: &std::collections::VecDeque<PeerTransmit<KeyValueDatabase<Self>>>,
+    ) -> Result<(), Self::Error> {
+        if let Some(persisted_transmits) = &mut self.transmits {
+            *persisted_transmits = transmits.iter().cloned().collect();
+        }
+        Ok(())
+    }
+
+    fn apply_transaction(
+        &mut self,
+        transaction: StorageTransaction<KeyValueDatabase<Self>>,
+    ) -> Result<(), Self::Error> {
+        self.inject_failure()?;
+        let mut staged = self.clone();
+        if let Some(current_term) = transaction.current_term() {
+            staged.current_term = current_term;
+        }
+        if let Some(voted_for) = transaction.voted_for() {
+            staged.voted_for = voted_for;
+        }
+        if let Some(down_to) = transaction.log_truncation() {
+            staged.log.retain(|entry| entry.index() < down_to);
+        }
+        for entry in transaction.log_entries() {
+            if Some(entry.index()) == self.crashing_log_index {
+                return Err(StorageError::Crashed);
+            }
+            staged.log.push(entry.clone());
+        }
+
+        *self = staged;
+        Ok(())
+    }
 }
 
 /// Errors that can happen during [Storage] operations.
@@ -101,4 +170,9 @@ impl RaftStorage<KeyValueDatabase<Self>> for Storage {
     derive_more::Error,
     derive_more::Display
 )]
-pub enum StorageError {}
+pub enum StorageError {
+    #[display("Crashed while persisting")]
+    Crashed,
+    #[display("Failed to persist due to an injected failure")]
+    InjectedFailure,
+}
@@ -0,0 +1,80 @@
+//! Storage transaction tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+fn entry(index: usize, term: usize) -> LogEntry<Application> {
+    LogEntry::builder().index(index).term(term).command(Command::NoOp).build()
+}
+
+#[test]
+fn transaction_applies_all_updates() -> anyhow::Result<()> {
+    let mut storage = Storage::default();
+    storage.transaction(|transaction| {
+        transaction
+            .set_current_term(Term(1))
+            .append_log_entry(entry(1, 1))
+            .append_log_entry(entry(2, 1));
+    })?;
+
+    storage.transaction(|transaction| {
+        transaction
+            .set_current_term(Term(2))
+            .set_voted_for(Some(PeerId(3)))
+            .truncate_log(LogIndex(2))
+            .append_log_entry(entry(2, 2));
+    })?;
+
+    assert_eq!(storage.current_term(), Term(2));
+    assert_eq!(storage.voted_for(), Some(PeerId(3)));
+    assert_eq!(storage.log(), &Log::from(vec![entry(1, 1), entry(2, 2)]));
+
+    Ok(())
+}
+
+#[test]
+fn crash_within_transaction_keeps_prior_state() -> anyhow::Result<()> {
+    let mut storage = Storage::default();
+    storage.transaction(|transaction| {
+        transaction.set_current_term(Term(1)).append_log_entry(entry(1, 1));
+    })?;
+
+    storage.crashing_log_index = Some(LogIndex(3));
+    let result = storage.transaction(|transaction| {
+        transaction
+            .set_current_term(Term(2))
+            .set_voted_for(Some(PeerId(2)))
+            .append_log_entry(entry(2, 2))
+            .append_log_entry(entry(3, 2));
+    });
+    assert!(result.is_err());
+
+    assert_eq!(storage.current_term(), Term(1));
+    assert_eq!(storage.voted_for(), None);
+    assert_eq!(storage.log(), &Log::from(vec![entry(1, 1)]));
+
+    Ok(())
+}
+
+#[test]
+fn truncating_within_transaction_drops_appended_entries() {
+    let mut transaction = StorageTransaction::<Application>::default();
+    assert!(transaction.is_empty());
+
+    transaction
+        .append_log_entry(entry(3, 1))
+        .append_log_entry(entry(4, 1))
+        .truncate_log(LogIndex(4))
+        .truncate_log(LogIndex(6));
+
+    assert!(!transaction.is_empty());
+    assert_eq!(transaction.log_truncation(), Some(LogIndex(4)));
+    assert_eq!(transaction.log_entries(), &[entry(3, 1)]);
+}
@@ -0,0 +1,185 @@
+//! Transmit tests.
+
+use {
+    rafty::prelude::*,
+    rafty_kvdb::*,
+};
+
+mod storage;
+use storage::Storage;
+
+type Application = KeyValueDatabase<Storage>;
+
+#[test]
+fn peer_transmit_builders_reject_mismatched_messages() {
+    let request = RequestVoteRequest::builder()
+        .term(1)
+        .candidate_id(PeerId(1))
+        .last_log_index(0)
+        .last_log_term(0)
+        .build();
+    let reply = AppendEntriesReply::builder().term(1).success(true).build();
+
+    let transmit = PeerTransmit::<Application>::request()
+        .peer_id(PeerId(2))
+        .request_id(RequestId(0))
+        .message(request.clone())
+        .build();
+    assert!(transmit.is_ok_and(|transmit| transmit.message().is_request()));
+
+    assert_eq!(
+        PeerTransmit::<Application>::reply()
+            .peer_id(PeerId(2))
+            .request_id(RequestId(0))
+            .message(request)
+            .build(),
+        Err(TransmitError::NotReply { request_id: RequestId(0) }),
+    );
+    assert_eq!(
+        PeerTransmit::<Application>::request()
+            .peer_id(PeerId(1))
+            .request_id(RequestId(1))
+            .message(reply)
+            .build(),
+        Err(TransmitError::NotRequest { request_id: RequestId(1) }),
+    );
+}
+
+#[test]
+fn client_transmit_builders_reject_mismatched_messages() {
+    let request =
+        QueryRequest::<Application>::builder().client_id(ClientId(1)).query(Query::Length).build();
+    let reply = CommandReply::<Application>::builder().result(Ok(CommandResult::Done)).build();
+
+    let transmit = ClientTransmit::reply()
+        .client_id(ClientId(1))
+        .peer_id(PeerId(1))
+        .request_id(RequestId(0))
+        .message(reply.clone())
+        .build();
+    assert!(transmit.is_ok_and(|transmit| transmit.message().is_reply()));
+
+    assert!(matches!(
+        ClientTransmit::reply()
+            .client_id(ClientId(1))
+            .peer_id(PeerId(1))
+            .request_id(RequestId(0))
+            .message(request)
+            .build(),
+        Err(TransmitError::NotReply { request_id: RequestId(0) }),
+    ));
+    assert!(matches!(
+        ClientTransmit::request()
+            .client_id(ClientId(1))
+            .peer_id(PeerId(1))
+            .request_id(RequestId(1))
+            .message(reply)
+            .build(),
+        Err(TransmitError::NotRequest { request_id: RequestId(1) }),
+    ));
+}
+
+#[test]
+fn persisted_append_entries_request_is_restored_after_restart() -> anyhow::Result<()> {
+    let _ = env_logger::try_init();
+
+    let storage = Storage { transmits: Some(Vec::new()), ..Storage::default() };
+    let mut simulation =
+        rafty_simulator::Simulation::<Application>::new(Consistency::Strong, vec![storage; 3], 1)?;
+    simulation.elect(PeerId(1))?;
+
+    simulation.perform(rafty_simulator::Action::SendCommand {
+        client_id: ClientId(1),
+        peer_id: Some(PeerId(1)),
+        request_id: None,
+        command: Command::Insert { key: "x".to_owned(), value: "1".to_owned() },
+    })?;
+    simulation.perform(rafty_simulator::Action::TransmitClientRequest {
+        client_id: ClientId(1),
+        request_id: RequestId(0),
+    })?;
+
+    let leader = simulation.peer(PeerId(1)).unwrap();
+    let buffered_transmits = leader.buffered_peer_transmits().iter().cloned().collect::<Vec<_>>();
+    assert!(buffered_transmits
+        .iter()
+        .any(|transmit| { matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_)) }));
+
+    let restarted_leader = Peer::<Application>::new(
+        PeerId(1),
+        leader.cluster().clone(),
+        Consistency::Strong,
+        leader.storage().clone(),
+    );
+    assert!(restarted_leader.role().is_follower());
+    assert_eq!(
+        restarted_leader.buffered_peer_transmits().iter().cloned().collect::<Vec<_>>(),
+        buffered_transmits,
+    );
+
+    let last_request_id =
+        buffered_transmits.iter().map(|transmit| transmit.request_id()).max().unwrap();
+    assert_eq!(restarted_leader.peek_next_request_id(), RequestId(last_request_id.0 + 1));
+
+    let restarted_follower = Peer::<Application>::new(
+        PeerId(2),
+        leader.cluster().clone(),
+        Consistency::Strong,
+        Storage::default(),
+    );
+    assert!(restarted_follower.buffered_peer_transmits().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn transmits_can_be_compared_ignoring_request_ids() {
+    let message = AppendEntriesRequest::<Application>::builder()
+        .term(1)
+        .leader_id(PeerId(1))
+        .prev_log_index(0)
+        .prev_log_term(0)
+        .entries([])
+        .leader_commit(0)
+        .build();
+    let transmit = |peer_id: usize, request_id: usize| {
+        PeerTransmit::builder()
+            .peer_id(PeerId(peer_id))
+            .request_id(RequestId(request_id))
+            .message(message.clone())
+            .build()
+    };
+
+    assert_ne!(transmit(2, 0), transmit(2, 1));
+    assert!(transmit(2, 0).eq_ignoring_request_id(&transmit(2, 1)));
+    assert!(!transmit(2, 0).eq_ignoring_request_id(&transmit(3, 0)));
+
+    let transmit = |request_id: usize| {
+        ClientTransmit::<Application>::builder()
+            .client_id(ClientId(1))
+            .peer_id(PeerId(1))
+            .request_id(RequestId(request_id))
+            .message(QueryRequest::builder().client_id(ClientId(1)).query(Query::Length).build())
+            .build()
+    };
+    assert!(transmit(0).eq_ignoring_request_id(&transmit(1)));
+}
+
+#[test]
+fn transmit_equality_ignores_creation_time() {
+    let transmit = || {
+        PeerTransmit::<Application>::builder()
+            .peer_id(PeerId(2))
+            .request_id(RequestId(0))
+            .message(RequestVoteReply::builder().term(1).vote(Vote::Granted).build())
+            .build()
+    };
+
+    let older = transmit();
+    let newer = transmit();
+    assert!(older.created_at() < newer.created_at());
+    assert!(newer.created_at() < transmit_clock());
+
+    assert_eq!(older, newer);
+    assert_eq!(format!("{older:?}"), format!("{newer:?}"));
+}
@@ -4,6 +4,9 @@ use crate::prelude::*;
 
 /// Application to make distributed.
 pub trait Application: Clone + Debug + Eq + PartialEq + Send + Sync + 'static {
+    /// Name of the application to identify it in logs and diagnostics.
+    const NAME: &'static str;
+
     /// Machine to replicate across [Peer]s.
     type Machine: Machine<Self>;
 
@@ -29,4 +32,20 @@ pub trait Application: Clone + Debug + Eq + PartialEq + Send + Sync + 'static {
         + Send
         + Sync
         + 'static;
+
+    /// Encodes a [Snapshot] into bytes for [Storage]s to persist.
+    ///
+    /// This lets the application pick a format suitable for its [Machine],
+    /// instead of relying on the generic serialization of the [Storage].
+    fn encode_snapshot(snapshot: &Snapshot<Self>) -> Vec<u8>;
+
+    /// Decodes a [Snapshot] from bytes produced by [Application::encode_snapshot].
+    fn decode_snapshot(bytes: &[u8]) -> Result<Snapshot<Self>, SnapshotDecodingError>;
+
+    /// Gets whether two commands commute, meaning they can be applied in any order.
+    ///
+    /// It's based on [Command::conflicts_with], so commands don't commute by default.
+    fn commands_commute(command: &Self::Command, other_command: &Self::Command) -> bool {
+        !command.conflicts_with(other_command) && !other_command.conflicts_with(command)
+    }
 }
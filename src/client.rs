@@ -3,9 +3,11 @@
 use crate::prelude::*;
 
 /// Client to issue [Command]s and [Query]s to [Peer]s.
+#[derive(Clone)]
 pub struct Client<A: Application> {
     pub(crate) id: ClientId,
     pub(crate) cluster: Cluster,
+    pub(crate) consistency: Consistency,
 
     pub(crate) leader: Option<PeerId>,
 
@@ -18,15 +20,22 @@ pub struct Client<A: Application> {
     pub(crate) queries: BTreeMap<RequestId, A::Query>,
     pub(crate) query_results: BTreeMap<RequestId, Result<A::QueryResult, ClientError<A>>>,
 
+    pub(crate) leader_discoveries: BTreeSet<RequestId>,
+    pub(crate) awaiting_leader: BTreeSet<RequestId>,
+    pub(crate) request_attempts: BTreeMap<RequestId, (PeerId, usize)>,
+
     pub(crate) buffered_client_transmits: VecDeque<ClientTransmit<A>>,
 }
 
 impl<A: Application> Client<A> {
     /// Creates a new client.
-    pub fn new(id: ClientId, cluster: Cluster) -> Self {
+    ///
+    /// Consistency should match the consistency of the peers in the cluster.
+    pub fn new(id: ClientId, cluster: Cluster, consistency: Consistency) -> Self {
         Self {
             id,
             cluster,
+            consistency,
             leader: None,
             rng: StdRng::from_os_rng(),
             request_counter: RequestCounter::default(),
@@ -34,21 +43,72 @@ impl<A: Application> Client<A> {
             command_results: Default::default(),
             queries: Default::default(),
             query_results: Default::default(),
+            leader_discoveries: Default::default(),
+            awaiting_leader: Default::default(),
+            request_attempts: Default::default(),
             buffered_client_transmits: Default::default(),
         }
     }
 }
 
+impl<A: Application> Client<A> {
+    /// Number of timed out attempts of a request to the same peer after which
+    /// the request is rerouted to a different peer.
+    pub const MAX_ATTEMPTS_PER_PEER: usize = 3;
+}
+
 impl<A: Application> Client<A> {
     /// Gets the identifier of the client.
     pub fn id(&self) -> ClientId {
         self.id
     }
 
+    /// Gets the consistency mode of the client.
+    pub fn consistency(&self) -> Consistency {
+        self.consistency
+    }
+
+    /// Gets the leader known by the client.
+    pub fn leader(&self) -> Option<PeerId> {
+        self.leader
+    }
+
     /// Gets the buffered transmits of the client.
     pub fn buffered_client_transmits(&self) -> &VecDeque<ClientTransmit<A>> {
         &self.buffered_client_transmits
     }
+
+    /// Gets the command requests awaiting a reply.
+    pub fn outstanding_commands(&self) -> &BTreeMap<RequestId, A::Command> {
+        &self.commands
+    }
+
+    /// Gets the query requests awaiting a reply.
+    pub fn outstanding_queries(&self) -> &BTreeMap<RequestId, A::Query> {
+        &self.queries
+    }
+
+    /// Gets the peer an outstanding request is sent to
+    /// alongside the number of its attempts to the peer that timed out.
+    pub fn request_attempts(&self, request_id: RequestId) -> Option<(PeerId, usize)> {
+        self.request_attempts.get(&request_id).copied()
+    }
+
+    /// Gets the result of a command request, if it's been replied.
+    pub fn command_result(
+        &self,
+        request_id: RequestId,
+    ) -> Option<&Result<A::CommandResult, ClientError<A>>> {
+        self.command_results.get(&request_id)
+    }
+
+    /// Gets the result of a query request, if it's been replied.
+    pub fn query_result(
+        &self,
+        request_id: RequestId,
+    ) -> Option<&Result<A::QueryResult, ClientError<A>>> {
+        self.query_results.get(&request_id)
+    }
 }
 
 impl<A: Application> Client<A> {
@@ -58,7 +118,23 @@ impl<A: Application> Client<A> {
         command: A::Command,
         peer_id: Option<PeerId>,
     ) -> Result<RequestId, ClientError<A>> {
-        let request_id = RequestId(self.request_counter.next());
+        let request_id = self.next_request_id();
+        self.command_with_request_id(request_id, command, peer_id)
+    }
+
+    /// Submits a command to the cluster with an explicit request id.
+    ///
+    /// Uses the given request id instead of the next one, which must not be used before.
+    pub fn command_with_request_id(
+        &mut self,
+        request_id: RequestId,
+        command: A::Command,
+        peer_id: Option<PeerId>,
+    ) -> Result<RequestId, ClientError<A>> {
+        if self.is_request_id_used(request_id) {
+            return Err(ClientError::RequestIdAlreadyUsed { request_id });
+        }
+
         let peer_id = match peer_id {
             Some(peer_id) => {
                 log::info!(
@@ -105,14 +181,16 @@ impl<A: Application> Client<A> {
             },
         };
         self.commands.insert(request_id, command.clone());
+        self.request_attempts.insert(request_id, (peer_id, 0));
 
-        let request = CommandRequest::builder().command(command).build();
-        let transmit = ClientTransmit::builder()
+        let request = CommandRequest::builder().client_id(self.id).command(command).build();
+        let transmit = ClientTransmit::request()
             .peer_id(peer_id)
             .client_id(self.id)
             .request_id(request_id)
             .message(request)
-            .build();
+            .build()
+            .expect("Message of the transmit is a request");
 
         self.buffered_client_transmits.push_back(transmit);
         Ok(request_id)
@@ -124,7 +202,23 @@ impl<A: Application> Client<A> {
         query: A::Query,
         peer_id: Option<PeerId>,
     ) -> Result<RequestId, ClientError<A>> {
-        let request_id = RequestId(self.request_counter.next());
+        let request_id = self.next_request_id();
+        self.query_with_request_id(request_id, query, peer_id)
+    }
+
+    /// Submits a query to the cluster with an explicit request id.
+    ///
+    /// Uses the given request id instead of the next one, which must not be used before.
+    pub fn query_with_request_id(
+        &mut self,
+        request_id: RequestId,
+        query: A::Query,
+        peer_id: Option<PeerId>,
+    ) -> Result<RequestId, ClientError<A>> {
+        if self.is_request_id_used(request_id) {
+            return Err(ClientError::RequestIdAlreadyUsed { request_id });
+        }
+
         let peer_id = match peer_id {
             Some(peer_id) => {
                 log::info!(
@@ -171,19 +265,256 @@ impl<A: Application> Client<A> {
             },
         };
         self.queries.insert(request_id, query.clone());
+        self.request_attempts.insert(request_id, (peer_id, 0));
 
-        let request = QueryRequest::builder().query(query).build();
-        let transmit = ClientTransmit::builder()
+        let request = QueryRequest::builder().client_id(self.id).query(query).build();
+        let transmit = ClientTransmit::request()
             .peer_id(peer_id)
             .client_id(self.id)
             .request_id(request_id)
             .message(request)
-            .build();
+            .build()
+            .expect("Message of the transmit is a request");
 
         self.buffered_client_transmits.push_back(transmit);
         Ok(request_id)
     }
 
+    /// Submits a query to every peer in the cluster.
+    ///
+    /// Results can be compared using [Client::reconcile_query_results]
+    /// to detect peers that are lagging behind.
+    pub fn query_all(
+        &mut self,
+        query: A::Query,
+    ) -> Result<BTreeMap<PeerId, RequestId>, ClientError<A>> {
+        if self.cluster.is_empty() {
+            return Err(ClientError::EmptyCluster);
+        }
+
+        let peer_ids = self.cluster.iter().copied().collect::<Vec<_>>();
+        let mut request_ids = BTreeMap::new();
+        for peer_id in peer_ids {
+            let request_id = self.query(query.clone(), Some(peer_id))?;
+            request_ids.insert(peer_id, request_id);
+        }
+        Ok(request_ids)
+    }
+
+    /// Asks every peer in the cluster for the leader they know.
+    ///
+    /// Replies update the leader of the client, so the following commands and queries
+    /// are sent to the leader directly instead of a randomly selected peer.
+    pub fn find_leader(&mut self) -> Result<BTreeMap<PeerId, RequestId>, ClientError<A>> {
+        if self.cluster.is_empty() {
+            return Err(ClientError::EmptyCluster);
+        }
+
+        let peer_ids = self.cluster.iter().copied().collect::<Vec<_>>();
+        let mut request_ids = BTreeMap::new();
+        for peer_id in peer_ids {
+            let request_id = self.next_request_id();
+            log::info!(
+                "|{}| Asking for the leader in request {} via peer {}.",
+                self.id,
+                request_id,
+                peer_id,
+            );
+            self.leader_discoveries.insert(request_id);
+
+            let request = FindLeaderRequest::builder().client_id(self.id).build();
+            let transmit = ClientTransmit::request()
+                .peer_id(peer_id)
+                .client_id(self.id)
+                .request_id(request_id)
+                .message(request)
+                .build()
+                .expect("Message of the transmit is a request");
+
+            self.buffered_client_transmits.push_back(transmit);
+            request_ids.insert(peer_id, request_id);
+        }
+        Ok(request_ids)
+    }
+
+    /// Postpones a request until the leader is discovered, starting a discovery
+    /// with [Client::find_leader] unless one is in progress already.
+    pub(crate) fn await_leader(&mut self, request_id: RequestId) {
+        log::info!(
+            "|{}| Postponing request {} until the leader is discovered.",
+            self.id,
+            request_id,
+        );
+        self.leader = None;
+        self.awaiting_leader.insert(request_id);
+
+        if self.leader_discoveries.is_empty() {
+            log::info!("|{}| Discovering the leader.", self.id);
+            if let Err(error) = self.find_leader() {
+                log::error!("|{}| Failed to discover the leader ({}).", self.id, error);
+            }
+        }
+    }
+
+    /// Sends the requests postponed until the leader is discovered to the discovered leader.
+    pub(crate) fn retry_awaiting_leader(&mut self) {
+        let Some(leader_id) = self.leader else {
+            return;
+        };
+        for request_id in std::mem::take(&mut self.awaiting_leader) {
+            let message: ClientMessage<A> = if let Some(command) = self.commands.get(&request_id) {
+                CommandRequest::builder().client_id(self.id).command(command.clone()).build().into()
+            } else if let Some(query) = self.queries.get(&request_id) {
+                QueryRequest::builder().client_id(self.id).query(query.clone()).build().into()
+            } else {
+                continue;
+            };
+
+            log::info!(
+                "|{}| Sending postponed request {} to the discovered leader peer {}.",
+                self.id,
+                request_id,
+                leader_id,
+            );
+            let transmit = ClientTransmit::request()
+                .peer_id(leader_id)
+                .client_id(self.id)
+                .request_id(request_id)
+                .message(message)
+                .build()
+                .expect("Message of the transmit is a request");
+            self.buffered_client_transmits.push_back(transmit);
+            self.request_attempts.insert(request_id, (leader_id, 0));
+        }
+    }
+
+    /// Triggers the timeout of an outstanding request, which is sent again.
+    ///
+    /// After [Client::MAX_ATTEMPTS_PER_PEER] timed out attempts to the same peer, the peer is
+    /// considered unresponsive, so the request is rerouted to a randomly selected different peer.
+    pub fn trigger_request_timeout(&mut self, request_id: RequestId) {
+        let message: ClientMessage<A> = if let Some(command) = self.commands.get(&request_id) {
+            CommandRequest::builder().client_id(self.id).command(command.clone()).build().into()
+        } else if let Some(query) = self.queries.get(&request_id) {
+            QueryRequest::builder().client_id(self.id).query(query.clone()).build().into()
+        } else {
+            log::debug!(
+                "|{}| Request {} timed out but it's either unknown or already been replied.",
+                self.id,
+                request_id,
+            );
+            return;
+        };
+        let Some((peer_id, attempts)) = self.request_attempts.get(&request_id).copied() else {
+            return;
+        };
+
+        let attempts = attempts + 1;
+        log::info!(
+            "|{}| Request {} timed out {} times in a row via peer {}.",
+            self.id,
+            request_id,
+            attempts,
+            peer_id,
+        );
+
+        let (peer_id, attempts) = if attempts < Self::MAX_ATTEMPTS_PER_PEER {
+            log::info!("|{}| Sending request {} again via peer {}.", self.id, request_id, peer_id);
+            (peer_id, attempts)
+        } else {
+            if self.leader == Some(peer_id) {
+                log::info!(
+                    "|{}| Forgetting the leader as peer {} is unresponsive.",
+                    self.id,
+                    peer_id,
+                );
+                self.leader = None;
+            }
+            match self.cluster.iter().filter(|other| **other != peer_id).choose(&mut self.rng) {
+                Some(other_peer_id) => {
+                    log::info!(
+                        "|{}| Rerouting request {} via the randomly selected peer {} \
+                        as peer {} is unresponsive.",
+                        self.id,
+                        request_id,
+                        other_peer_id,
+                        peer_id,
+                    );
+                    (*other_peer_id, 0)
+                },
+                None => {
+                    log::info!(
+                        "|{}| Sending request {} again via peer {} \
+                        as there are no other peers to reroute it to.",
+                        self.id,
+                        request_id,
+                        peer_id,
+                    );
+                    (peer_id, 0)
+                },
+            }
+        };
+
+        let transmit = ClientTransmit::request()
+            .peer_id(peer_id)
+            .client_id(self.id)
+            .request_id(request_id)
+            .message(message)
+            .build()
+            .expect("Message of the transmit is a request");
+        self.buffered_client_transmits.push_back(transmit);
+        self.request_attempts.insert(request_id, (peer_id, attempts));
+    }
+
+    /// Reconciles the results of a query submitted to multiple peers with [Client::query_all].
+    ///
+    /// Divergence of the results is reported by [QueryReconciliation::is_divergent]
+    /// and [QueryReconciliation::divergent_peers], for the caller to act on.
+    pub fn reconcile_query_results(
+        &self,
+        request_ids: &BTreeMap<PeerId, RequestId>,
+    ) -> QueryReconciliation<A> {
+        let mut reconciliation = QueryReconciliation {
+            results: BTreeMap::new(),
+            errors: BTreeMap::new(),
+            pending: BTreeSet::new(),
+        };
+        for (&peer_id, request_id) in request_ids {
+            match self.query_results.get(request_id) {
+                Some(Ok(result)) => {
+                    reconciliation.results.insert(peer_id, result.clone());
+                },
+                Some(Err(error)) => {
+                    reconciliation.errors.insert(peer_id, error.clone());
+                },
+                None => {
+                    reconciliation.pending.insert(peer_id);
+                },
+            }
+        }
+
+        reconciliation
+    }
+
+    /// Gets the next request id which isn't used by the client.
+    fn next_request_id(&mut self) -> RequestId {
+        loop {
+            let request_id = RequestId(self.request_counter.next());
+            if !self.is_request_id_used(request_id) {
+                return request_id;
+            }
+        }
+    }
+
+    /// Gets whether the request id is used by a request of the client.
+    pub fn is_request_id_used(&self, request_id: RequestId) -> bool {
+        self.commands.contains_key(&request_id)
+            || self.command_results.contains_key(&request_id)
+            || self.queries.contains_key(&request_id)
+            || self.query_results.contains_key(&request_id)
+            || self.leader_discoveries.contains(&request_id)
+    }
+
     pub fn receive_reply(
         &mut self,
         peer_id: PeerId,
@@ -191,7 +522,9 @@ impl<A: Application> Client<A> {
         message: ClientMessage<A>,
     ) {
         match message {
-            ClientMessage::CommandRequest(_) | ClientMessage::QueryRequest(_) => {
+            ClientMessage::CommandRequest(_)
+            | ClientMessage::QueryRequest(_)
+            | ClientMessage::FindLeaderRequest(_) => {
                 log::warn!(
                     "|{}| Peer {} sent a request to the client which shouldn't have happened.",
                     self.id,
@@ -205,6 +538,13 @@ impl<A: Application> Client<A> {
             ClientMessage::QueryReply(reply) => {
                 reply.receive(peer_id, request_id, self);
             },
+            ClientMessage::FindLeaderReply(reply) => {
+                reply.receive(peer_id, request_id, self);
+            },
+        }
+
+        if !self.commands.contains_key(&request_id) && !self.queries.contains_key(&request_id) {
+            self.request_attempts.remove(&request_id);
         }
     }
 }
@@ -226,3 +566,58 @@ impl<A: Application> Client<A> {
         self.buffered_client_transmits = new_buffered_client_transmits;
     }
 }
+
+/// Reconciliation of the results of a query submitted to multiple [Peer]s.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueryReconciliation<A: Application> {
+    results: BTreeMap<PeerId, A::QueryResult>,
+    errors: BTreeMap<PeerId, ClientError<A>>,
+    pending: BTreeSet<PeerId>,
+}
+
+impl<A: Application> QueryReconciliation<A> {
+    /// Gets the successful results of the peers.
+    pub fn results(&self) -> &BTreeMap<PeerId, A::QueryResult> {
+        &self.results
+    }
+
+    /// Gets the errors returned by the peers.
+    pub fn errors(&self) -> &BTreeMap<PeerId, ClientError<A>> {
+        &self.errors
+    }
+
+    /// Gets the peers which haven't replied yet.
+    pub fn pending(&self) -> &BTreeSet<PeerId> {
+        &self.pending
+    }
+
+    /// Gets whether the peers returned different results.
+    pub fn is_divergent(&self) -> bool {
+        let mut results = self.results.values();
+        match results.next() {
+            Some(first) => results.any(|result| result != first),
+            None => false,
+        }
+    }
+
+    /// Gets the result all of the peers agree on, if all of them returned the same result.
+    pub fn agreed_result(&self) -> Option<&A::QueryResult> {
+        if !self.errors.is_empty() || !self.pending.is_empty() || self.is_divergent() {
+            return None;
+        }
+        self.results.values().next()
+    }
+
+    /// Gets the peers whose results differ from the result returned by the most peers.
+    pub fn divergent_peers(&self) -> BTreeSet<PeerId> {
+        let majority_result = self
+            .results
+            .values()
+            .max_by_key(|result| self.results.values().filter(|other| other == result).count());
+        self.results
+            .iter()
+            .filter(|(_, result)| Some(*result) != majority_result)
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+}
@@ -28,6 +28,33 @@ pub trait Command:
     /// Leaders send [AppendEntriesRequest]s with a no-op entry to other [Peer]s upon being elected.
     /// This ensured the log replication process starts as soon as the leader is elected.
     fn no_op() -> Self;
+
+    /// Gets whether applying the command and another command in different orders
+    /// may result in different [Machine]s or different results.
+    ///
+    /// Commands are always applied serially in the order of the log, so it's only a hint
+    /// for documentation and future optimizations. Commands conflict by default.
+    fn conflicts_with(&self, other: &Self) -> bool {
+        let _ = other;
+        true
+    }
+
+    /// Gets the input schemas of the kinds of commands that can be built from user inputs.
+    ///
+    /// Interactive tools use it to build commands without application specific code.
+    /// No kinds are described by default.
+    fn schemas() -> &'static [InputSchema] {
+        &[]
+    }
+
+    /// Builds a command of the kind with the given name from the user inputs,
+    /// which are in the order of the inputs in its [InputSchema].
+    ///
+    /// Returns `None` if the kind is unknown or the inputs are invalid.
+    fn from_inputs(name: &str, inputs: &[String]) -> Option<Self> {
+        let _ = (name, inputs);
+        None
+    }
 }
 
 /// Result of a [Command].
@@ -50,4 +77,10 @@ pub trait Command:
 pub trait CommandResult:
     Clone + Debug + Eq + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
 {
+    /// Gets a human-readable summary of the result.
+    ///
+    /// Interactive tools use it to display results. It's the debug representation by default.
+    fn summary(&self) -> String {
+        format!("{self:?}")
+    }
 }
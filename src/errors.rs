@@ -11,13 +11,66 @@ use crate::prelude::*;
     derive_more::Display,
     derive_more::Error
 )]
+#[serde(bound = "")]
 pub enum ClientError<A: Application> {
     #[display("Cluster is empty")]
     EmptyCluster,
     #[display("Leader is not known by the peer")]
     LeaderUnknown,
+    #[display("Peer is not the leader, retry after {retry_after} ticks")]
+    NotLeaderRetryAfter { retry_after: usize },
     #[display("Leader changed to peer {new_leader_id}")]
     LeaderChanged { new_leader_id: PeerId },
+    #[display("Leader hasn't committed an entry from its term yet")]
+    LeaderNotReady,
     #[display("Storage error: {underlying_error}")]
     StorageError { underlying_error: A::StorageError },
+    #[display("Request {request_id} is already used by the client")]
+    RequestIdAlreadyUsed { request_id: RequestId },
+}
+
+/// Errors that can happen when decoding [Snapshot]s.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    derive_more::Error
+)]
+#[display("Snapshot is malformed: {_0}")]
+pub struct SnapshotDecodingError(#[error(not(source))] pub String);
+
+/// Errors that can happen when parsing [Consistency]s.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    derive_more::Error
+)]
+#[display("Consistency `{_0}` is not one of `strong` or `eventual`")]
+pub struct ConsistencyParsingError(#[error(not(source))] pub String);
+
+/// Errors that can happen when creating [PeerTransmit]s and [ClientTransmit]s.
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    derive_more::Display,
+    derive_more::Error
+)]
+pub enum TransmitError {
+    #[display("Message of request {request_id} is not a request")]
+    NotRequest { request_id: RequestId },
+    #[display("Message of reply to request {request_id} is not a reply")]
+    NotReply { request_id: RequestId },
 }
@@ -12,6 +12,7 @@ pub mod peer;
 pub mod primitives;
 pub mod query;
 pub mod role;
+pub mod schema;
 pub mod snapshot;
 pub mod storage;
 pub mod transmit;
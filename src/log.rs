@@ -25,11 +25,35 @@ impl<A: Application> Log<A> {
     pub fn entry(&self, index: LogIndex) -> Option<&LogEntry<A>> {
         self.binary_search_by_key(&index, |entry| entry.index()).map(|index| &self[index]).ok()
     }
+
+    /// Looks up the log entry with the given index, taking the snapshot the log follows into account.
+    pub fn lookup<'log>(&'log self, snapshot: &Snapshot<A>, index: LogIndex) -> LogLookup<'log, A> {
+        if let Some(entry) = self.entry(index) {
+            return LogLookup::Present(entry);
+        }
+
+        if index <= snapshot.last_included_index() {
+            LogLookup::Compacted {
+                last_included_index: snapshot.last_included_index(),
+                last_included_term: snapshot.last_included_term(),
+            }
+        } else {
+            LogLookup::Missing
+        }
+    }
+
+    /// Gets the sequence of the next log entry to be created by the leader of the given term.
+    pub(crate) fn next_sequence(&self, term: Term) -> usize {
+        self.iter().rev().take_while(|entry| entry.term() == term).count()
+    }
 }
 
 
 /// Entries within a [Log].
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+///
+/// Equality ignores the diagnostic sequence of the entries,
+/// as it doesn't have any effect on replication.
+#[derive(Clone, Debug, Serialize, Deserialize, bon::Builder)]
 #[serde(bound = "A::Command : Serialize + DeserializeOwned")]
 pub struct LogEntry<A: Application> {
     #[builder(into)]
@@ -40,8 +64,19 @@ pub struct LogEntry<A: Application> {
 
     #[builder(into)]
     command: A::Command,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sequence: Option<usize>,
+}
+
+impl<A: Application> PartialEq for LogEntry<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.term == other.term && self.command == other.command
+    }
 }
 
+impl<A: Application> Eq for LogEntry<A> {}
+
 impl<A: Application> LogEntry<A> {
     /// Gets the index of the log entry.
     pub fn index(&self) -> LogIndex {
@@ -57,4 +92,49 @@ impl<A: Application> LogEntry<A> {
     pub fn command(&self) -> &A::Command {
         &self.command
     }
+
+    /// Gets the position of the log entry among the entries created by the leader in its term.
+    ///
+    /// It's only for diagnostics (e.g., to correlate entries with the requests creating them),
+    /// so it's not available for entries created without it.
+    pub fn sequence(&self) -> Option<usize> {
+        self.sequence
+    }
+
+    /// Validates the log entry.
+    ///
+    /// Log index 0 is reserved for the empty log and the empty snapshot,
+    /// so only a no-op entry may be built with it.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.index == LogIndex(0) && self.command != A::Command::no_op() {
+            return Err(format!(
+                "Log entry with index 0 cannot hold a command other than the no-op (got {:?})",
+                self.command,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Result of looking up a [LogEntry] of a [Peer] by its index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LogLookup<'log, A: Application> {
+    /// Entry is in the log.
+    Present(&'log LogEntry<A>),
+    /// Entry is compacted into the snapshot, which includes entries up to and including
+    /// `last_included_index`, the last of which is from `last_included_term`.
+    Compacted { last_included_index: LogIndex, last_included_term: Term },
+    /// Entry is not yet in the log.
+    Missing,
+}
+
+/// Status of a [LogEntry] within the [Log] of a [Peer].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EntryStatus {
+    /// Entry is committed and applied to the [Machine] of the peer.
+    Applied,
+    /// Entry is committed but not yet applied to the [Machine] of the peer.
+    Committed,
+    /// Entry is not yet known to be replicated by the majority of the cluster.
+    Uncommitted,
 }
@@ -19,4 +19,21 @@ pub trait Machine<A: Application<Machine = Self>>:
 
     /// Runs a [Query] in the machine.
     fn query(&self, query: &A::Query) -> A::QueryResult;
+
+    /// Reacts to a [Command] applied to the machine, for side effects such as notifications.
+    ///
+    /// It's called after each command applied from a committed log entry, and does nothing by default.
+    fn on_applied(&mut self, _index: LogIndex, _command: &A::Command) {}
+
+    /// Clears the machine in place, making it equal to its default.
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Checks the invariants of the machine, returning the violation if there is any.
+    ///
+    /// It's called after each applied command in debug builds.
+    fn check_invariants(&self) -> Result<(), String> {
+        Ok(())
+    }
 }
@@ -48,8 +48,10 @@ impl AppendEntriesReply {
                 log::info!("({}) Redirecting awaiting client requests.", receiving_peer.id);
                 // TODO: remember client requests, redirect them to the new leader
                 log::info!("({}) Stepping down to become a follower.", receiving_peer.id);
-                receiving_peer.role =
-                    Role::Follower(FollowerState::builder().leader_id(None).build());
+                receiving_peer.transition_role(
+                    self.term,
+                    Role::Follower(FollowerState::builder().leader_id(None).build()),
+                );
             }
 
             return;
@@ -70,14 +72,29 @@ impl AppendEntriesReply {
                 return;
             };
 
+            if let Some(inflight) = leader_state.inflight_append_entries.get_mut(&sending_peer_id) {
+                inflight.remove(&request_id);
+            }
+            leader_state.append_entries_sent_to.remove(&request_id);
+            if let Some(sent_at) = leader_state.append_entries_sent_at.remove(&request_id) {
+                let contact =
+                    leader_state.last_leader_contact.entry(sending_peer_id).or_insert(sent_at);
+                *contact = (*contact).max(sent_at);
+            }
+            for read in leader_state.pending_reads.iter_mut() {
+                if read.heartbeat_request_ids.contains(&request_id) {
+                    read.acknowledged_by.insert(sending_peer_id);
+                }
+            }
+
             if self.success {
+                let replicated_log_index = request
+                    .entries
+                    .last()
+                    .map(|entry| entry.index())
+                    .unwrap_or(request.prev_log_index);
                 if let Some(next_log_index) = leader_state.next_index.get_mut(&sending_peer_id) {
-                    let new_log_index = request
-                        .entries
-                        .last()
-                        .map(|entry| entry.index())
-                        .unwrap_or(request.prev_log_index)
-                        .next();
+                    let new_log_index = replicated_log_index.next();
                     if *next_log_index != new_log_index {
                         log::info!(
                             "({}) Peer {} appended entries up to log index {}.",
@@ -88,20 +105,60 @@ impl AppendEntriesReply {
                         *next_log_index = new_log_index;
                     }
                 }
-                if let Some(match_index) = leader_state.match_index.get_mut(&sending_peer_id) {
-                    let replicated_log_index = request
-                        .entries
-                        .last()
-                        .map(|entry| entry.index())
-                        .unwrap_or(request.prev_log_index);
-                    if *match_index != replicated_log_index {
-                        log::info!(
-                            "({}) Peer {} replicated entries up to log index {}.",
-                            receiving_peer.id,
-                            sending_peer_id,
-                            match_index,
-                        );
-                        *match_index = replicated_log_index;
+                if let Some(match_index) = leader_state.match_index.get_mut(&sending_peer_id)
+                    && *match_index != replicated_log_index
+                {
+                    log::info!(
+                        "({}) Peer {} replicated entries up to log index {}.",
+                        receiving_peer.id,
+                        sending_peer_id,
+                        match_index,
+                    );
+                    *match_index = replicated_log_index;
+                }
+
+                let superseded_request_ids = leader_state
+                    .append_entries_sent_to
+                    .iter()
+                    .filter(|(superseded_request_id, peer_id)| {
+                        **peer_id == sending_peer_id && **superseded_request_id < request_id
+                    })
+                    .map(|(superseded_request_id, _)| *superseded_request_id)
+                    .filter(|superseded_request_id| {
+                        let is_awaited_by_reads = leader_state
+                            .pending_reads
+                            .iter()
+                            .any(|read| read.heartbeat_request_ids.contains(superseded_request_id));
+                        !is_awaited_by_reads
+                            && leader_state
+                                .append_entries_requests
+                                .get(superseded_request_id)
+                                .is_some_and(|superseded_request| {
+                                    superseded_request
+                                        .entries
+                                        .last()
+                                        .map(|entry| entry.index())
+                                        .unwrap_or(superseded_request.prev_log_index)
+                                        <= replicated_log_index
+                                })
+                    })
+                    .collect::<Vec<_>>();
+                for superseded_request_id in superseded_request_ids {
+                    log::debug!(
+                        "({}) Request {} to peer {} is superseded by request {}, \
+                        its reply will be ignored.",
+                        receiving_peer.id,
+                        superseded_request_id,
+                        sending_peer_id,
+                        request_id,
+                    );
+                    leader_state.append_entries_requests.remove(&superseded_request_id);
+                    leader_state.append_entries_sent_to.remove(&superseded_request_id);
+                    leader_state.append_entries_sent_at.remove(&superseded_request_id);
+                    if let Some(inflight) =
+                        leader_state.inflight_append_entries.get_mut(&sending_peer_id)
+                    {
+                        inflight.remove(&superseded_request_id);
                     }
                 }
 
@@ -120,6 +177,20 @@ impl AppendEntriesReply {
 
                     accumulated_replication_count += replication_count;
                     if accumulated_replication_count >= majority {
+                        let log_entry_term =
+                            receiving_peer.storage.log().entry(log_index).map(|entry| entry.term());
+                        if log_entry_term != Some(current_term) {
+                            log::info!(
+                                "({}) Majority of the peers appended up to log index {} \
+                                    but it's not from the current term {} \
+                                    so it cannot be committed by counting replicas.",
+                                receiving_peer.id,
+                                log_index,
+                                current_term,
+                            );
+                            break 'search;
+                        }
+
                         log::info!(
                             "({}) Majority of the peers appended up to log index {} \
                                 so committing log entries from index {} to index {}.",
@@ -134,31 +205,115 @@ impl AppendEntriesReply {
                 }
                 receiving_peer.commit_index = new_commit_index;
 
+                if !leader_state.initial_noop_committed
+                    && receiving_peer
+                        .storage
+                        .log()
+                        .entry(new_commit_index)
+                        .is_some_and(|entry| entry.term() == current_term)
+                {
+                    log::info!(
+                        "({}) Initial no-op entry is committed so leadership is confirmed.",
+                        receiving_peer.id,
+                    );
+                    leader_state.initial_noop_committed = true;
+                }
+
+                let nothing_in_flight = leader_state
+                    .inflight_append_entries
+                    .get(&sending_peer_id)
+                    .is_none_or(|requests| requests.is_empty());
+                if receiving_peer.max_inflight_per_peer.is_some()
+                    && nothing_in_flight
+                    && let Some(next_index) = leader_state.next_index.get(&sending_peer_id).copied()
+                {
+                    let held_back_entries =
+                        receiving_peer.storage.entries_from(next_index).to_vec();
+
+                    let prev_log_index = next_index.previous();
+                    let prev_log_term = match receiving_peer
+                        .storage
+                        .log()
+                        .lookup(receiving_peer.storage.snapshot(), prev_log_index)
+                    {
+                        LogLookup::Present(entry) => Some(entry.term()),
+                        LogLookup::Compacted { last_included_index, last_included_term }
+                            if last_included_index == prev_log_index =>
+                        {
+                            Some(last_included_term)
+                        },
+                        _ => None,
+                    };
+
+                    if !held_back_entries.is_empty()
+                        && let Some(prev_log_term) = prev_log_term
+                    {
+                        log::info!(
+                            "({}) Sending held back log entries starting from index {} to peer {}.",
+                            receiving_peer.id,
+                            next_index,
+                            sending_peer_id,
+                        );
+
+                        let request = AppendEntriesRequest::builder()
+                            .term(current_term)
+                            .leader_id(receiving_peer.id)
+                            .prev_log_index(prev_log_index)
+                            .prev_log_term(prev_log_term)
+                            .entries(held_back_entries)
+                            .leader_commit(receiving_peer.commit_index)
+                            .build();
+
+                        let request_id = receiving_peer.request_counter.next();
+                        let transmit = PeerTransmit::request()
+                            .peer_id(sending_peer_id)
+                            .request_id(request_id)
+                            .message(request.clone())
+                            .build()
+                            .expect("Message of the transmit is a request");
+
+                        leader_state.append_entries_requests.insert(transmit.request_id(), request);
+                        leader_state
+                            .append_entries_sent_at
+                            .insert(transmit.request_id(), receiving_peer.time);
+                        leader_state
+                            .append_entries_sent_to
+                            .insert(transmit.request_id(), sending_peer_id);
+                        leader_state
+                            .inflight_append_entries
+                            .entry(sending_peer_id)
+                            .or_default()
+                            .insert(transmit.request_id());
+                        receiving_peer.buffered_peer_transmits.push_back(transmit);
+                    }
+                }
+
                 return;
             }
 
             if let Some(next_index) = leader_state.next_index.get_mut(&sending_peer_id) {
-                if *next_index > receiving_peer.storage.snapshot().last_included_index().next() {
+                if *next_index > receiving_peer.storage.snapshot_metadata().0.next() {
                     *next_index = next_index.previous();
                 } else {
                     // TODO: snapshots
                     unimplemented!()
                 }
 
-                let log = receiving_peer.storage.log();
-                let next_index_position =
-                    match log.binary_search_by(|entry| entry.index().cmp(next_index)) {
-                        Ok(position) => position,
-                        Err(_) => {
-                            // TODO: needs snapshot
-                            unimplemented!()
-                        },
-                    };
-
-                let next_term = if *next_index == LogIndex(0) {
-                    Term(0)
-                } else {
-                    log[next_index_position].term()
+                let next_term = match receiving_peer
+                    .storage
+                    .log()
+                    .lookup(receiving_peer.storage.snapshot(), *next_index)
+                {
+                    LogLookup::Present(entry) => entry.term(),
+                    LogLookup::Compacted { last_included_index, last_included_term }
+                        if last_included_index == *next_index =>
+                    {
+                        last_included_term
+                    },
+                    LogLookup::Compacted { .. } | LogLookup::Missing => {
+                        // TODO: needs snapshot
+                        unimplemented!()
+                    },
                 };
 
                 let request = AppendEntriesRequest::builder()
@@ -166,18 +321,28 @@ impl AppendEntriesReply {
                     .leader_id(receiving_peer.id)
                     .prev_log_index(*next_index)
                     .prev_log_term(next_term)
-                    .entries(log[next_index_position..].to_vec())
+                    .entries(receiving_peer.storage.entries_from(*next_index).to_vec())
                     .leader_commit(receiving_peer.commit_index)
                     .build();
 
                 let request_id = receiving_peer.request_counter.next();
-                let transmit = PeerTransmit::builder()
+                let transmit = PeerTransmit::request()
                     .peer_id(sending_peer_id)
                     .request_id(request_id)
                     .message(request.clone())
-                    .build();
+                    .build()
+                    .expect("Message of the transmit is a request");
 
                 leader_state.append_entries_requests.insert(transmit.request_id(), request);
+                leader_state
+                    .append_entries_sent_at
+                    .insert(transmit.request_id(), receiving_peer.time);
+                leader_state.append_entries_sent_to.insert(transmit.request_id(), sending_peer_id);
+                leader_state
+                    .inflight_append_entries
+                    .entry(sending_peer_id)
+                    .or_default()
+                    .insert(transmit.request_id());
                 receiving_peer.buffered_peer_transmits.push_back(transmit);
             }
         }
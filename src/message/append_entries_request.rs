@@ -2,6 +2,7 @@ use crate::prelude::*;
 
 /// Request from the leader to other [Peer]s to replicate log entries.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+#[serde(bound = "")]
 pub struct AppendEntriesRequest<A: Application> {
     #[builder(into)]
     pub(crate) term: Term,
@@ -39,73 +40,137 @@ impl<A: Application> AppendEntriesRequest<A> {
             return AppendEntriesReply::builder().term(current_term).success(false).build();
         }
 
+        if let Some(violation) = self.entries.iter().find_map(|entry| entry.validate().err()) {
+            log::error!(
+                "({}) Peer {} sent a malformed log entry, rejecting the request: {}",
+                receiving_peer.id,
+                sending_peer_id,
+                violation,
+            );
+            return AppendEntriesReply::builder().term(current_term).success(false).build();
+        }
+
         if self.prev_log_index != LogIndex(0) {
-            let log = receiving_peer.log();
-            let prev_log_position = match log
-                .binary_search_by(|entry| entry.index().cmp(&self.prev_log_index))
-            {
-                Ok(position) => position,
-                Err(_) => {
+            match receiving_peer.lookup_log_entry(self.prev_log_index) {
+                LogLookup::Present(prev_log) if prev_log.term() == self.prev_log_term => {},
+                LogLookup::Present(prev_log) => {
+                    let prev_log_index = prev_log.index();
+                    receiving_peer.storage.truncate_log(prev_log_index).expect("TODO");
+                    return AppendEntriesReply::builder().term(current_term).success(false).build();
+                },
+                // Entry at the boundary of the snapshot is compacted,
+                // but the snapshot still records its term.
+                LogLookup::Compacted { last_included_index, last_included_term }
+                    if last_included_index == self.prev_log_index
+                        && last_included_term == self.prev_log_term => {},
+                LogLookup::Compacted { .. } | LogLookup::Missing => {
                     return AppendEntriesReply::builder().term(current_term).success(false).build();
                 },
-            };
-
-            let prev_log = &log[prev_log_position];
-            if prev_log.term() != self.prev_log_term {
-                receiving_peer.storage.truncate_log(prev_log.index()).expect("TODO");
-                return AppendEntriesReply::builder().term(current_term).success(false).build();
             }
         }
 
+        let mut transaction = StorageTransaction::default();
+
         if self.term > current_term {
             log::info!(
                 "({}) Entering term {} as a follower of peer {}.",
                 receiving_peer.id,
                 self.term,
-                sending_peer_id,
+                self.leader_id,
+            );
+            receiving_peer.transition_role(
+                self.term,
+                Role::Follower(FollowerState::builder().leader_id(self.leader_id).build()),
             );
-            receiving_peer.role =
-                Role::Follower(FollowerState::builder().leader_id(sending_peer_id).build());
 
-            receiving_peer.storage.set_current_term(self.term).expect("TODO");
+            transaction.set_current_term(self.term);
         }
 
         match &mut receiving_peer.role {
             Role::Follower(follower_state) => {
-                follower_state.leader_id = Some(sending_peer_id);
+                if let Some(recorded_leader_id) = follower_state.leader_id
+                    && recorded_leader_id != self.leader_id
+                {
+                    log::warn!(
+                        "({}) Peer {} claims to be the leader of term {} \
+                            while peer {} is recorded as the leader, following peer {}.",
+                        receiving_peer.id,
+                        self.leader_id,
+                        self.term,
+                        recorded_leader_id,
+                        self.leader_id,
+                    );
+                }
+                follower_state.leader_id = Some(self.leader_id);
             },
             Role::Candidate(_) => {
                 log::info!(
                     "({}) Peer {} is elected to be the leader of term {}, \
                         so stepping down from the election to become a follower.",
                     receiving_peer.id,
-                    sending_peer_id,
+                    self.leader_id,
+                    current_term,
+                );
+                receiving_peer.transition_role(
                     current_term,
+                    Role::Follower(FollowerState::builder().leader_id(self.leader_id).build()),
                 );
-                receiving_peer.role =
-                    Role::Follower(FollowerState::builder().leader_id(sending_peer_id).build());
             },
             Role::Leader(_) => {
                 unreachable!();
             },
         }
 
+        let last_new_index =
+            self.entries.last().map(|entry| entry.index()).unwrap_or(self.prev_log_index);
+
+        let log = receiving_peer.storage.log();
+        let mut conflict_resolved = false;
         for new_entry in self.entries {
+            if !conflict_resolved {
+                match log.entry(new_entry.index()) {
+                    Some(existing_entry) if existing_entry.term() == new_entry.term() => {
+                        // Entry is already in the log, e.g., due to a retransmitted request.
+                        continue;
+                    },
+                    Some(existing_entry) => {
+                        log::info!(
+                            "({}) Removing `{:?}` and the entries after it \
+                                as they conflict with the log of the leader.",
+                            receiving_peer.id,
+                            existing_entry,
+                        );
+                        transaction.truncate_log(new_entry.index());
+                    },
+                    None => {},
+                }
+                conflict_resolved = true;
+            }
+
             log::info!(
                 "({}) Appending `{:?}` as instructed by the leader.",
                 receiving_peer.id,
                 new_entry
             );
-            receiving_peer.storage.append_log_entry(new_entry.clone()).expect("TODO");
+
+            transaction.append_log_entry(new_entry);
         }
+        receiving_peer.storage.apply_transaction(transaction).expect("TODO");
 
-        log::info!(
-            "({}) Setting commit index from {} to leaders commit index {}",
-            receiving_peer.id,
-            receiving_peer.commit_index,
-            self.leader_commit,
-        );
-        receiving_peer.commit_index = self.leader_commit;
+        // Entries after the last new entry are not known to match the log of the leader,
+        // and a reordered request might carry an older commit index of the leader.
+        let commit_index = receiving_peer.commit_index.max(self.leader_commit.min(last_new_index));
+        if commit_index != receiving_peer.commit_index {
+            log::info!(
+                "({}) Setting commit index from {} to {} as the leaders commit index is {}",
+                receiving_peer.id,
+                receiving_peer.commit_index,
+                commit_index,
+                self.leader_commit,
+            );
+            receiving_peer.commit_index = commit_index;
+        }
+        receiving_peer.note_valid_leader_contact();
 
         AppendEntriesReply::builder().term(current_term).success(true).build()
     }
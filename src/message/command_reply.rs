@@ -2,10 +2,18 @@ use crate::prelude::*;
 
 /// Reply to a [CommandRequest].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+#[serde(bound = "")]
 pub struct CommandReply<A: Application> {
     result: Result<A::CommandResult, ClientError<A>>,
 }
 
+impl<A: Application> CommandReply<A> {
+    /// Gets the result of the command.
+    pub fn result(&self) -> &Result<A::CommandResult, ClientError<A>> {
+        &self.result
+    }
+}
+
 impl<A: RaftApplication> CommandReply<A> {
     pub(crate) fn receive(
         self,
@@ -15,13 +23,23 @@ impl<A: RaftApplication> CommandReply<A> {
     ) {
         match self.result {
             Ok(result) => {
+                if receiving_client.commands.remove(&request_id).is_none() {
+                    log::debug!(
+                        "|{}| Peer {} replied to request {}, \
+                        which is either unknown or already been replied.",
+                        receiving_client.id,
+                        sending_peer_id,
+                        request_id,
+                    );
+                    return;
+                }
+
                 log::info!(
                     "|{}| Peer {} returned the result of request {}.",
                     receiving_client.id,
                     sending_peer_id,
                     request_id,
                 );
-                receiving_client.commands.remove(&request_id);
                 receiving_client.command_results.insert(request_id, Ok(result));
             },
             Err(error) => {
@@ -30,7 +48,7 @@ impl<A: RaftApplication> CommandReply<A> {
                         let command = match receiving_client.commands.get(&request_id) {
                             Some(command) => command,
                             None => {
-                                log::info!(
+                                log::debug!(
                                     "|{}| Peer {} replied to request {}, \
                                     which is either unknown or already been replied.",
                                     receiving_client.id,
@@ -57,15 +75,20 @@ impl<A: RaftApplication> CommandReply<A> {
                         );
                         receiving_client.leader = Some(*new_leader_id);
 
-                        let request = CommandRequest::builder().command(command.clone()).build();
-                        let transmit = ClientTransmit::builder()
+                        let request = CommandRequest::builder()
+                            .client_id(receiving_client.id)
+                            .command(command.clone())
+                            .build();
+                        let transmit = ClientTransmit::request()
                             .peer_id(*new_leader_id)
                             .client_id(receiving_client.id)
                             .request_id(request_id)
                             .message(request)
-                            .build();
+                            .build()
+                            .expect("Message of the transmit is a request");
 
                         receiving_client.buffered_client_transmits.push_back(transmit);
+                        receiving_client.request_attempts.insert(request_id, (*new_leader_id, 0));
                     },
                     ClientError::LeaderUnknown => {
                         log::info!(
@@ -73,7 +96,21 @@ impl<A: RaftApplication> CommandReply<A> {
                             receiving_client.id,
                             sending_peer_id,
                         );
-                        log::info!("|{}| Try commanding via another peer.", receiving_client.id);
+                        if receiving_client.commands.contains_key(&request_id) {
+                            receiving_client.await_leader(request_id);
+                        }
+                    },
+                    ClientError::NotLeaderRetryAfter { retry_after } => {
+                        log::info!(
+                            "|{}| Peer {} says it's not the leader as an election is in progress.",
+                            receiving_client.id,
+                            sending_peer_id,
+                        );
+                        log::info!(
+                            "|{}| Please try again after {} ticks.",
+                            receiving_client.id,
+                            retry_after,
+                        );
                     },
                     ClientError::StorageError { underlying_error } => {
                         log::info!(
@@ -84,7 +121,9 @@ impl<A: RaftApplication> CommandReply<A> {
                         );
                         log::info!("|{}| Please try again.", receiving_client.id);
                     },
-                    ClientError::EmptyCluster => unreachable!(),
+                    ClientError::EmptyCluster
+                    | ClientError::LeaderNotReady
+                    | ClientError::RequestIdAlreadyUsed { .. } => unreachable!(),
                 }
             },
         }
@@ -2,7 +2,11 @@ use crate::prelude::*;
 
 /// Request from a [Client] to a [Peer] to apply a [Command] to the replicated [Machine].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+#[serde(bound = "")]
 pub struct CommandRequest<A: Application> {
+    #[builder(into)]
+    client_id: ClientId,
+
     command: A::Command,
 }
 
@@ -13,6 +17,17 @@ impl<A: Application> CommandRequest<A> {
         request_id: RequestId,
         receiving_peer: &mut Peer<A>,
     ) -> Option<CommandReply<A>> {
+        if self.client_id != sending_client_id {
+            log::warn!(
+                "({}) Client {} sent request {} on behalf of client {}, ignoring.",
+                receiving_peer.id,
+                sending_client_id,
+                request_id,
+                self.client_id,
+            );
+            return None;
+        }
+
         log::info!(
             "({}) Client {} commands `{:?}` in its request {}.",
             receiving_peer.id,
@@ -21,10 +36,9 @@ impl<A: Application> CommandRequest<A> {
             request_id,
         );
 
-        let leader_state = match &mut receiving_peer.role {
-            Role::Leader(leader_state) => {
+        match &receiving_peer.role {
+            Role::Leader(_) => {
                 log::info!("({}) Processing the command as the leader.", receiving_peer.id);
-                leader_state
             },
             Role::Candidate(_) => {
                 log::info!(
@@ -33,8 +47,11 @@ impl<A: Application> CommandRequest<A> {
                     receiving_peer.id,
                     receiving_peer.current_term(),
                 );
+                let retry_after = Peer::<A>::CANDIDATE_RETRY_AFTER;
                 return Some(
-                    CommandReply::builder().result(Err(ClientError::LeaderUnknown)).build(),
+                    CommandReply::builder()
+                        .result(Err(ClientError::NotLeaderRetryAfter { retry_after }))
+                        .build(),
                 );
             },
             Role::Follower(follower_state) => {
@@ -60,35 +77,9 @@ impl<A: Application> CommandRequest<A> {
                     },
                 });
             },
-        };
-
-        let (prev_log_index, prev_log_term) = receiving_peer
-            .storage
-            .log()
-            .last()
-            .map(|entry| (entry.index(), entry.term()))
-            .unwrap_or((
-                receiving_peer.storage.snapshot().last_included_index(),
-                receiving_peer.storage.snapshot().last_included_term(),
-            ));
-
-        let log_entry = LogEntry::builder()
-            .index(prev_log_index.next())
-            .term(receiving_peer.storage.current_term())
-            .command(self.command)
-            .build();
+        }
 
-        log::info!(
-            "({}) Appending `{:?}` as the leader and instructing the peers to do the same.",
-            receiving_peer.id,
-            log_entry,
-        );
-        if let Err(error) = receiving_peer.storage.append_log_entry(log_entry.clone()) {
-            log::error!(
-                "({}) Failed to persistently append the log entry ({}).",
-                receiving_peer.id,
-                error,
-            );
+        if let Err(error) = receiving_peer.replicate_command(self.command) {
             log::info!("({}) Letting the user know about the failure.", receiving_peer.id);
             return Some(
                 CommandReply::builder()
@@ -97,30 +88,6 @@ impl<A: Application> CommandRequest<A> {
             );
         }
 
-        for peer_id in receiving_peer.cluster.iter() {
-            if *peer_id == receiving_peer.id {
-                continue;
-            }
-            let request = AppendEntriesRequest {
-                term: receiving_peer.storage.current_term(),
-                leader_id: receiving_peer.id,
-                prev_log_index,
-                prev_log_term,
-                entries: vec![log_entry.clone()],
-                leader_commit: receiving_peer.commit_index,
-            };
-
-            let request_id = receiving_peer.request_counter.next();
-            let transmit = PeerTransmit::builder()
-                .peer_id(*peer_id)
-                .request_id(request_id)
-                .message(request.clone())
-                .build();
-
-            leader_state.append_entries_requests.insert(transmit.request_id(), request);
-            receiving_peer.buffered_peer_transmits.push_back(transmit);
-        }
-
         None
     }
 }
@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+/// Reply to a [FindLeaderRequest].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+pub struct FindLeaderReply {
+    #[builder(into)]
+    leader_id: Option<PeerId>,
+}
+
+impl FindLeaderReply {
+    /// Gets the leader known by the replying peer.
+    pub fn leader_id(&self) -> Option<PeerId> {
+        self.leader_id
+    }
+}
+
+impl FindLeaderReply {
+    pub(crate) fn receive<A: Application>(
+        self,
+        sending_peer_id: PeerId,
+        request_id: RequestId,
+        receiving_client: &mut Client<A>,
+    ) {
+        if !receiving_client.leader_discoveries.remove(&request_id) {
+            log::debug!(
+                "|{}| Peer {} replied to request {}, \
+                which is either unknown or already been replied.",
+                receiving_client.id,
+                sending_peer_id,
+                request_id,
+            );
+            return;
+        }
+
+        match self.leader_id {
+            Some(leader_id) if leader_id == sending_peer_id => {
+                log::info!(
+                    "|{}| Peer {} says it's the leader, updating the leader to peer {}.",
+                    receiving_client.id,
+                    sending_peer_id,
+                    leader_id,
+                );
+                receiving_client.leader = Some(leader_id);
+                receiving_client.retry_awaiting_leader();
+            },
+            Some(leader_id) => {
+                if receiving_client.leader.is_some() {
+                    log::info!(
+                        "|{}| Peer {} says the leader is peer {}, \
+                        keeping the known leader as it might be a stale hint.",
+                        receiving_client.id,
+                        sending_peer_id,
+                        leader_id,
+                    );
+                    return;
+                }
+                log::info!(
+                    "|{}| Peer {} says the leader is peer {}, updating the leader to peer {}.",
+                    receiving_client.id,
+                    sending_peer_id,
+                    leader_id,
+                    leader_id,
+                );
+                receiving_client.leader = Some(leader_id);
+                receiving_client.retry_awaiting_leader();
+            },
+            None => {
+                log::info!(
+                    "|{}| Peer {} says it doesn't know the leader.",
+                    receiving_client.id,
+                    sending_peer_id,
+                );
+            },
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+
+/// Request from a [Client] to a [Peer] to learn the leader known by the peer.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+pub struct FindLeaderRequest {
+    #[builder(into)]
+    client_id: ClientId,
+}
+
+impl FindLeaderRequest {
+    pub(crate) fn receive<A: Application>(
+        self,
+        sending_client_id: ClientId,
+        request_id: RequestId,
+        receiving_peer: &Peer<A>,
+    ) -> Option<FindLeaderReply> {
+        if self.client_id != sending_client_id {
+            log::warn!(
+                "({}) Client {} sent request {} on behalf of client {}, ignoring.",
+                receiving_peer.id,
+                sending_client_id,
+                request_id,
+                self.client_id,
+            );
+            return None;
+        }
+
+        let leader_id = match &receiving_peer.role {
+            Role::Follower(follower_state) => follower_state.leader_id,
+            Role::Candidate(_) => None,
+            Role::Leader(_) => Some(receiving_peer.id),
+        };
+        match leader_id {
+            Some(leader_id) => {
+                log::info!(
+                    "({}) Client {} asks for the leader in its request {}, \
+                    replying with peer {}.",
+                    receiving_peer.id,
+                    sending_client_id,
+                    request_id,
+                    leader_id,
+                );
+            },
+            None => {
+                log::info!(
+                    "({}) Client {} asks for the leader in its request {}, \
+                    replying that the leader is not known.",
+                    receiving_peer.id,
+                    sending_client_id,
+                    request_id,
+                );
+            },
+        }
+
+        Some(FindLeaderReply::builder().maybe_leader_id(leader_id).build())
+    }
+}
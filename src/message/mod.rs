@@ -4,11 +4,13 @@ use crate::prelude::*;
 
 mod append_entries_request;
 mod command_request;
+mod find_leader_request;
 mod query_request;
 mod request_vote_request;
 
 mod append_entries_reply;
 mod command_reply;
+mod find_leader_reply;
 mod query_reply;
 mod request_vote_reply;
 
@@ -17,6 +19,8 @@ pub use {
     append_entries_request::AppendEntriesRequest,
     command_reply::CommandReply,
     command_request::CommandRequest,
+    find_leader_reply::FindLeaderReply,
+    find_leader_request::FindLeaderRequest,
     query_reply::QueryReply,
     query_request::QueryRequest,
     request_vote_reply::{
@@ -28,6 +32,7 @@ pub use {
 
 /// Message between a [Peer] and another [Peer].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, derive_more::From)]
+#[serde(bound = "")]
 pub enum PeerMessage<A: Application> {
     RequestVoteRequest(#[from] RequestVoteRequest),
     RequestVoteReply(#[from] RequestVoteReply),
@@ -50,22 +55,36 @@ impl<A: Application> PeerMessage<A> {
 
 /// Message between a [Peer] and a [Client].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, derive_more::From)]
+#[serde(bound = "")]
 pub enum ClientMessage<A: Application> {
     CommandRequest(#[from] CommandRequest<A>),
     CommandReply(#[from] CommandReply<A>),
 
     QueryRequest(#[from] QueryRequest<A>),
     QueryReply(#[from] QueryReply<A>),
+
+    FindLeaderRequest(#[from] FindLeaderRequest),
+    FindLeaderReply(#[from] FindLeaderReply),
 }
 
 impl<A: Application> ClientMessage<A> {
     /// Gets whether the message is a request.
     pub fn is_request(&self) -> bool {
-        matches!(self, ClientMessage::CommandRequest(_) | ClientMessage::QueryRequest(_))
+        matches!(
+            self,
+            ClientMessage::CommandRequest(_)
+                | ClientMessage::QueryRequest(_)
+                | ClientMessage::FindLeaderRequest(_)
+        )
     }
 
     /// Gets whether the message is a reply.
     pub fn is_reply(&self) -> bool {
-        matches!(self, ClientMessage::CommandReply(_) | ClientMessage::QueryReply(_))
+        matches!(
+            self,
+            ClientMessage::CommandReply(_)
+                | ClientMessage::QueryReply(_)
+                | ClientMessage::FindLeaderReply(_)
+        )
     }
 }
@@ -2,10 +2,18 @@ use crate::prelude::*;
 
 /// Reply to a [QueryRequest].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+#[serde(bound = "")]
 pub struct QueryReply<A: Application> {
     result: Result<A::QueryResult, ClientError<A>>,
 }
 
+impl<A: Application> QueryReply<A> {
+    /// Gets the result of the query.
+    pub fn result(&self) -> &Result<A::QueryResult, ClientError<A>> {
+        &self.result
+    }
+}
+
 impl<A: RaftApplication> QueryReply<A> {
     pub(crate) fn receive(
         self,
@@ -15,22 +23,48 @@ impl<A: RaftApplication> QueryReply<A> {
     ) {
         match self.result {
             Ok(result) => {
+                if receiving_client.queries.remove(&request_id).is_none() {
+                    log::debug!(
+                        "|{}| Peer {} replied to request {}, \
+                        which is either unknown or already been replied.",
+                        receiving_client.id,
+                        sending_peer_id,
+                        request_id,
+                    );
+                    return;
+                }
+
                 log::info!(
                     "|{}| Peer {} returned the result of request {}.",
                     receiving_client.id,
                     sending_peer_id,
                     request_id,
                 );
-                receiving_client.queries.remove(&request_id);
                 receiving_client.query_results.insert(request_id, Ok(result));
             },
             Err(error) => {
                 match &error {
+                    ClientError::LeaderChanged { new_leader_id }
+                        if receiving_client.consistency == Consistency::Eventual =>
+                    {
+                        log::info!(
+                            "|{}| Peer {} says the leader is peer {}, \
+                            but its local answer is enough as the cluster is eventually consistent.",
+                            receiving_client.id,
+                            sending_peer_id,
+                            new_leader_id,
+                        );
+                        log::info!(
+                            "|{}| Try querying peer {} again.",
+                            receiving_client.id,
+                            sending_peer_id,
+                        );
+                    },
                     ClientError::LeaderChanged { new_leader_id } => {
                         let query = match receiving_client.queries.get(&request_id) {
                             Some(query) => query,
                             None => {
-                                log::info!(
+                                log::debug!(
                                     "|{}| Peer {} replied to request {}, \
                                     which is either unknown or already been replied.",
                                     receiving_client.id,
@@ -57,15 +91,20 @@ impl<A: RaftApplication> QueryReply<A> {
                         );
                         receiving_client.leader = Some(*new_leader_id);
 
-                        let request = QueryRequest::builder().query(query.clone()).build();
-                        let transmit = ClientTransmit::builder()
+                        let request = QueryRequest::builder()
+                            .client_id(receiving_client.id)
+                            .query(query.clone())
+                            .build();
+                        let transmit = ClientTransmit::request()
                             .peer_id(*new_leader_id)
                             .client_id(receiving_client.id)
                             .request_id(request_id)
                             .message(request)
-                            .build();
+                            .build()
+                            .expect("Message of the transmit is a request");
 
                         receiving_client.buffered_client_transmits.push_back(transmit);
+                        receiving_client.request_attempts.insert(request_id, (*new_leader_id, 0));
                     },
                     ClientError::LeaderUnknown => {
                         log::info!(
@@ -73,7 +112,29 @@ impl<A: RaftApplication> QueryReply<A> {
                             receiving_client.id,
                             sending_peer_id,
                         );
-                        log::info!("|{}| Try querying via another peer.", receiving_client.id);
+                        if receiving_client.queries.contains_key(&request_id) {
+                            receiving_client.await_leader(request_id);
+                        }
+                    },
+                    ClientError::NotLeaderRetryAfter { retry_after } => {
+                        log::info!(
+                            "|{}| Peer {} says it's not the leader as an election is in progress.",
+                            receiving_client.id,
+                            sending_peer_id,
+                        );
+                        log::info!(
+                            "|{}| Please try again after {} ticks.",
+                            receiving_client.id,
+                            retry_after,
+                        );
+                    },
+                    ClientError::LeaderNotReady => {
+                        log::info!(
+                            "|{}| Peer {} says it's the leader but it's not ready to serve queries.",
+                            receiving_client.id,
+                            sending_peer_id,
+                        );
+                        log::info!("|{}| Please try again.", receiving_client.id);
                     },
                     ClientError::StorageError { underlying_error } => {
                         log::info!(
@@ -84,7 +145,9 @@ impl<A: RaftApplication> QueryReply<A> {
                         );
                         log::info!("|{}| Please try again.", receiving_client.id);
                     },
-                    ClientError::EmptyCluster => unreachable!(),
+                    ClientError::EmptyCluster | ClientError::RequestIdAlreadyUsed { .. } => {
+                        unreachable!()
+                    },
                 }
             },
         }
@@ -2,7 +2,11 @@ use crate::prelude::*;
 
 /// Request from a [Client] to a [Peer] to make a [Query] on the replicated [Machine].
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
+#[serde(bound = "")]
 pub struct QueryRequest<A: Application> {
+    #[builder(into)]
+    client_id: ClientId,
+
     query: A::Query,
 }
 
@@ -13,6 +17,17 @@ impl<A: Application> QueryRequest<A> {
         request_id: RequestId,
         receiving_peer: &mut Peer<A>,
     ) -> Option<QueryReply<A>> {
+        if self.client_id != sending_client_id {
+            log::warn!(
+                "({}) Client {} sent request {} on behalf of client {}, ignoring.",
+                receiving_peer.id,
+                sending_client_id,
+                request_id,
+                self.client_id,
+            );
+            return None;
+        }
+
         log::info!(
             "({}) Client {} queries `{:?}` in its request {}.",
             receiving_peer.id,
@@ -51,7 +66,12 @@ impl<A: Application> QueryRequest<A> {
                     receiving_peer.id,
                     receiving_peer.current_term(),
                 );
-                return Some(QueryReply::builder().result(Err(ClientError::LeaderUnknown)).build());
+                let retry_after = Peer::<A>::CANDIDATE_RETRY_AFTER;
+                return Some(
+                    QueryReply::builder()
+                        .result(Err(ClientError::NotLeaderRetryAfter { retry_after }))
+                        .build(),
+                );
             },
             Role::Follower(follower_state) => {
                 return Some(match follower_state.leader_id {
@@ -78,8 +98,92 @@ impl<A: Application> QueryRequest<A> {
             },
         }
 
-        // TODO
+        if !receiving_peer.can_serve_linearizable_read() {
+            log::info!(
+                "({}) Not running the query as no entry from term {} is committed yet \
+                and letting the client know.",
+                receiving_peer.id,
+                receiving_peer.current_term(),
+            );
+            return Some(QueryReply::builder().result(Err(ClientError::LeaderNotReady)).build());
+        }
+
+        match receiving_peer.consistency {
+            Consistency::Strong => {
+                // TODO
+                None
+            },
+            Consistency::Eventual => unreachable!(),
+            Consistency::LeaseRead if receiving_peer.holds_lease() => {
+                if receiving_peer.last_applied < receiving_peer.commit_index {
+                    log::info!(
+                        "({}) Applying committed entries before running the query.",
+                        receiving_peer.id,
+                    );
+                    receiving_peer.apply_committed();
+                }
+
+                log::info!(
+                    "({}) Running the query as the leader holding a lease \
+                        and returning the result to the client.",
+                    receiving_peer.id,
+                );
 
-        None
+                let query_result = receiving_peer.machine.query(&self.query);
+                Some(QueryReply::builder().result(Ok(query_result)).build())
+            },
+            Consistency::LeaseRead | Consistency::ReadIndex => {
+                let read_index = receiving_peer.commit_index;
+                log::info!(
+                    "({}) Confirming leadership with a heartbeat round \
+                        before running the query at read index {}.",
+                    receiving_peer.id,
+                    read_index,
+                );
+
+                let heartbeat_request_ids = receiving_peer.broadcast_heartbeat();
+                if let Role::Leader(leader_state) = &mut receiving_peer.role {
+                    leader_state.pending_reads.push(PendingRead {
+                        client_id: sending_client_id,
+                        request_id,
+                        query: self.query,
+                        read_index,
+                        heartbeat_request_ids,
+                        acknowledged_by: BTreeSet::new(),
+                    });
+                }
+
+                None
+            },
+            Consistency::LogRead => {
+                log::info!(
+                    "({}) Appending a no-op entry to run the query right after applying it.",
+                    receiving_peer.id,
+                );
+                match receiving_peer.replicate_command(A::Command::no_op()) {
+                    Ok(index) => {
+                        if let Role::Leader(leader_state) = &mut receiving_peer.role {
+                            leader_state.log_reads.entry(index).or_default().push(LogRead {
+                                client_id: sending_client_id,
+                                request_id,
+                                query: self.query,
+                            });
+                        }
+                        None
+                    },
+                    Err(error) => {
+                        log::info!(
+                            "({}) Letting the client know about the failure.",
+                            receiving_peer.id,
+                        );
+                        Some(
+                            QueryReply::builder()
+                                .result(Err(ClientError::StorageError { underlying_error: error }))
+                                .build(),
+                        )
+                    },
+                }
+            },
+        }
     }
 }
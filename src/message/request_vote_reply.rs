@@ -11,6 +11,11 @@ pub enum Vote {
     NotGrantedDueToBeingGrantedToAnotherPeer,
     /// Vote is not granted due to a storage error.
     NotGrantedDueToStorageError,
+    /// Vote is not granted due to requested peer hearing from a leader within the last
+    /// [Peer::LEASE_DURATION] ticks, which is still considered healthy.
+    ///
+    /// Only peers with [Peer::set_leader_stickiness] enabled deny votes this way.
+    NotGrantedDueToActiveLeaderLease,
     /// Vote is granted.
     Granted,
 }
@@ -68,7 +73,7 @@ impl RequestVoteReply {
             };
 
             log::info!("({}) Stepping down to become a follower.", receiving_peer_id);
-            receiving_peer.role = Role::Follower(FollowerState::default());
+            receiving_peer.transition_role(self.term, Role::Follower(FollowerState::default()));
 
             receiving_peer.buffered_peer_transmits.retain(|transmit| {
                 !matches!(transmit.message(), PeerMessage::RequestVoteRequest(..))
@@ -99,7 +104,7 @@ impl RequestVoteReply {
             },
         };
 
-        if !candidate_state.vote_request_ids().contains(&request_id) {
+        if !candidate_state.vote_requests().contains_key(&request_id) {
             log::info!(
                 "({}) Peer {} replied to an old vote request, which will be ignored.",
                 receiving_peer.id,
@@ -110,7 +115,18 @@ impl RequestVoteReply {
 
         match self.vote {
             Vote::Granted => {
-                assert_eq!(self.term, current_term);
+                // Request ids are not unique across restarts of the peer, so a vote granted
+                // in a previous election can carry the request id of a vote request of this one.
+                if self.term != current_term {
+                    log::info!(
+                        "({}) Peer {} granted vote for the election in term {}, \
+                        which will be ignored.",
+                        receiving_peer_id,
+                        sending_peer_id,
+                        self.term,
+                    );
+                    return;
+                }
 
                 log::info!("({}) Peer {} granted vote.", receiving_peer_id, sending_peer_id);
                 candidate_state.grant_vote(request_id);
@@ -144,24 +160,43 @@ impl RequestVoteReply {
                             .log()
                             .last()
                             .map(|entry| entry.index())
-                            .unwrap_or(receiving_peer.snapshot().last_included_index()),
+                            .unwrap_or(receiving_peer.storage.snapshot_metadata().0),
                     )
                     .last_log_term(
                         receiving_peer
                             .log()
                             .last()
                             .map(|entry| entry.term())
-                            .unwrap_or(receiving_peer.snapshot().last_included_term()),
+                            .unwrap_or(receiving_peer.storage.snapshot_metadata().1),
                     )
                     .build();
-                let transmit = PeerTransmit::builder()
+                let transmit = PeerTransmit::request()
                     .peer_id(sending_peer_id)
                     .request_id(request_id)
                     .message(request)
-                    .build();
+                    .build()
+                    .expect("Message of the transmit is a request");
                 receiving_peer.buffered_peer_transmits.push_back(transmit);
             },
-            Vote::NotGrantedDueToBeingInHigherTerm => {},
+            Vote::NotGrantedDueToBeingInHigherTerm => {
+                // Replies from peers in higher terms are handled above, so reaching here means
+                // the reply claims a higher term without carrying it. Candidate cannot catch up
+                // to a term it doesn't know, but it's behind either way, so it steps down.
+                log::warn!(
+                    "({}) Peer {} didn't grant vote as it's in a higher term \
+                    but replied with the current term {}.",
+                    receiving_peer_id,
+                    sending_peer_id,
+                    self.term,
+                );
+
+                log::info!("({}) Stepping down to become a follower.", receiving_peer_id);
+                receiving_peer.transition_role(self.term, Role::Follower(FollowerState::default()));
+
+                receiving_peer.buffered_peer_transmits.retain(|transmit| {
+                    !matches!(transmit.message(), PeerMessage::RequestVoteRequest(..))
+                });
+            },
             Vote::NotGrantedDueToBeingLessUpToDate => {
                 log::info!(
                     "({}) Peer {} didn't grant vote as its log is more up to date.",
@@ -176,6 +211,13 @@ impl RequestVoteReply {
                     sending_peer_id,
                 );
             },
+            Vote::NotGrantedDueToActiveLeaderLease => {
+                log::info!(
+                    "({}) Peer {} didn't grant vote as it heard from its leader recently.",
+                    receiving_peer_id,
+                    sending_peer_id,
+                );
+            },
         }
     }
 }
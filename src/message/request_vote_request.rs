@@ -60,6 +60,26 @@ impl RequestVoteRequest {
             }
         }
 
+        if self.term > current_term
+            && receiving_peer.leader_stickiness
+            && let Role::Follower(follower_state) = &receiving_peer.role
+            && let Some(leader_id) = follower_state.leader_id
+            && let Some(since_last_valid_leader_contact) =
+                receiving_peer.since_last_valid_leader_contact()
+            && since_last_valid_leader_contact < Peer::<A>::LEASE_DURATION
+        {
+            log::info!(
+                "({}) Not granting vote to peer {} because \
+                    leader {} contacted it {} ticks ago, which is within the lease.",
+                receiving_peer.id,
+                sending_peer_id,
+                leader_id,
+                since_last_valid_leader_contact,
+            );
+            reply.set_vote(Vote::NotGrantedDueToActiveLeaderLease);
+            return reply;
+        }
+
         if self.term > current_term {
             log::info!(
                 "({}) Voted term {} is higher than the current term {}, \
@@ -89,6 +109,28 @@ impl RequestVoteRequest {
             }
 
             reply.set_term(self.term);
+
+            match &mut receiving_peer.role {
+                Role::Follower(follower_state) => {
+                    if let Some(stale_leader_id) = follower_state.leader_id.take() {
+                        log::info!(
+                            "({}) Forgetting peer {} as the leader \
+                                since it's the leader of an older term.",
+                            receiving_peer.id,
+                            stale_leader_id,
+                        );
+                    }
+                },
+                Role::Candidate(_) | Role::Leader(_) => {
+                    log::info!("({}) Stepping down to become a follower.", receiving_peer.id);
+                    receiving_peer
+                        .transition_role(self.term, Role::Follower(FollowerState::default()));
+
+                    receiving_peer.buffered_peer_transmits.retain(|transmit| {
+                        !matches!(transmit.message(), PeerMessage::RequestVoteRequest(..))
+                    });
+                },
+            }
         }
 
         if let Some(voted_peer_id) = receiving_peer.voted_for() {
@@ -105,16 +147,7 @@ impl RequestVoteRequest {
             return reply;
         }
 
-        let (last_log_index, last_log_term) =
-            receiving_peer.log().last().map(|entry| (entry.index(), entry.term())).unwrap_or((
-                receiving_peer.snapshot().last_included_index(),
-                receiving_peer.snapshot().last_included_term(),
-            ));
-
-        let is_at_least_as_up_to_date = self.last_log_term > last_log_term
-            || (self.last_log_term == last_log_term && self.last_log_index >= last_log_index);
-
-        if !is_at_least_as_up_to_date {
+        if !receiving_peer.log_is_at_least_as_up_to_date(self.last_log_index, self.last_log_term) {
             log::info!(
                 "({}) Not granting vote to peer {} because it's log is not as up to date.",
                 receiving_peer.id,
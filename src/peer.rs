@@ -9,12 +9,17 @@ pub struct Peer<A: Application> {
     pub(crate) consistency: Consistency,
 
     pub(crate) role: Role<A>,
+    pub(crate) role_history: VecDeque<RoleTransition>,
     pub(crate) machine: A::Machine,
     pub(crate) storage: A::Storage,
 
     pub(crate) commit_index: LogIndex,
     pub(crate) last_applied: LogIndex,
 
+    pub(crate) time: usize,
+    pub(crate) last_valid_leader_contact: Option<usize>,
+    pub(crate) max_inflight_per_peer: Option<usize>,
+    pub(crate) leader_stickiness: bool,
     pub(crate) request_counter: RequestCounter,
 
     pub(crate) buffered_peer_transmits: VecDeque<PeerTransmit<A>>,
@@ -30,16 +35,31 @@ impl<A: Application> Peer<A> {
         storage: A::Storage,
     ) -> Self {
         let role = Role::default();
+        let role_history = VecDeque::default();
 
         let snapshot = storage.snapshot();
+        let cluster = snapshot.cluster().cloned().unwrap_or(cluster);
         let machine = snapshot.machine().clone();
 
         let commit_index = snapshot.last_included_index();
         let last_applied = snapshot.last_included_index();
 
-        let request_counter = RequestCounter::default();
+        let time = 0;
+        let last_valid_leader_contact = None;
+        let max_inflight_per_peer = None;
+        let leader_stickiness = false;
+        // Transmits restored from the storage were buffered before a restart,
+        // so new requests shouldn't reuse their request ids.
+        let buffered_peer_transmits = VecDeque::from(storage.buffered_peer_transmits());
+        let request_counter = RequestCounter::starting_from(
+            buffered_peer_transmits
+                .iter()
+                .filter(|transmit| transmit.message().is_request())
+                .map(|transmit| transmit.request_id().0 + 1)
+                .max()
+                .unwrap_or(0),
+        );
 
-        let buffered_peer_transmits = VecDeque::default();
         let buffered_client_transmits = VecDeque::default();
 
         Self {
@@ -47,10 +67,15 @@ impl<A: Application> Peer<A> {
             cluster,
             consistency,
             role,
+            role_history,
             machine,
             storage,
             commit_index,
             last_applied,
+            time,
+            last_valid_leader_contact,
+            max_inflight_per_peer,
+            leader_stickiness,
             request_counter,
             buffered_peer_transmits,
             buffered_client_transmits,
@@ -58,6 +83,47 @@ impl<A: Application> Peer<A> {
     }
 }
 
+impl<A: Application> Clone for Peer<A>
+where
+    A::Storage: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            cluster: self.cluster.clone(),
+            consistency: self.consistency,
+            role: self.role.clone(),
+            role_history: self.role_history.clone(),
+            machine: self.machine.clone(),
+            storage: self.storage.clone(),
+            commit_index: self.commit_index,
+            last_applied: self.last_applied,
+            time: self.time,
+            last_valid_leader_contact: self.last_valid_leader_contact,
+            max_inflight_per_peer: self.max_inflight_per_peer,
+            leader_stickiness: self.leader_stickiness,
+            request_counter: self.request_counter.clone(),
+            buffered_peer_transmits: self.buffered_peer_transmits.clone(),
+            buffered_client_transmits: self.buffered_client_transmits.clone(),
+        }
+    }
+}
+
+impl<A: Application> Peer<A> {
+    /// Maximum number of role transitions kept in the role history of peers.
+    pub const ROLE_HISTORY_CAPACITY: usize = 32;
+
+    /// Number of ticks clients are advised to wait before retrying
+    /// when their requests are rejected by candidates during elections.
+    pub const CANDIDATE_RETRY_AFTER: usize = 10;
+
+    /// Number of ticks leaders can serve reads without confirming their leadership
+    /// after the majority of the peers acknowledged their contact.
+    ///
+    /// It must be shorter than the election timeout of the peers for leases to be safe.
+    pub const LEASE_DURATION: usize = 10;
+}
+
 impl<A: Application> Peer<A> {
     /// Gets the identifier of the peer.
     pub fn id(&self) -> PeerId {
@@ -79,6 +145,13 @@ impl<A: Application> Peer<A> {
         &self.role
     }
 
+    /// Gets the recent role transitions of the peer, from the oldest to the newest.
+    ///
+    /// Only the last [Peer::ROLE_HISTORY_CAPACITY] transitions are kept.
+    pub fn role_history(&self) -> &VecDeque<RoleTransition> {
+        &self.role_history
+    }
+
     /// Gets the machine of the peer.
     pub fn machine(&self) -> &A::Machine {
         &self.machine
@@ -89,6 +162,11 @@ impl<A: Application> Peer<A> {
         &self.storage
     }
 
+    /// Consumes the peer to get its storage, e.g., to restart the peer from it.
+    pub fn into_storage(self) -> A::Storage {
+        self.storage
+    }
+
     /// Gets the current term of the peer.
     pub fn current_term(&self) -> Term {
         self.storage.current_term()
@@ -104,6 +182,25 @@ impl<A: Application> Peer<A> {
         self.storage.log()
     }
 
+    /// Looks up the log entry with the given index, taking the snapshot into account.
+    pub fn lookup_log_entry(&self, index: LogIndex) -> LogLookup<'_, A> {
+        self.log().lookup(self.snapshot(), index)
+    }
+
+    /// Gets the log of the peer alongside the status of each entry.
+    pub fn annotated_log(&self) -> impl Iterator<Item = (&LogEntry<A>, EntryStatus)> {
+        self.log().iter().map(|entry| {
+            let status = if entry.index() <= self.last_applied {
+                EntryStatus::Applied
+            } else if entry.index() <= self.commit_index {
+                EntryStatus::Committed
+            } else {
+                EntryStatus::Uncommitted
+            };
+            (entry, status)
+        })
+    }
+
     /// Gets the latest snapshot of the peer.
     pub fn snapshot(&self) -> &Snapshot<A> {
         self.storage.snapshot()
@@ -119,6 +216,158 @@ impl<A: Application> Peer<A> {
         self.last_applied
     }
 
+    /// Gets the request id that will be assigned to the next request of the peer.
+    pub fn peek_next_request_id(&self) -> RequestId {
+        RequestId(self.request_counter.peek())
+    }
+
+    /// Gets whether a log ending with the given index and term is at least as up to date
+    /// as the log of the peer.
+    ///
+    /// The log with the later last term is more up to date. If the last terms are the same,
+    /// the longer log is more up to date. Entries compacted into the snapshot are considered.
+    pub fn log_is_at_least_as_up_to_date(
+        &self,
+        last_log_index: LogIndex,
+        last_log_term: Term,
+    ) -> bool {
+        let (own_last_log_index, own_last_log_term) = self
+            .log()
+            .last()
+            .map(|entry| (entry.index(), entry.term()))
+            .unwrap_or(self.storage.snapshot_metadata());
+
+        last_log_term > own_last_log_term
+            || (last_log_term == own_last_log_term && last_log_index >= own_last_log_index)
+    }
+
+    /// Gets whether the peer can serve linearizable reads.
+    ///
+    /// Only leaders which committed an entry from their current term can do so,
+    /// as they might not know the latest committed entries of the cluster otherwise.
+    /// The no-op entry appended upon election is what makes it possible early in a term.
+    pub fn can_serve_linearizable_read(&self) -> bool {
+        match &self.role {
+            Role::Leader(leader_state) => leader_state.initial_noop_committed(),
+            _ => false,
+        }
+    }
+
+    /// Gets whether the peer holds a leader lease to serve linearizable reads right away.
+    ///
+    /// Only leaders which can serve linearizable reads and whose contact is acknowledged
+    /// by the majority of the peers within the last [Peer::LEASE_DURATION] ticks hold a lease.
+    /// Leases are never held without leader stickiness, as other leaders can be elected meanwhile.
+    pub fn holds_lease(&self) -> bool {
+        if !self.leader_stickiness {
+            return false;
+        }
+        let Role::Leader(leader_state) = &self.role else {
+            return false;
+        };
+        if !leader_state.initial_noop_committed() {
+            return false;
+        }
+
+        let acknowledged = leader_state
+            .last_leader_contact
+            .iter()
+            .filter(|(peer_id, contact)| {
+                **peer_id != self.id && self.time < **contact + Self::LEASE_DURATION
+            })
+            .count();
+
+        acknowledged + 1 >= self.majority()
+    }
+
+    /// Gets the logical time of the peer in ticks.
+    pub fn time(&self) -> usize {
+        self.time
+    }
+
+    /// Gets the time of the latest append entries request accepted from the leader.
+    ///
+    /// Drivers should reschedule the election timeout of the peer when it changes.
+    pub fn last_valid_leader_contact(&self) -> Option<usize> {
+        self.last_valid_leader_contact
+    }
+
+    /// Gets how many ticks passed since the latest append entries request accepted from the leader.
+    pub fn since_last_valid_leader_contact(&self) -> Option<usize> {
+        self.last_valid_leader_contact.map(|contact| self.time - contact)
+    }
+
+    /// Gets how many peers, including the leader itself, replicated the last log entry
+    /// of the leader, and how many peers are required for the majority.
+    ///
+    /// A write could commit right away if the former reaches the latter.
+    /// Returns `None` if the peer is not the leader.
+    pub fn quorum_status(&self) -> Option<(usize, usize)> {
+        let Role::Leader(leader_state) = &self.role else {
+            return None;
+        };
+
+        let last_log_index = self
+            .log()
+            .last()
+            .map(|entry| entry.index())
+            .unwrap_or(self.storage.snapshot_metadata().0);
+        let replicated = leader_state
+            .match_index()
+            .iter()
+            .filter(|(peer_id, match_index)| {
+                **peer_id != self.id && **match_index == last_log_index
+            })
+            .count();
+
+        Some((replicated + 1, self.majority()))
+    }
+
+    /// Gets a single line summary of the status of the peer for compact logging.
+    ///
+    /// It's formatted as `Peer 3 | Leader | term=4 | commit=12 | applied=12 | log=13`,
+    /// where `log` is the index of the last log entry.
+    pub fn debug_summary(&self) -> String {
+        let last_log_index = self
+            .log()
+            .last()
+            .map(|entry| entry.index())
+            .unwrap_or(self.storage.snapshot_metadata().0);
+        format!(
+            "Peer {} | {} | term={} | commit={} | applied={} | log={}",
+            self.id,
+            self.role.kind(),
+            self.current_term(),
+            self.commit_index,
+            self.last_applied,
+            last_log_index,
+        )
+    }
+
+    /// Gets the maximum number of append entries requests the peer keeps in flight
+    /// to each other peer as the leader, if there is a limit.
+    pub fn max_inflight_per_peer(&self) -> Option<usize> {
+        self.max_inflight_per_peer
+    }
+
+    /// Gets whether the peer denies votes to candidates while the lease of its leader is active.
+    pub fn leader_stickiness(&self) -> bool {
+        self.leader_stickiness
+    }
+
+    /// Gets the number of append entries requests in flight to a peer
+    /// that are neither replied nor considered lost yet.
+    pub fn inflight_append_entries(&self, peer_id: PeerId) -> usize {
+        let Role::Leader(leader_state) = &self.role else {
+            return 0;
+        };
+        leader_state
+            .inflight_append_entries
+            .get(&peer_id)
+            .map(|requests| requests.len())
+            .unwrap_or(0)
+    }
+
     /// Gets the buffered peer transmits of the peer.
     pub fn buffered_peer_transmits(&self) -> &VecDeque<PeerTransmit<A>> {
         &self.buffered_peer_transmits
@@ -131,6 +380,45 @@ impl<A: Application> Peer<A> {
 }
 
 impl<A: Application> Peer<A> {
+    /// Sets the maximum number of append entries requests the peer keeps in flight
+    /// to each other peer as the leader.
+    ///
+    /// New log entries are held back from the peers at the limit, and sent
+    /// once their requests in flight are replied. Requests that are still in flight
+    /// after a whole heartbeat interval, i.e., sent before the previous heartbeat timeout,
+    /// are considered lost when the heartbeat times out. No limit is applied if `None`.
+    pub fn set_max_inflight_per_peer(&mut self, max_inflight_per_peer: Option<usize>) {
+        self.max_inflight_per_peer = max_inflight_per_peer;
+    }
+
+    /// Sets whether the peer denies votes to candidates while the lease of its leader is active.
+    ///
+    /// Leases are measured in the logical time of the peer, which only moves when
+    /// the driver advances it, so it's disabled by default. Enabling it without advancing
+    /// time prevents followers from ever voting for a new leader once a leader contacts them.
+    pub fn set_leader_stickiness(&mut self, leader_stickiness: bool) {
+        self.leader_stickiness = leader_stickiness;
+    }
+
+    /// Advances the logical time of the peer.
+    pub fn advance_time(&mut self, ticks: usize) {
+        self.time += ticks;
+    }
+
+    /// Persists the buffered peer transmits of the peer to restore them after a restart.
+    ///
+    /// Drivers should call it after handling events, it does nothing unless
+    /// the storage of the peer persists buffered transmits.
+    pub fn persist_buffered_peer_transmits(&mut self) -> Result<(), A::StorageError> {
+        self.storage.set_buffered_peer_transmits(&self.buffered_peer_transmits)
+    }
+
+    /// Records that the peer accepted an append entries request from the leader,
+    /// which resets its election timer.
+    pub(crate) fn note_valid_leader_contact(&mut self) {
+        self.last_valid_leader_contact = Some(self.time);
+    }
+
     /// Triggers an election timout on the peer.
     pub fn trigger_election_timeout(&mut self) {
         log::info!("({}) Election timed out.", self.id);
@@ -166,39 +454,140 @@ impl<A: Application> Peer<A> {
                 self.log()
                     .last()
                     .map(|entry| entry.index())
-                    .unwrap_or(self.snapshot().last_included_index()),
+                    .unwrap_or(self.storage.snapshot_metadata().0),
             )
             .last_log_term(
                 self.log()
                     .last()
                     .map(|entry| entry.term())
-                    .unwrap_or(self.snapshot().last_included_term()),
+                    .unwrap_or(self.storage.snapshot_metadata().1),
             )
             .build();
 
-        let mut request_ids = BTreeSet::new();
+        let mut vote_requests = BTreeMap::new();
         for peer_id in self.cluster.iter().copied() {
             if peer_id == self.id {
                 continue;
             }
 
             let request_id = self.request_counter.next();
-            let transmit = PeerTransmit::builder()
+            let transmit = PeerTransmit::request()
                 .peer_id(peer_id)
                 .request_id(request_id)
                 .message(request.clone())
-                .build();
-            request_ids.insert(transmit.request_id());
+                .build()
+                .expect("Message of the transmit is a request");
+            vote_requests.insert(transmit.request_id(), peer_id);
             self.buffered_peer_transmits.push_back(transmit);
         }
 
-        self.role = Role::Candidate(
-            CandidateState::builder().votes_granted(1).vote_request_ids(request_ids).build(),
+        self.transition_role(
+            self.current_term(),
+            Role::Candidate(
+                CandidateState::builder()
+                    .term(new_term)
+                    .votes_granted(1)
+                    .vote_requests(vote_requests)
+                    .build(),
+            ),
         );
     }
 
+    /// Retransmits awaited vote requests of the peer which are no longer buffered.
+    ///
+    /// Vote requests can be lost in transit, which would stall the election until the next
+    /// election timeout. Retransmitted requests keep their request ids, so whichever reply
+    /// arrives first is counted and the others are ignored. Nothing is retransmitted once
+    /// the term of the peer moves past the term of its election.
+    pub fn retransmit_vote_requests(&mut self) {
+        let Role::Candidate(candidate_state) = &self.role else {
+            log::warn!(
+                "({}) Vote requests are not retransmitted as {}.",
+                self.id,
+                match self.role {
+                    Role::Follower(_) => "a follower",
+                    Role::Leader(_) => "a leader",
+                    Role::Candidate(_) => unreachable!(),
+                }
+            );
+            return;
+        };
+
+        if candidate_state.term != self.current_term() {
+            log::warn!(
+                "({}) Vote requests for term {} are not retransmitted as the current term is {}.",
+                self.id,
+                candidate_state.term,
+                self.current_term(),
+            );
+            return;
+        }
+
+        let request = RequestVoteRequest::builder()
+            .term(candidate_state.term)
+            .candidate_id(self.id)
+            .last_log_index(
+                self.log()
+                    .last()
+                    .map(|entry| entry.index())
+                    .unwrap_or(self.storage.snapshot_metadata().0),
+            )
+            .last_log_term(
+                self.log()
+                    .last()
+                    .map(|entry| entry.term())
+                    .unwrap_or(self.storage.snapshot_metadata().1),
+            )
+            .build();
+
+        for (request_id, peer_id) in candidate_state.vote_requests.iter() {
+            let is_buffered = self.buffered_peer_transmits.iter().any(|transmit| {
+                transmit.message().is_request()
+                    && transmit.peer_id() == *peer_id
+                    && transmit.request_id() == *request_id
+            });
+            if is_buffered {
+                continue;
+            }
+
+            log::info!(
+                "({}) Requesting vote from peer {} again with request {}.",
+                self.id,
+                peer_id,
+                request_id,
+            );
+            let transmit = PeerTransmit::request()
+                .peer_id(*peer_id)
+                .request_id(*request_id)
+                .message(request.clone())
+                .build()
+                .expect("Message of the transmit is a request");
+            self.buffered_peer_transmits.push_back(transmit);
+        }
+    }
+
     /// Triggers a heartbeat timout on the peer.
     pub fn trigger_heartbeat_timeout(&mut self) {
+        if let Role::Leader(leader_state) = &mut self.role {
+            leader_state.expire_lost_append_entries(RequestId(self.request_counter.peek()));
+            self.broadcast_heartbeat();
+        } else {
+            log::warn!(
+                "({}) Heartbeat timed out but is ignored as {}.",
+                self.id,
+                match self.role {
+                    Role::Follower(_) => "a follower",
+                    Role::Candidate(_) => "a candidate",
+                    Role::Leader(_) => unreachable!(),
+                }
+            );
+        }
+    }
+
+    /// Sends a heartbeat to every other peer if the peer is the leader
+    /// and returns the identifiers of the sent requests.
+    pub(crate) fn broadcast_heartbeat(&mut self) -> BTreeSet<RequestId> {
+        let mut heartbeat_request_ids = BTreeSet::new();
         let request = AppendEntriesRequest::builder()
             .term(self.current_term())
             .leader_id(self.id)
@@ -206,13 +595,13 @@ impl<A: Application> Peer<A> {
                 self.log()
                     .last()
                     .map(|entry| entry.index())
-                    .unwrap_or(self.snapshot().last_included_index()),
+                    .unwrap_or(self.storage.snapshot_metadata().0),
             )
             .prev_log_term(
                 self.log()
                     .last()
                     .map(|entry| entry.term())
-                    .unwrap_or(self.snapshot().last_included_term()),
+                    .unwrap_or(self.storage.snapshot_metadata().1),
             )
             .entries([])
             .leader_commit(self.commit_index())
@@ -224,25 +613,25 @@ impl<A: Application> Peer<A> {
                 }
 
                 let request_id = self.request_counter.next();
-                let transmit = PeerTransmit::builder()
+                let transmit = PeerTransmit::request()
                     .peer_id(peer_id)
                     .request_id(request_id)
                     .message(request.clone())
-                    .build();
+                    .build()
+                    .expect("Message of the transmit is a request");
                 leader_state.append_entries_requests.insert(transmit.request_id(), request.clone());
+                leader_state.append_entries_sent_at.insert(transmit.request_id(), self.time);
+                leader_state.append_entries_sent_to.insert(transmit.request_id(), peer_id);
+                leader_state
+                    .inflight_append_entries
+                    .entry(peer_id)
+                    .or_default()
+                    .insert(transmit.request_id());
+                heartbeat_request_ids.insert(transmit.request_id());
                 self.buffered_peer_transmits.push_back(transmit);
             }
-        } else {
-            log::warn!(
-                "({}) Heartbeat timed out but is ignored as {}.",
-                self.id,
-                match self.role {
-                    Role::Follower(_) => "a follower",
-                    Role::Candidate(_) => "a candidate",
-                    Role::Leader(_) => unreachable!(),
-                }
-            );
         }
+        heartbeat_request_ids
     }
 
     /// Receives a message from another peer and updates internal state accordingly.
@@ -255,11 +644,12 @@ impl<A: Application> Peer<A> {
         match message {
             PeerMessage::RequestVoteRequest(request) => {
                 let reply = request.receive(peer_id, self);
-                let transmit = PeerTransmit::builder()
+                let transmit = PeerTransmit::reply()
                     .peer_id(peer_id)
                     .request_id(request_id)
                     .message(reply)
-                    .build();
+                    .build()
+                    .expect("Message of the transmit is a reply");
                 self.buffered_peer_transmits.push_back(transmit);
             },
             PeerMessage::RequestVoteReply(reply) => {
@@ -268,19 +658,36 @@ impl<A: Application> Peer<A> {
 
             PeerMessage::AppendEntriesRequest(request) => {
                 let reply = request.receive(peer_id, self);
-                let transmit = PeerTransmit::builder()
+                let transmit = PeerTransmit::reply()
                     .peer_id(peer_id)
                     .request_id(request_id)
                     .message(reply)
-                    .build();
+                    .build()
+                    .expect("Message of the transmit is a reply");
                 self.buffered_peer_transmits.push_back(transmit);
             },
             PeerMessage::AppendEntriesReply(reply) => {
                 reply.receive(peer_id, request_id, self);
+                self.serve_confirmed_reads();
             },
         }
     }
 
+    /// Receives a message from another peer and returns the resulting outgoing transmits.
+    ///
+    /// It's an alternative to [Peer::receive_peer_message] for transports that send transmits
+    /// right away, so all buffered peer transmits (including the ones buffered before the call)
+    /// are drained and returned in order.
+    pub fn handle_message(
+        &mut self,
+        peer_id: PeerId,
+        request_id: RequestId,
+        message: PeerMessage<A>,
+    ) -> Vec<PeerTransmit<A>> {
+        self.receive_peer_message(peer_id, request_id, message);
+        self.buffered_peer_transmits.drain(..).collect()
+    }
+
     /// Receives a client message and updates internal state accordingly.
     pub fn receive_client_message(
         &mut self,
@@ -289,7 +696,9 @@ impl<A: Application> Peer<A> {
         message: ClientMessage<A>,
     ) {
         match message {
-            ClientMessage::QueryReply(_) | ClientMessage::CommandReply(_) => {
+            ClientMessage::QueryReply(_)
+            | ClientMessage::CommandReply(_)
+            | ClientMessage::FindLeaderReply(_) => {
                 log::warn!(
                     "({}) Client {} sent a reply which shouldn't have happened.",
                     self.id,
@@ -299,24 +708,39 @@ impl<A: Application> Peer<A> {
             ClientMessage::QueryRequest(request) => {
                 let reply = request.receive(client_id, request_id, self);
                 if let Some(reply) = reply {
-                    let transmit = ClientTransmit::builder()
+                    let transmit = ClientTransmit::reply()
                         .peer_id(self.id)
                         .client_id(client_id)
                         .request_id(request_id)
                         .message(reply)
-                        .build();
+                        .build()
+                        .expect("Message of the transmit is a reply");
                     self.buffered_client_transmits.push_back(transmit);
                 }
             },
             ClientMessage::CommandRequest(request) => {
                 let reply = request.receive(client_id, request_id, self);
                 if let Some(reply) = reply {
-                    let transmit = ClientTransmit::builder()
+                    let transmit = ClientTransmit::reply()
                         .peer_id(self.id)
                         .client_id(client_id)
                         .request_id(request_id)
                         .message(reply)
-                        .build();
+                        .build()
+                        .expect("Message of the transmit is a reply");
+                    self.buffered_client_transmits.push_back(transmit);
+                }
+            },
+            ClientMessage::FindLeaderRequest(request) => {
+                let reply = request.receive(client_id, request_id, self);
+                if let Some(reply) = reply {
+                    let transmit = ClientTransmit::reply()
+                        .peer_id(self.id)
+                        .client_id(client_id)
+                        .request_id(request_id)
+                        .message(reply)
+                        .build()
+                        .expect("Message of the transmit is a reply");
                     self.buffered_client_transmits.push_back(transmit);
                 }
             },
@@ -324,23 +748,91 @@ impl<A: Application> Peer<A> {
     }
 
     /// Applies commands of log entries that are replicated by majority to the machine of the peer.
-    pub fn apply_committed(&mut self) {
+    ///
+    /// Returns the results of the applied commands alongside the indices of their log entries.
+    pub fn apply_committed(&mut self) -> Vec<(LogIndex, A::CommandResult)> {
+        let mut results = Vec::new();
         let mut last_applied = self.last_applied;
         while last_applied < self.commit_index {
             last_applied = last_applied.next();
-            match self.storage.log().entry(last_applied) {
-                Some(entry) => {
+            match self.storage.log().lookup(self.storage.snapshot(), last_applied) {
+                LogLookup::Present(entry) if entry.index() != last_applied => {
+                    log::error!(
+                        "({}) Log entry {} to apply is looked up as `{:?}` with a different index, \
+                            stopping applying committed entries.",
+                        self.id,
+                        last_applied,
+                        entry,
+                    );
+                    last_applied = last_applied.previous();
+                    break;
+                },
+                LogLookup::Present(entry) => {
                     log::info!("({}) Applying `{:?}`.", self.id, entry,);
 
                     let command = entry.command();
-                    self.machine.apply(command);
+                    results.push((last_applied, self.machine.apply(command)));
+
+                    #[cfg(debug_assertions)]
+                    if let Err(violation) = self.machine.check_invariants() {
+                        panic!(
+                            "({}) Applying log entry {} violated the invariants of the machine: {}",
+                            self.id, last_applied, violation,
+                        );
+                    }
+
+                    self.machine.on_applied(last_applied, command);
+
+                    if let Role::Leader(leader_state) = &mut self.role
+                        && let Some(reads) = leader_state.log_reads.remove(&last_applied)
+                    {
+                        for read in reads {
+                            log::info!(
+                                "({}) Log entry {} of request {} of client {} is applied \
+                                    so running the query and returning the result to the client.",
+                                self.id,
+                                last_applied,
+                                read.request_id,
+                                read.client_id,
+                            );
+
+                            let query_result = self.machine.query(&read.query);
+                            let transmit = ClientTransmit::reply()
+                                .peer_id(self.id)
+                                .client_id(read.client_id)
+                                .request_id(read.request_id)
+                                .message(QueryReply::builder().result(Ok(query_result)).build())
+                                .build()
+                                .expect("Message of the transmit is a reply");
+                            self.buffered_client_transmits.push_back(transmit);
+                        }
+                    }
+                },
+                LogLookup::Compacted { last_included_index, .. } => {
+                    log::error!(
+                        "({}) Log entry {} to apply is compacted into the snapshot, \
+                            restoring the machine from the snapshot up to log index {} instead.",
+                        self.id,
+                        last_applied,
+                        last_included_index,
+                    );
+                    self.machine = self.storage.snapshot().machine().clone();
+                    last_applied = last_included_index;
                 },
-                None => {
-                    unreachable!()
+                LogLookup::Missing => {
+                    log::error!(
+                        "({}) Log entry {} to apply is committed but missing from the log, \
+                            stopping applying committed entries.",
+                        self.id,
+                        last_applied,
+                    );
+                    last_applied = last_applied.previous();
+                    break;
                 },
             }
         }
         self.last_applied = last_applied;
+        results
     }
 }
 
@@ -353,12 +845,12 @@ impl<A: Application> Peer<A> {
             .log()
             .last()
             .map(|entry| entry.index())
-            .unwrap_or(self.snapshot().last_included_index());
+            .unwrap_or(self.storage.snapshot_metadata().0);
         let prev_log_term = self
             .log()
             .last()
             .map(|entry| entry.term())
-            .unwrap_or(self.snapshot().last_included_term());
+            .unwrap_or(self.storage.snapshot_metadata().1);
 
         let no_op = A::Command::no_op();
         let no_op_log_index = prev_log_index.next();
@@ -367,6 +859,7 @@ impl<A: Application> Peer<A> {
             .index(no_op_log_index)
             .term(self.current_term())
             .command(no_op)
+            .sequence(self.log().next_sequence(self.current_term()))
             .build();
         log::info!(
             "({}) Appending `{:?}` as the leader and instructing the peers to do the same.",
@@ -385,18 +878,25 @@ impl<A: Application> Peer<A> {
             .build();
 
         let mut append_entries_requests = BTreeMap::default();
+        let mut append_entries_sent_at = BTreeMap::default();
+        let mut append_entries_sent_to = BTreeMap::default();
+        let mut inflight_append_entries = BTreeMap::<PeerId, BTreeSet<RequestId>>::default();
         for peer_id in self.cluster.iter().copied() {
             if peer_id == self.id {
                 continue;
             }
 
             let request_id = self.request_counter.next();
-            let transmit = PeerTransmit::builder()
+            let transmit = PeerTransmit::request()
                 .peer_id(peer_id)
                 .request_id(request_id)
                 .message(request.clone())
-                .build();
+                .build()
+                .expect("Message of the transmit is a request");
             append_entries_requests.insert(transmit.request_id(), request.clone());
+            append_entries_sent_at.insert(transmit.request_id(), self.time);
+            append_entries_sent_to.insert(transmit.request_id(), peer_id);
+            inflight_append_entries.entry(peer_id).or_default().insert(transmit.request_id());
             self.buffered_peer_transmits.push_back(transmit);
         }
 
@@ -413,17 +913,166 @@ impl<A: Application> Peer<A> {
             if peer_id == self.id {
                 match_index.insert(peer_id, no_op_log_index);
             } else {
-                match_index.insert(peer_id, self.snapshot().last_included_index());
+                match_index.insert(peer_id, self.storage.snapshot_metadata().0);
             }
         }
 
-        self.role = Role::Leader(
-            LeaderState::builder()
-                .next_index(next_index)
-                .match_index(match_index)
-                .append_entries_requests(append_entries_requests)
-                .build(),
+        self.transition_role(
+            self.current_term(),
+            Role::Leader(
+                LeaderState::builder()
+                    .next_index(next_index)
+                    .match_index(match_index)
+                    .append_entries_requests(append_entries_requests)
+                    .append_entries_sent_at(append_entries_sent_at)
+                    .append_entries_sent_to(append_entries_sent_to)
+                    .inflight_append_entries(inflight_append_entries)
+                    .build(),
+            ),
+        );
+    }
+
+    /// Appends a command to the log as the leader and instructs the peers to do the same.
+    ///
+    /// Returns the index of the appended log entry.
+    pub(crate) fn replicate_command(
+        &mut self,
+        command: A::Command,
+    ) -> Result<LogIndex, A::StorageError> {
+        let Role::Leader(leader_state) = &mut self.role else {
+            unreachable!();
+        };
+
+        let (prev_log_index, prev_log_term) =
+            self.storage.log().last().map(|entry| (entry.index(), entry.term())).unwrap_or((
+                self.storage.snapshot_metadata().0,
+                self.storage.snapshot_metadata().1,
+            ));
+
+        let current_term = self.storage.current_term();
+        let log_entry = LogEntry::builder()
+            .index(prev_log_index.next())
+            .term(current_term)
+            .command(command)
+            .sequence(self.storage.log().next_sequence(current_term))
+            .build();
+
+        log::info!(
+            "({}) Appending `{:?}` as the leader and instructing the peers to do the same.",
+            self.id,
+            log_entry,
         );
+        if let Err(error) = self.storage.append_log_entry(log_entry.clone()) {
+            log::error!("({}) Failed to persistently append the log entry ({}).", self.id, error,);
+            return Err(error);
+        }
+
+        for peer_id in self.cluster.iter() {
+            if *peer_id == self.id {
+                continue;
+            }
+            if let Some(max_inflight_per_peer) = self.max_inflight_per_peer {
+                let inflight = leader_state
+                    .inflight_append_entries
+                    .get(peer_id)
+                    .map(|requests| requests.len())
+                    .unwrap_or(0);
+                if inflight >= max_inflight_per_peer {
+                    log::info!(
+                        "({}) Holding back the log entry from peer {} \
+                                as {} append entries requests are already in flight to it.",
+                        self.id,
+                        peer_id,
+                        inflight,
+                    );
+                    continue;
+                }
+            }
+            let request = AppendEntriesRequest {
+                term: self.storage.current_term(),
+                leader_id: self.id,
+                prev_log_index,
+                prev_log_term,
+                entries: vec![log_entry.clone()],
+                leader_commit: self.commit_index,
+            };
+
+            let request_id = self.request_counter.next();
+            let transmit = PeerTransmit::request()
+                .peer_id(*peer_id)
+                .request_id(request_id)
+                .message(request.clone())
+                .build()
+                .expect("Message of the transmit is a request");
+
+            leader_state.append_entries_requests.insert(transmit.request_id(), request);
+            leader_state.append_entries_sent_at.insert(transmit.request_id(), self.time);
+            leader_state.append_entries_sent_to.insert(transmit.request_id(), *peer_id);
+            leader_state
+                .inflight_append_entries
+                .entry(*peer_id)
+                .or_default()
+                .insert(transmit.request_id());
+            self.buffered_peer_transmits.push_back(transmit);
+        }
+
+        Ok(log_entry.index())
+    }
+
+    /// Serves the pending reads whose heartbeat rounds are acknowledged by the majority.
+    pub(crate) fn serve_confirmed_reads(&mut self) {
+        let majority = self.majority();
+        let Role::Leader(leader_state) = &mut self.role else {
+            return;
+        };
+
+        let (confirmed_reads, pending_reads): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut leader_state.pending_reads)
+                .into_iter()
+                .partition(|read| read.acknowledged_by.len() + 1 >= majority);
+        leader_state.pending_reads = pending_reads;
+
+        if confirmed_reads.is_empty() {
+            return;
+        }
+
+        if self.last_applied < self.commit_index {
+            log::info!("({}) Applying committed entries before running the queries.", self.id);
+            self.apply_committed();
+        }
+
+        for read in confirmed_reads {
+            log::info!(
+                "({}) Leadership is confirmed for request {} of client {} \
+                    so running the query at read index {} and returning the result to the client.",
+                self.id,
+                read.request_id,
+                read.client_id,
+                read.read_index,
+            );
+
+            let query_result = self.machine.query(&read.query);
+            let transmit = ClientTransmit::reply()
+                .peer_id(self.id)
+                .client_id(read.client_id)
+                .request_id(read.request_id)
+                .message(QueryReply::builder().result(Ok(query_result)).build())
+                .build()
+                .expect("Message of the transmit is a reply");
+            self.buffered_client_transmits.push_back(transmit);
+        }
+    }
+
+    /// Changes the role of the peer and records the transition in the role history.
+    pub(crate) fn transition_role(&mut self, term: Term, new_role: Role<A>) {
+        if self.role_history.len() == Self::ROLE_HISTORY_CAPACITY {
+            self.role_history.pop_front();
+        }
+        let transition =
+            RoleTransition::builder().term(term).from(self.role.kind()).to(new_role.kind()).build();
+        self.role_history.push_back(transition);
+        self.role = new_role;
+        log::debug!("({}) {}", self.id, self.debug_summary());
     }
 }
 
@@ -461,6 +1110,24 @@ impl<A: Application> Peer<A> {
         self.storage.install_snapshot(new_snapshot)
     }
 
+    /// Reloads the machine, the commit index and the last applied of the peer
+    /// from the snapshot in its storage.
+    ///
+    /// It re-syncs the in-memory view of the peer after its storage is modified externally,
+    /// without recreating the peer. Commit index is never decreased, as committed entries
+    /// stay committed.
+    ///
+    /// Should only be used for testing purposes!
+    pub fn force_snapshot_from_storage(&mut self) {
+        let snapshot = self.storage.snapshot();
+        if let Some(cluster) = snapshot.cluster() {
+            self.cluster = cluster.clone();
+        }
+        self.machine = snapshot.machine().clone();
+        self.last_applied = snapshot.last_included_index();
+        self.commit_index = self.commit_index.max(snapshot.last_included_index());
+    }
+
     /// Overwrites the commit index of the peer.
     ///
     /// Should only be used for testing purposes!
@@ -475,11 +1142,29 @@ impl<A: Application> Peer<A> {
         self.last_applied = new_last_applied;
     }
 
-    /// Overwrites the role of the peer.
+    /// Overwrites the cluster of the peer.
+    ///
+    /// Should only be used for testing purposes!
+    pub fn set_cluster(&mut self, new_cluster: Cluster) {
+        self.cluster = new_cluster;
+    }
+
+    /// Steps down from leadership to become a follower of no one in the current term.
+    ///
+    /// Should only be used for testing purposes!
+    pub fn step_down(&mut self) {
+        log::info!("({}) Stepping down to become a follower.", self.id);
+        self.transition_role(
+            self.current_term(),
+            Role::Follower(FollowerState::builder().leader_id(None).build()),
+        );
+    }
+
+    /// Overwrites the role of the peer, recording the transition in the current term.
     ///
     /// Should only be used for testing purposes!
     pub fn set_role(&mut self, new_role: Role<A>) {
-        self.role = new_role;
+        self.transition_role(self.current_term(), new_role);
     }
 
     /// Overwrites the machine of the peer.
@@ -489,6 +1174,32 @@ impl<A: Application> Peer<A> {
         self.machine = new_machine;
     }
 
+    /// Grants a simulated vote to the peer from the first peer its vote is still awaited from,
+    /// without routing an actual reply, and makes it the leader if the majority is reached.
+    ///
+    /// Returns the peer the vote is granted on behalf of, or `None` if the peer
+    /// is not a candidate or it's not awaiting any votes.
+    ///
+    /// Should only be used for testing and teaching purposes!
+    pub fn grant_simulated_vote(&mut self) -> Option<PeerId> {
+        let Role::Candidate(candidate_state) = &mut self.role else {
+            return None;
+        };
+        let (request_id, voter_id) = candidate_state
+            .vote_requests()
+            .first_key_value()
+            .map(|(request_id, peer_id)| (*request_id, *peer_id))?;
+
+        log::info!("({}) Peer {} is simulated to grant vote.", self.id, voter_id);
+        candidate_state.grant_vote(request_id);
+        log::info!("({}) {} votes are granted.", self.id, candidate_state.votes_granted());
+
+        if candidate_state.votes_granted() >= self.majority() {
+            self.become_leader();
+        }
+        Some(voter_id)
+    }
+
     /// Gets the buffered peer transmits of the peer mutably.
     pub fn buffered_peer_transmits_mut(&mut self) -> &mut VecDeque<PeerTransmit<A>> {
         &mut self.buffered_peer_transmits
@@ -3,15 +3,25 @@
 #[doc(inline)]
 pub use crate::{
     application::Application as RaftApplication,
-    client::Client,
+    client::{
+        Client,
+        QueryReconciliation,
+    },
     command::{
         Command as RaftCommand,
         CommandResult as RaftCommandResult,
     },
-    errors::ClientError,
+    errors::{
+        ClientError,
+        ConsistencyParsingError,
+        SnapshotDecodingError,
+        TransmitError,
+    },
     log::{
+        EntryStatus,
         Log,
         LogEntry,
+        LogLookup,
     },
     machine::Machine as RaftMachine,
     message::{
@@ -20,6 +30,8 @@ pub use crate::{
         ClientMessage,
         CommandReply,
         CommandRequest,
+        FindLeaderReply,
+        FindLeaderRequest,
         PeerMessage,
         QueryReply,
         QueryRequest,
@@ -46,11 +58,20 @@ pub use crate::{
         CandidateState,
         FollowerState,
         LeaderState,
+        LogRead,
+        PendingRead,
         Role,
+        RoleKind,
+        RoleTransition,
     },
+    schema::InputSchema,
     snapshot::Snapshot,
-    storage::Storage as RaftStorage,
+    storage::{
+        Storage as RaftStorage,
+        StorageTransaction,
+    },
     transmit::{
+        transmit_clock,
         ClientTransmit,
         PeerTransmit,
     },
@@ -88,6 +109,7 @@ pub(crate) use {
             Deref,
             DerefMut,
         },
+        str::FromStr,
         sync::atomic::{
             AtomicUsize,
             Ordering as AtomicOrdering,
@@ -1,4 +1,7 @@
 //! Primitives of the crate.
+//!
+//! Primitives are displayed as bare numbers (e.g., `3` for `PeerId(3)`) which respect width
+//! and alignment flags, so the surrounding text labels them (e.g., `peer {}` or `Peer {}`).
 
 use crate::prelude::*;
 
@@ -41,8 +44,46 @@ pub struct Cluster(
     BTreeSet<PeerId>,
 );
 
+impl Cluster {
+    /// Gets the peers added and removed to turn the cluster into the other cluster.
+    pub fn diff(&self, other: &Cluster) -> (BTreeSet<PeerId>, BTreeSet<PeerId>) {
+        let added = other.0.difference(&self.0).copied().collect();
+        let removed = self.0.difference(&other.0).copied().collect();
+        (added, removed)
+    }
+
+    /// Changes the membership of the cluster by adding and removing peers.
+    ///
+    /// Removals are applied after additions, so a peer in both sets is not in the cluster.
+    pub fn apply_change(&mut self, added: &BTreeSet<PeerId>, removed: &BTreeSet<PeerId>) {
+        self.0.extend(added.iter().copied());
+        self.0.retain(|peer_id| !removed.contains(peer_id));
+    }
+
+    /// Gets the cluster with a peer added, which is the same cluster if the peer is already in it.
+    pub fn with_added(mut self, peer_id: PeerId) -> Cluster {
+        self.0.insert(peer_id);
+        self
+    }
+
+    /// Gets the cluster with a peer removed, which is the same cluster if the peer is not in it.
+    pub fn with_removed(mut self, peer_id: PeerId) -> Cluster {
+        self.0.remove(&peer_id);
+        self
+    }
+}
+
+impl<const N: usize> From<[PeerId; N]> for Cluster {
+    fn from(peer_ids: [PeerId; N]) -> Cluster {
+        Cluster(peer_ids.into())
+    }
+}
+
 /// Consistency requirement of [Peer]s.
-#[derive(Clone, Copy)]
+///
+/// Consistencies are displayed and parsed in lowercase (e.g., `strong`, `eventual`, `lease`
+/// or `read-index`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, derive_more::Display)]
 pub enum Consistency {
     /// Strong consistency.
     ///
@@ -53,6 +94,7 @@ pub enum Consistency {
     /// which involves waiting for the majority of replies to upcoming heartbeats.
     ///
     /// It also makes sure all committed entries are applied before responding.
+    #[display("strong")]
     Strong,
 
     /// Eventual consistency.
@@ -63,7 +105,55 @@ pub enum Consistency {
     /// more up-to-date data.
     ///
     /// Eventual consistency is enough for many applications, so it's also available.
+    #[display("eventual")]
     Eventual,
+
+    /// Linearizable reads using leader leases.
+    ///
+    /// In this mode, followers and candidates redirect client queries to the leader as well.
+    ///
+    /// Leader replies right away as long as the majority of the peers acknowledged its contact
+    /// within the last [Peer::LEASE_DURATION] ticks, as no other leader can be elected until then.
+    /// Otherwise, it falls back to confirming its leadership with a heartbeat round.
+    ///
+    /// It relies on clocks of the peers to advance at the same rate, and on the peers to have
+    /// [Peer::set_leader_stickiness] enabled. Leaders never hold a lease otherwise.
+    #[display("lease")]
+    LeaseRead,
+
+    /// Linearizable reads using read indices.
+    ///
+    /// In this mode, followers and candidates redirect client queries to the leader as well.
+    ///
+    /// Leader records its commit index as the read index of the query, and sends a heartbeat round
+    /// to confirm it's still the leader. Once the majority of the peers acknowledge the round,
+    /// it applies the committed entries up to the read index and replies to the client.
+    #[display("read-index")]
+    ReadIndex,
+
+    /// Linearizable reads through the log.
+    ///
+    /// In this mode, followers and candidates redirect client queries to the leader as well.
+    ///
+    /// Leader appends a no-op entry for the query and runs the query right after applying it,
+    /// so the read is ordered with the commands in the log at the cost of replicating an entry.
+    #[display("log-read")]
+    LogRead,
+}
+
+impl FromStr for Consistency {
+    type Err = ConsistencyParsingError;
+
+    fn from_str(consistency: &str) -> Result<Self, Self::Err> {
+        match consistency {
+            "strong" => Ok(Consistency::Strong),
+            "eventual" => Ok(Consistency::Eventual),
+            "lease" => Ok(Consistency::LeaseRead),
+            "read-index" => Ok(Consistency::ReadIndex),
+            "log-read" => Ok(Consistency::LogRead),
+            _ => Err(ConsistencyParsingError(consistency.to_owned())),
+        }
+    }
 }
 
 /// Index of a [LogEntry].
@@ -125,10 +215,27 @@ pub struct RequestCounter {
 }
 
 impl RequestCounter {
+    /// Creates a counter starting from the given request id.
+    pub fn starting_from(next_request_id: usize) -> Self {
+        Self { next_request_id: AtomicUsize::new(next_request_id) }
+    }
+
     /// Gets the next request id.
     pub fn next(&self) -> usize {
         self.next_request_id.fetch_add(1, AtomicOrdering::Relaxed)
     }
+
+    /// Gets the next request id without advancing the counter.
+    pub fn peek(&self) -> usize {
+        self.next_request_id.load(AtomicOrdering::Relaxed)
+    }
+}
+
+impl Clone for RequestCounter {
+    fn clone(&self) -> Self {
+        let next_request_id = self.next_request_id.load(AtomicOrdering::Relaxed);
+        Self { next_request_id: AtomicUsize::new(next_request_id) }
+    }
 }
 
 /// Identifier of a request.
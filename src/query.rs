@@ -21,6 +21,22 @@ use crate::prelude::*;
 pub trait Query:
     Clone + Debug + Eq + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
 {
+    /// Gets the input schemas of the kinds of queries that can be built from user inputs.
+    ///
+    /// Interactive tools use it to build queries without application specific code.
+    /// No kinds are described by default.
+    fn schemas() -> &'static [InputSchema] {
+        &[]
+    }
+
+    /// Builds a query of the kind with the given name from the user inputs,
+    /// which are in the order of the inputs in its [InputSchema].
+    ///
+    /// Returns `None` if the kind is unknown or the inputs are invalid.
+    fn from_inputs(name: &str, inputs: &[String]) -> Option<Self> {
+        let _ = (name, inputs);
+        None
+    }
 }
 
 /// Result of a [Query].
@@ -45,4 +61,10 @@ pub trait Query:
 pub trait QueryResult:
     Clone + Debug + Eq + PartialEq + Serialize + DeserializeOwned + Send + Sync + 'static
 {
+    /// Gets a human-readable summary of the result.
+    ///
+    /// Interactive tools use it to display results. It's the debug representation by default.
+    fn summary(&self) -> String {
+        format!("{self:?}")
+    }
 }
@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 /// Role of a [Peer].
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Role<A: Application> {
     Follower(FollowerState),
@@ -25,6 +26,49 @@ impl<A: Application> Role<A> {
     pub fn is_leader(&self) -> bool {
         matches!(self, Role::Leader(_))
     }
+
+    /// Gets the kind of the role.
+    pub fn kind(&self) -> RoleKind {
+        match self {
+            Role::Follower(_) => RoleKind::Follower,
+            Role::Candidate(_) => RoleKind::Candidate,
+            Role::Leader(_) => RoleKind::Leader,
+        }
+    }
+}
+
+/// Kind of a [Role], without its state.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, derive_more::Display)]
+pub enum RoleKind {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+/// Transition of a [Peer] from one [Role] to another.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, bon::Builder)]
+pub struct RoleTransition {
+    #[builder(into)]
+    term: Term,
+    from: RoleKind,
+    to: RoleKind,
+}
+
+impl RoleTransition {
+    /// Gets the term the transition happened in.
+    pub fn term(&self) -> Term {
+        self.term
+    }
+
+    /// Gets the kind of the role before the transition.
+    pub fn from(&self) -> RoleKind {
+        self.from
+    }
+
+    /// Gets the kind of the role after the transition.
+    pub fn to(&self) -> RoleKind {
+        self.to
+    }
 }
 
 impl<A: Application> Default for Role<A> {
@@ -50,16 +94,24 @@ impl FollowerState {
 /// State of a candidate.
 #[derive(Clone, Debug, Eq, PartialEq, bon::Builder)]
 pub struct CandidateState {
+    #[builder(into)]
+    pub(crate) term: Term,
+
     #[builder(with = FromIterator::from_iter)]
-    pub(crate) vote_request_ids: BTreeSet<RequestId>,
+    pub(crate) vote_requests: BTreeMap<RequestId, PeerId>,
 
     pub(crate) votes_granted: usize,
 }
 
 impl CandidateState {
-    /// Gets vote request ids for this term.
-    pub fn vote_request_ids(&self) -> &BTreeSet<RequestId> {
-        &self.vote_request_ids
+    /// Gets the term the election of the candidate is for.
+    pub fn term(&self) -> Term {
+        self.term
+    }
+
+    /// Gets the awaited vote requests for this term with the peers they are sent to.
+    pub fn vote_requests(&self) -> &BTreeMap<RequestId, PeerId> {
+        &self.vote_requests
     }
 
     /// Gets the number of votes granted for this term.
@@ -70,14 +122,16 @@ impl CandidateState {
 
 impl CandidateState {
     pub(crate) fn grant_vote(&mut self, request_id: RequestId) {
-        if self.vote_request_ids.remove(&request_id) {
+        if self.vote_requests.remove(&request_id).is_some() {
             self.votes_granted += 1;
         }
     }
 }
 
 /// State of a leader.
-#[derive(Clone, Debug, Eq, PartialEq, bon::Builder)]
+///
+/// Equality of leader states ignores the bookkeeping of the append entries requests in flight.
+#[derive(Clone, bon::Builder, derive_more::Debug)]
 pub struct LeaderState<A: Application> {
     #[builder(with = FromIterator::from_iter)]
     pub(crate) next_index: BTreeMap<PeerId, LogIndex>,
@@ -87,8 +141,50 @@ pub struct LeaderState<A: Application> {
 
     #[builder(with = FromIterator::from_iter, default)]
     pub(crate) append_entries_requests: BTreeMap<RequestId, AppendEntriesRequest<A>>,
+
+    #[builder(default)]
+    pub(crate) initial_noop_committed: bool,
+
+    #[builder(default)]
+    pub(crate) pending_reads: Vec<PendingRead<A>>,
+
+    #[builder(with = FromIterator::from_iter, default)]
+    pub(crate) log_reads: BTreeMap<LogIndex, Vec<LogRead<A>>>,
+
+    #[builder(with = FromIterator::from_iter, default)]
+    #[debug(skip)]
+    pub(crate) append_entries_sent_at: BTreeMap<RequestId, usize>,
+
+    #[builder(with = FromIterator::from_iter, default)]
+    #[debug(skip)]
+    pub(crate) append_entries_sent_to: BTreeMap<RequestId, PeerId>,
+
+    #[builder(with = FromIterator::from_iter, default)]
+    #[debug(skip)]
+    pub(crate) inflight_append_entries: BTreeMap<PeerId, BTreeSet<RequestId>>,
+
+    #[builder(skip)]
+    #[debug(skip)]
+    pub(crate) previous_heartbeat_timeout: Option<RequestId>,
+
+    #[builder(with = FromIterator::from_iter, default)]
+    #[debug(skip)]
+    pub(crate) last_leader_contact: BTreeMap<PeerId, usize>,
+}
+
+impl<A: Application> PartialEq for LeaderState<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.next_index == other.next_index
+            && self.match_index == other.match_index
+            && self.append_entries_requests == other.append_entries_requests
+            && self.initial_noop_committed == other.initial_noop_committed
+            && self.pending_reads == other.pending_reads
+            && self.log_reads == other.log_reads
+    }
 }
 
+impl<A: Application> Eq for LeaderState<A> {}
+
 impl<A: Application> LeaderState<A> {
     /// Gets the next index of peers.
     pub fn next_index(&self) -> &BTreeMap<PeerId, LogIndex> {
@@ -99,4 +195,135 @@ impl<A: Application> LeaderState<A> {
     pub fn match_index(&self) -> &BTreeMap<PeerId, LogIndex> {
         &self.match_index
     }
+
+    /// Gets the append entries requests awaiting replies alongside their request ids.
+    pub fn append_entries_requests(&self) -> &BTreeMap<RequestId, AppendEntriesRequest<A>> {
+        &self.append_entries_requests
+    }
+
+    /// Gets whether the no-op entry appended upon election is committed.
+    ///
+    /// Leadership is confirmed once it's committed, as the leader knows
+    /// the latest committed entries of the cluster from that point on.
+    pub fn initial_noop_committed(&self) -> bool {
+        self.initial_noop_committed
+    }
+
+    /// Gets the reads awaiting the confirmation of the leadership.
+    pub fn pending_reads(&self) -> &[PendingRead<A>] {
+        &self.pending_reads
+    }
+
+    /// Gets the reads awaiting their log entries to be applied alongside the indices of the entries.
+    pub fn log_reads(&self) -> &BTreeMap<LogIndex, Vec<LogRead<A>>> {
+        &self.log_reads
+    }
+
+    /// Gets the time of the latest contact of the leader acknowledged by other peers.
+    ///
+    /// Contact time is the time the acknowledged append entries request is sent at.
+    pub fn last_leader_contact(&self) -> &BTreeMap<PeerId, usize> {
+        &self.last_leader_contact
+    }
+}
+
+impl<A: Application> LeaderState<A> {
+    /// Expires the append entries requests in flight to each peer which were already
+    /// in flight when the heartbeat timed out previously, as they're considered lost.
+    ///
+    /// Expired requests are forgotten, so their late replies are ignored, unless they're
+    /// heartbeats awaited by pending reads. Those are kept until they're replied, but they
+    /// no longer count as in flight.
+    ///
+    /// Requests sent from `next_request_id` on are expired on the next heartbeat timeout.
+    pub(crate) fn expire_lost_append_entries(&mut self, next_request_id: RequestId) {
+        if let Some(previous_heartbeat_timeout) = self.previous_heartbeat_timeout {
+            for requests in self.inflight_append_entries.values_mut() {
+                requests.retain(|request_id| *request_id >= previous_heartbeat_timeout);
+            }
+
+            let expired_request_ids = self
+                .append_entries_sent_to
+                .range(..previous_heartbeat_timeout)
+                .map(|(request_id, _)| *request_id)
+                .filter(|request_id| {
+                    !self
+                        .pending_reads
+                        .iter()
+                        .any(|read| read.heartbeat_request_ids.contains(request_id))
+                })
+                .collect::<Vec<_>>();
+            for request_id in expired_request_ids {
+                self.append_entries_requests.remove(&request_id);
+                self.append_entries_sent_to.remove(&request_id);
+                self.append_entries_sent_at.remove(&request_id);
+            }
+        }
+        self.previous_heartbeat_timeout = Some(next_request_id);
+    }
+}
+
+/// Read awaiting the confirmation of the leadership before being served.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRead<A: Application> {
+    pub(crate) client_id: ClientId,
+    pub(crate) request_id: RequestId,
+    pub(crate) query: A::Query,
+    pub(crate) read_index: LogIndex,
+    pub(crate) heartbeat_request_ids: BTreeSet<RequestId>,
+    pub(crate) acknowledged_by: BTreeSet<PeerId>,
+}
+
+impl<A: Application> PendingRead<A> {
+    /// Gets the client the read is requested by.
+    pub fn client_id(&self) -> ClientId {
+        self.client_id
+    }
+
+    /// Gets the request of the read.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// Gets the query of the read.
+    pub fn query(&self) -> &A::Query {
+        &self.query
+    }
+
+    /// Gets the commit index of the leader when the read is requested.
+    ///
+    /// Read is served after committed entries up to this index are applied.
+    pub fn read_index(&self) -> LogIndex {
+        self.read_index
+    }
+
+    /// Gets the peers which acknowledged the heartbeat round of the read.
+    pub fn acknowledged_by(&self) -> &BTreeSet<PeerId> {
+        &self.acknowledged_by
+    }
+}
+
+/// Read awaiting its log entry to be applied before being served.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogRead<A: Application> {
+    pub(crate) client_id: ClientId,
+    pub(crate) request_id: RequestId,
+    pub(crate) query: A::Query,
+}
+
+impl<A: Application> LogRead<A> {
+    /// Gets the client the read is requested by.
+    pub fn client_id(&self) -> ClientId {
+        self.client_id
+    }
+
+    /// Gets the request of the read.
+    pub fn request_id(&self) -> RequestId {
+        self.request_id
+    }
+
+    /// Gets the query of the read.
+    pub fn query(&self) -> &A::Query {
+        &self.query
+    }
 }
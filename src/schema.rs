@@ -0,0 +1,28 @@
+//! Input schema definitions.
+
+/// Description of a kind of [Command](crate::command::Command) or [Query](crate::query::Query)
+/// for building it from textual user inputs.
+///
+/// Interactive tools (e.g., the debugger) use it to ask users for inputs generically.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InputSchema {
+    name: &'static str,
+    inputs: &'static [&'static str],
+}
+
+impl InputSchema {
+    /// Creates an input schema from the name of the kind and the names of its inputs.
+    pub const fn new(name: &'static str, inputs: &'static [&'static str]) -> Self {
+        Self { name, inputs }
+    }
+
+    /// Gets the name of the kind.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Gets the names of the inputs required to build the kind, in order.
+    pub fn inputs(&self) -> &'static [&'static str] {
+        self.inputs
+    }
+}
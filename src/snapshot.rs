@@ -3,10 +3,18 @@
 use crate::prelude::*;
 
 /// Snapshot of a [Machine] after [LogEntry]s up to a certain [LogIndex] is applied.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, bon::Builder)]
 pub struct Snapshot<A: Application> {
+    #[builder(into)]
     last_included_index: LogIndex,
+
+    #[builder(into)]
     last_included_term: Term,
+
+    #[builder(into)]
+    cluster: Option<Cluster>,
+
+    #[builder(into)]
     machine: A::Machine,
 }
 
@@ -21,6 +29,13 @@ impl<A: Application> Snapshot<A> {
         self.last_included_term
     }
 
+    /// Gets the cluster as of the last included log entry, if it's recorded.
+    ///
+    /// Peers restored from the snapshot use it instead of their initial cluster.
+    pub fn cluster(&self) -> Option<&Cluster> {
+        self.cluster.as_ref()
+    }
+
     /// Gets the machine when the snapshot is taken.
     pub fn machine(&self) -> &A::Machine {
         &self.machine
@@ -32,6 +47,7 @@ impl<A: Application> Default for Snapshot<A> {
         Self {
             last_included_index: LogIndex(0),
             last_included_term: Term(0),
+            cluster: None,
             machine: A::Machine::default(),
         }
     }
@@ -61,6 +61,17 @@ pub trait Storage<A: Application>: Send + Sync + 'static {
 
     /// Gets the persistent log.
     fn log(&self) -> &Log<A>;
+    /// Gets the entries of the persistent log starting from an index.
+    ///
+    /// It's empty if the index is compacted into the snapshot or it's after the last entry.
+    fn entries_from(&self, index: LogIndex) -> &[LogEntry<A>] {
+        let (last_included_index, _) = self.snapshot_metadata();
+        if index <= last_included_index {
+            return &[];
+        }
+        let log = self.log();
+        &log[log.partition_point(|entry| entry.index() < index)..]
+    }
     /// Append an entry to the log persistently.
     fn append_log_entry(&mut self, entry: LogEntry<A>) -> Result<(), A::StorageError>;
     /// Truncate the log down to a certain log index persistently.
@@ -68,6 +79,147 @@ pub trait Storage<A: Application>: Send + Sync + 'static {
 
     /// Gets the current persistent snapshot.
     fn snapshot(&self) -> &Snapshot<A>;
+    /// Gets the last included index and the last included term of the persistent snapshot.
+    ///
+    /// It's read from [Storage::snapshot] by default, storages that don't keep the machine
+    /// of the snapshot in memory can override it to avoid loading the machine.
+    fn snapshot_metadata(&self) -> (LogIndex, Term) {
+        let snapshot = self.snapshot();
+        (snapshot.last_included_index(), snapshot.last_included_term())
+    }
     /// Installs a new snapshot persistently.
     fn install_snapshot(&mut self, snapshot: Snapshot<A>) -> Result<(), A::StorageError>;
+
+    /// Gets the persistent buffered peer transmits to restore when the peer is created.
+    ///
+    /// Buffered transmits are not persisted by default, so nothing is restored.
+    fn buffered_peer_transmits(&self) -> Vec<PeerTransmit<A>> {
+        Vec::new()
+    }
+    /// Sets the buffered peer transmits persistently.
+    ///
+    /// Buffered transmits are not persisted by default, so it does nothing.
+    fn set_buffered_peer_transmits(
+        &mut self,
+        transmits: &VecDeque<PeerTransmit<A>>,
+    ) -> Result<(), A::StorageError> {
+        let _ = transmits;
+        Ok(())
+    }
+
+    /// Applies the updates of a transaction persistently and atomically.
+    ///
+    /// Either all of the updates are persisted or none of them are, even if the process
+    /// crashes in the middle of persisting them. Log truncation is applied before appends.
+    ///
+    /// Updates are applied one by one by default, which is not atomic, so storages
+    /// should override it to make sure a crash doesn't leave some of the updates persisted.
+    fn apply_transaction(
+        &mut self,
+        transaction: StorageTransaction<A>,
+    ) -> Result<(), A::StorageError> {
+        match (transaction.current_term, transaction.voted_for) {
+            (Some(current_term), Some(voted_for)) => {
+                self.set_current_term_and_voted_for(current_term, voted_for)?;
+            },
+            (Some(current_term), None) => self.set_current_term(current_term)?,
+            (None, Some(voted_for)) => self.set_voted_for(voted_for)?,
+            (None, None) => {},
+        }
+        if let Some(down_to) = transaction.log_truncation {
+            self.truncate_log(down_to)?;
+        }
+        for entry in transaction.log_entries {
+            self.append_log_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Builds a transaction and applies it with [Storage::apply_transaction].
+    fn transaction(
+        &mut self,
+        build: impl FnOnce(&mut StorageTransaction<A>),
+    ) -> Result<(), A::StorageError> {
+        let mut transaction = StorageTransaction::default();
+        build(&mut transaction);
+        self.apply_transaction(transaction)
+    }
+}
+
+/// Updates to apply to a [Storage] atomically.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "A::Command : Serialize + DeserializeOwned")]
+pub struct StorageTransaction<A: Application> {
+    current_term: Option<Term>,
+    voted_for: Option<Option<PeerId>>,
+    log_truncation: Option<LogIndex>,
+    log_entries: Vec<LogEntry<A>>,
+}
+
+impl<A: Application> StorageTransaction<A> {
+    /// Sets the current term within the transaction.
+    pub fn set_current_term(&mut self, term: Term) -> &mut Self {
+        self.current_term = Some(term);
+        self
+    }
+
+    /// Sets the voted for within the transaction.
+    pub fn set_voted_for(&mut self, voted_for: Option<PeerId>) -> &mut Self {
+        self.voted_for = Some(voted_for);
+        self
+    }
+
+    /// Truncates the log down to a certain log index within the transaction.
+    ///
+    /// Entries appended within the transaction are truncated as well.
+    pub fn truncate_log(&mut self, down_to: LogIndex) -> &mut Self {
+        self.log_entries.retain(|entry| entry.index() < down_to);
+        self.log_truncation = Some(match self.log_truncation {
+            Some(log_truncation) => log_truncation.min(down_to),
+            None => down_to,
+        });
+        self
+    }
+
+    /// Appends an entry to the log within the transaction.
+    pub fn append_log_entry(&mut self, entry: LogEntry<A>) -> &mut Self {
+        self.log_entries.push(entry);
+        self
+    }
+}
+
+impl<A: Application> StorageTransaction<A> {
+    /// Gets the new current term, if it's updated.
+    pub fn current_term(&self) -> Option<Term> {
+        self.current_term
+    }
+
+    /// Gets the new voted for, if it's updated.
+    pub fn voted_for(&self) -> Option<Option<PeerId>> {
+        self.voted_for
+    }
+
+    /// Gets the log index to truncate the log down to, if the log is truncated.
+    pub fn log_truncation(&self) -> Option<LogIndex> {
+        self.log_truncation
+    }
+
+    /// Gets the entries to append to the log.
+    pub fn log_entries(&self) -> &[LogEntry<A>] {
+        &self.log_entries
+    }
+
+    /// Gets whether the transaction doesn't update anything.
+    pub fn is_empty(&self) -> bool {
+        self.current_term.is_none()
+            && self.voted_for.is_none()
+            && self.log_truncation.is_none()
+            && self.log_entries.is_empty()
+    }
+}
+
+impl<A: Application> Default for StorageTransaction<A> {
+    fn default() -> Self {
+        Self { current_term: None, voted_for: None, log_truncation: None, log_entries: Vec::new() }
+    }
 }
@@ -2,8 +2,26 @@
 
 use crate::prelude::*;
 
+/// Logical clock of the transmits, which ticks every time a transmit is created.
+static TRANSMIT_CLOCK: AtomicUsize = AtomicUsize::new(0);
+
+/// Gets the current time of the logical clock of the transmits.
+///
+/// Subtracting [PeerTransmit::created_at] or [ClientTransmit::created_at] from it
+/// gives the number of transmits created since, which is the age of the transmit.
+pub fn transmit_clock() -> usize {
+    TRANSMIT_CLOCK.load(AtomicOrdering::SeqCst)
+}
+
+fn tick_transmit_clock() -> usize {
+    TRANSMIT_CLOCK.fetch_add(1, AtomicOrdering::SeqCst)
+}
+
 /// Transmit between a [Peer] and another [Peer].
-#[derive(Clone, Debug, Eq, PartialEq, bon::Builder)]
+///
+/// Equality of transmits ignores their [PeerTransmit::created_at] timestamps.
+#[derive(Clone, Serialize, Deserialize, bon::Builder, derive_more::Debug)]
+#[serde(bound = "")]
 pub struct PeerTransmit<A: Application> {
     #[builder(into)]
     peer_id: PeerId,
@@ -13,8 +31,23 @@ pub struct PeerTransmit<A: Application> {
 
     #[builder(into)]
     message: PeerMessage<A>,
+
+    #[builder(skip = tick_transmit_clock())]
+    #[serde(skip, default = "tick_transmit_clock")]
+    #[debug(skip)]
+    created_at: usize,
+}
+
+impl<A: Application> PartialEq for PeerTransmit<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.peer_id == other.peer_id
+            && self.request_id == other.request_id
+            && self.message == other.message
+    }
 }
 
+impl<A: Application> Eq for PeerTransmit<A> {}
+
 impl<A: Application> PeerTransmit<A> {
     /// Gets the target [PeerId] of the transmit.
     pub fn peer_id(&self) -> PeerId {
@@ -30,6 +63,40 @@ impl<A: Application> PeerTransmit<A> {
     pub fn message(&self) -> &PeerMessage<A> {
         &self.message
     }
+
+    /// Gets the time of the [transmit_clock] when the transmit is created.
+    pub fn created_at(&self) -> usize {
+        self.created_at
+    }
+}
+
+#[bon::bon]
+impl<A: Application> PeerTransmit<A> {
+    /// Creates a transmit of a request, ensuring its message is a request.
+    #[builder(start_fn = request, finish_fn = build)]
+    pub fn new_request(
+        #[builder(into)] peer_id: PeerId,
+        #[builder(into)] request_id: RequestId,
+        #[builder(into)] message: PeerMessage<A>,
+    ) -> Result<Self, TransmitError> {
+        if !message.is_request() {
+            return Err(TransmitError::NotRequest { request_id });
+        }
+        Ok(Self { peer_id, request_id, message, created_at: tick_transmit_clock() })
+    }
+
+    /// Creates a transmit of a reply, ensuring its message is a reply.
+    #[builder(start_fn = reply, finish_fn = build)]
+    pub fn new_reply(
+        #[builder(into)] peer_id: PeerId,
+        #[builder(into)] request_id: RequestId,
+        #[builder(into)] message: PeerMessage<A>,
+    ) -> Result<Self, TransmitError> {
+        if !message.is_reply() {
+            return Err(TransmitError::NotReply { request_id });
+        }
+        Ok(Self { peer_id, request_id, message, created_at: tick_transmit_clock() })
+    }
 }
 
 impl<A: Application> PeerTransmit<A> {
@@ -37,10 +104,18 @@ impl<A: Application> PeerTransmit<A> {
     pub fn into_message(self) -> PeerMessage<A> {
         self.message
     }
+
+    /// Gets whether the transmit is equal to another transmit, ignoring their [RequestId]s.
+    ///
+    /// Request ids are assigned by counters, so it's useful for comparing the contents
+    /// of transmits without depending on the order they are created in.
+    pub fn eq_ignoring_request_id(&self, other: &Self) -> bool {
+        self.peer_id == other.peer_id && self.message == other.message
+    }
 }
 
 /// Transmit between a [Peer] and a [Client].
-#[derive(Debug, bon::Builder)]
+#[derive(Clone, bon::Builder, derive_more::Debug)]
 pub struct ClientTransmit<A: Application> {
     #[builder(into)]
     client_id: ClientId,
@@ -53,6 +128,10 @@ pub struct ClientTransmit<A: Application> {
 
     #[builder(into)]
     message: ClientMessage<A>,
+
+    #[builder(skip = tick_transmit_clock())]
+    #[debug(skip)]
+    created_at: usize,
 }
 
 impl<A: Application> ClientTransmit<A> {
@@ -75,11 +154,57 @@ impl<A: Application> ClientTransmit<A> {
     pub fn message(&self) -> &ClientMessage<A> {
         &self.message
     }
+
+    /// Gets the time of the [transmit_clock] when the transmit is created.
+    pub fn created_at(&self) -> usize {
+        self.created_at
+    }
 }
 
+#[bon::bon]
 impl<A: Application> ClientTransmit<A> {
-    /// Converts the transmit into its [PeerMessage].
+    /// Creates a transmit of a request, ensuring its message is a request.
+    #[builder(start_fn = request, finish_fn = build)]
+    pub fn new_request(
+        #[builder(into)] client_id: ClientId,
+        #[builder(into)] peer_id: PeerId,
+        #[builder(into)] request_id: RequestId,
+        #[builder(into)] message: ClientMessage<A>,
+    ) -> Result<Self, TransmitError> {
+        if !message.is_request() {
+            return Err(TransmitError::NotRequest { request_id });
+        }
+        Ok(Self { client_id, peer_id, request_id, message, created_at: tick_transmit_clock() })
+    }
+
+    /// Creates a transmit of a reply, ensuring its message is a reply.
+    #[builder(start_fn = reply, finish_fn = build)]
+    pub fn new_reply(
+        #[builder(into)] client_id: ClientId,
+        #[builder(into)] peer_id: PeerId,
+        #[builder(into)] request_id: RequestId,
+        #[builder(into)] message: ClientMessage<A>,
+    ) -> Result<Self, TransmitError> {
+        if !message.is_reply() {
+            return Err(TransmitError::NotReply { request_id });
+        }
+        Ok(Self { client_id, peer_id, request_id, message, created_at: tick_transmit_clock() })
+    }
+}
+
+impl<A: Application> ClientTransmit<A> {
+    /// Converts the transmit into its [ClientMessage].
     pub fn into_message(self) -> ClientMessage<A> {
         self.message
     }
+
+    /// Gets whether the transmit is equal to another transmit, ignoring their [RequestId]s.
+    ///
+    /// Request ids are assigned by counters, so it's useful for comparing the contents
+    /// of transmits without depending on the order they are created in.
+    pub fn eq_ignoring_request_id(&self, other: &Self) -> bool {
+        self.client_id == other.client_id
+            && self.peer_id == other.peer_id
+            && self.message == other.message
+    }
 }
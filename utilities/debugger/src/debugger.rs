@@ -23,6 +23,9 @@ pub(crate) enum DebuggerState<A: RaftApplication, CW: CommandWidget<A>, QW: Quer
         client_id: ClientId,
         input_widget: QW,
     },
+    SpecifyingWatch {
+        input_widget: QW,
+    },
 
     #[allow(unused)]
     Phantom(PhantomData<A>),
@@ -55,20 +58,87 @@ pub struct Debugger<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>
     logs_widget: LogsWidget,
     info_widget: InfoWidget,
     control_widget: ControlWidget,
+
+    watched_query: Option<A::Query>,
+
+    logs_percentage: u16,
+    info_percentage: u16,
+
+    semi_automatic: bool,
+
+    show_help: bool,
+
+    playback: VecDeque<SimulationAction<A>>,
+    playback_interval: Duration,
+    playing: bool,
+    last_auto_step: Option<Instant>,
+
+    log_file: Option<PathBuf>,
 }
 
+#[bon::bon]
 impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
     /// Creates a debugger for a simulation.
     pub fn new(simulation: Simulation<A>) -> anyhow::Result<Self> {
+        Self::builder().simulation(simulation).build()
+    }
+
+    /// Creates a debugger for a simulation with a custom configuration.
+    ///
+    /// - `initial_peer_id` is the peer to select initially (defaults to `PeerId(1)`).
+    /// - `log_level` is the level to display the logs from (defaults to `LevelFilter::Debug`).
+    /// - `logs_percentage` is the height of the logs panel in percents (defaults to `50`).
+    /// - `info_percentage` is the width of the info panel in percents (defaults to `70`).
+    /// - `watched_query` is the query to watch on the selected peer (defaults to none).
+    /// - `semi_automatic` is whether to transmit replies automatically (defaults to `false`).
+    /// - `playback` is the actions to perform before pending transmits during playback
+    ///   (defaults to none).
+    /// - `playback_interval` is the time between the steps of playback (defaults to 1 second).
+    /// - `playing` is whether to start playing back immediately (defaults to `false`).
+    /// - `log_file` is the file to append the logs to alongside the logs panel (defaults to none).
+    #[builder(finish_fn = build)]
+    pub fn builder(
+        simulation: Simulation<A>,
+        #[builder(into, default = PeerId(1))] initial_peer_id: PeerId,
+        #[builder(default = LevelFilter::Debug)] log_level: LevelFilter,
+        #[builder(default = 50)] logs_percentage: u16,
+        #[builder(default = 70)] info_percentage: u16,
+        watched_query: Option<A::Query>,
+        #[builder(default)] semi_automatic: bool,
+        #[builder(default)] playback: Vec<SimulationAction<A>>,
+        #[builder(default = Duration::from_secs(1))] playback_interval: Duration,
+        #[builder(default)] playing: bool,
+        #[builder(into)] log_file: Option<PathBuf>,
+    ) -> anyhow::Result<Self> {
         if simulation.number_of_peers() == 0 {
             return Err(anyhow::anyhow!("Debugger cannot be initialized with no peers"));
         }
         if simulation.number_of_clients() == 0 {
             return Err(anyhow::anyhow!("Debugger cannot be initialized with no clients"));
         }
+        if initial_peer_id.0 == 0 || initial_peer_id.0 > simulation.number_of_peers() {
+            return Err(anyhow::anyhow!(
+                "Debugger cannot be initialized with peer {} selected as it doesn't exist",
+                initial_peer_id,
+            ));
+        }
+        if !(1..=99).contains(&logs_percentage) {
+            return Err(anyhow::anyhow!("Logs panel percentage must be between 1 and 99"));
+        }
+        if !(1..=99).contains(&info_percentage) {
+            return Err(anyhow::anyhow!("Info panel percentage must be between 1 and 99"));
+        }
+        if !(Self::MIN_PLAYBACK_INTERVAL..=Self::MAX_PLAYBACK_INTERVAL).contains(&playback_interval)
+        {
+            return Err(anyhow::anyhow!(
+                "Playback interval must be between {:?} and {:?}",
+                Self::MIN_PLAYBACK_INTERVAL,
+                Self::MAX_PLAYBACK_INTERVAL,
+            ));
+        }
 
-        let logs_widget = LogsWidget::default();
-        let info_widget = InfoWidget::new(&simulation);
+        let logs_widget = LogsWidget::new(log_level);
+        let info_widget = InfoWidget::new(&simulation, initial_peer_id);
         let control_widget = ControlWidget::new(&info_widget);
 
         Ok(Debugger {
@@ -77,15 +147,177 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
             logs_widget,
             info_widget,
             control_widget,
+            watched_query,
+            logs_percentage,
+            info_percentage,
+            semi_automatic,
+            show_help: false,
+            playback: playback.into(),
+            playback_interval,
+            playing,
+            last_auto_step: None,
+            log_file,
         })
     }
 }
 
 impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
-    /// Starts the debugging session.
-    pub fn start(mut self) -> anyhow::Result<()> {
-        tui_logger::init_logger(LevelFilter::Trace)?;
-        tui_logger::set_default_level(LevelFilter::Trace);
+    /// Gets the simulation of the debugger.
+    pub fn simulation(&self) -> &Simulation<A> {
+        &self.simulation
+    }
+
+    /// Gets the identifier of the selected peer.
+    pub fn selected_peer_id(&self) -> PeerId {
+        self.info_widget.main_tab_selection.peer_id()
+    }
+
+    /// Gets the watched query of the debugger.
+    pub fn watched_query(&self) -> Option<&A::Query> {
+        self.watched_query.as_ref()
+    }
+
+    /// Gets whether replies are transmitted automatically after each action.
+    pub fn is_semi_automatic(&self) -> bool {
+        self.semi_automatic
+    }
+
+    /// Gets whether the help overlay listing the keybindings is shown.
+    pub fn is_showing_help(&self) -> bool {
+        self.show_help
+    }
+
+    /// Gets whether the simulation is being played back automatically.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Gets the time between the steps of playback.
+    pub fn playback_interval(&self) -> Duration {
+        self.playback_interval
+    }
+
+    /// Gets the actions left to perform before pending transmits during playback.
+    pub fn remaining_playback(&self) -> &VecDeque<SimulationAction<A>> {
+        &self.playback
+    }
+
+    /// Gets the file the logs are appended to alongside the logs panel.
+    pub fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
+    /// Gets the height of the logs panel in percents.
+    pub fn logs_percentage(&self) -> u16 {
+        self.logs_percentage
+    }
+
+    /// Gets the width of the info panel in percents.
+    pub fn info_percentage(&self) -> u16 {
+        self.info_percentage
+    }
+}
+
+impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
+    /// Exports the machine of a peer as JSON to `machine-{peer_id}.json` in a directory.
+    ///
+    /// Returns the path of the exported file.
+    pub fn export_machine(
+        &self,
+        peer_id: PeerId,
+        directory: impl AsRef<Path>,
+    ) -> anyhow::Result<PathBuf> {
+        let peer = self.simulation.peer(peer_id).ok_or_else(|| {
+            anyhow::anyhow!("Machine of peer {} cannot be exported as it doesn't exist", peer_id)
+        })?;
+
+        let path = directory.as_ref().join(format!("machine-{peer_id}.json"));
+        let machine = serde_json::to_string_pretty(peer.machine())
+            .with_context(|| format!("Failed to serialize the machine of peer {peer_id}"))?;
+        fs::write(&path, machine).with_context(|| {
+            format!("Failed to write the machine of peer {} to {}", peer_id, path.display())
+        })?;
+        Ok(path)
+    }
+}
+
+impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
+    /// Number of percents panels are grown or shrunk by at a time.
+    pub const PANEL_RESIZE_STEP: u16 = 5;
+
+    /// Minimum percentage panels can be shrunk to at runtime.
+    pub const MIN_PANEL_PERCENTAGE: u16 = 10;
+
+    /// Maximum percentage panels can be grown to at runtime.
+    pub const MAX_PANEL_PERCENTAGE: u16 = 90;
+
+    /// Minimum time between the steps of playback.
+    pub const MIN_PLAYBACK_INTERVAL: Duration = Duration::from_millis(125);
+
+    /// Maximum time between the steps of playback.
+    pub const MAX_PLAYBACK_INTERVAL: Duration = Duration::from_secs(8);
+}
+
+impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
+    /// Shows the help overlay listing the keybindings if it's hidden, hides it otherwise.
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+}
+
+impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
+    /// Pauses the playback if it's playing, resumes it otherwise.
+    pub fn toggle_playback(&mut self) {
+        self.playing = !self.playing;
+        self.last_auto_step = None;
+        if self.playing {
+            log::info!("<$> Playing back every {:?}", self.playback_interval);
+        } else {
+            log::info!("<$> Paused the playback");
+        }
+    }
+
+    /// Halves the time between the steps of playback, down to [Self::MIN_PLAYBACK_INTERVAL].
+    pub fn speed_up_playback(&mut self) {
+        self.playback_interval = (self.playback_interval / 2).max(Self::MIN_PLAYBACK_INTERVAL);
+        log::info!("<$> Playing back every {:?}", self.playback_interval);
+    }
+
+    /// Doubles the time between the steps of playback, up to [Self::MAX_PLAYBACK_INTERVAL].
+    pub fn slow_down_playback(&mut self) {
+        self.playback_interval = (self.playback_interval * 2).min(Self::MAX_PLAYBACK_INTERVAL);
+        log::info!("<$> Playing back every {:?}", self.playback_interval);
+    }
+
+    /// Performs the next step of playback regardless of the playback interval.
+    ///
+    /// The next remaining playback action is performed if there is any, otherwise the first
+    /// pending transmit is transmitted. Returns whether there was anything to perform.
+    pub fn auto_step(&mut self) -> anyhow::Result<bool> {
+        let Some(action) = self.playback.pop_front().or_else(|| self.next_pending_transmit())
+        else {
+            return Ok(false);
+        };
+        log::info!("<$> Performing {} automatically", action.name());
+        self.simulation.perform(action)?;
+        Ok(true)
+    }
+}
+
+impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, CW, QW> {
+    /// Starts the debugging session and returns the simulation once the session is exited.
+    ///
+    /// The logger is initialized with the log file of the debugger,
+    /// unless it's already initialized using [init_logger].
+    pub fn start(mut self) -> anyhow::Result<Simulation<A>> {
+        init_logger(self.log_file.as_deref())?;
+
+        log::info!(
+            "<$> Debugging {} with {} peers and {} clients",
+            A::NAME,
+            self.simulation.number_of_peers(),
+            self.simulation.number_of_clients(),
+        );
 
         crossterm::terminal::enable_raw_mode().context("Failed to enable raw mode")?;
         crossterm::execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
@@ -110,13 +342,16 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
         thread::spawn({
             let event_sender = event_sender.clone();
             move || {
-                while event_sender.send(DebuggerEvent::Redraw).is_ok() {
+                while event_sender.send(DebuggerEvent::Redraw).is_ok()
+                    && event_sender.send(DebuggerEvent::AutoStep).is_ok()
+                {
                     thread::sleep(Duration::from_millis(16));
                 }
             }
         });
 
         self.run(&mut terminal, event_receiver)?;
+        tui_logger::move_events();
 
         terminal.clear().context("Failed to clear the terminal")?;
 
@@ -124,7 +359,7 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
         crossterm::execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
             .context("Failed to leave alternate screen")?;
 
-        Ok(())
+        Ok(self.simulation)
     }
 }
 
@@ -136,8 +371,14 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
     ) -> anyhow::Result<()> {
         for event in event_receiver {
             match event {
-                DebuggerEvent::UserEvent(event) => self.on_user_event(event),
+                DebuggerEvent::UserEvent(event) => {
+                    self.on_user_event(event);
+                    if self.semi_automatic {
+                        self.transmit_replies();
+                    }
+                },
                 DebuggerEvent::Redraw => self.on_redraw(terminal)?,
+                DebuggerEvent::AutoStep => self.on_auto_step(),
             }
             if matches!(self.state, DebuggerState::Exiting) {
                 break;
@@ -147,6 +388,15 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
     }
 
     fn on_user_event(&mut self, event: Event) {
+        if self.show_help {
+            if let Event::Key(event) = event
+                && matches!(event.code, Key::Char('?') | Key::F(12) | Key::Esc)
+            {
+                self.toggle_help();
+            }
+            return;
+        }
+
         #[allow(clippy::collapsible_if)]
         if let Event::Key(event) = event {
             if event.code == Key::Esc {
@@ -188,6 +438,16 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
                             },
                         }
                     },
+                    DebuggerState::SpecifyingWatch { input_widget } => {
+                        match input_widget.back() {
+                            Some(new_input_widget) => {
+                                *input_widget = new_input_widget;
+                            },
+                            None => {
+                                self.state = DebuggerState::Debugging;
+                            },
+                        }
+                    },
                 }
                 return;
             }
@@ -197,6 +457,77 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
             DebuggerState::Exiting => {},
 
             DebuggerState::Debugging => {
+                #[allow(clippy::collapsible_if)]
+                if let Event::Key(event) = event {
+                    if matches!(event.code, Key::Char('?') | Key::F(12)) {
+                        self.toggle_help();
+                        return;
+                    }
+                    if event.code == Key::Backspace {
+                        if let Some(query) = self.watched_query.take() {
+                            log::info!("<$> Unwatching `{:?}` query", query);
+                        }
+                        return;
+                    }
+                    if event.modifiers.contains(KeyModifiers::CONTROL) {
+                        let resized = match event.code {
+                            Key::Up => Some((&mut self.logs_percentage, true)),
+                            Key::Down => Some((&mut self.logs_percentage, false)),
+                            Key::Right => Some((&mut self.info_percentage, true)),
+                            Key::Left => Some((&mut self.info_percentage, false)),
+                            _ => None,
+                        };
+                        if let Some((percentage, grow)) = resized {
+                            *percentage = Self::resize_panel(*percentage, grow);
+                            return;
+                        }
+                    }
+                    if matches!(event.code, Key::Char('r') | Key::Char('R'))
+                        && event.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        self.semi_automatic = !self.semi_automatic;
+                        if self.semi_automatic {
+                            log::info!("<$> Transmitting replies automatically");
+                        } else {
+                            log::info!("<$> Transmitting replies manually");
+                        }
+                        return;
+                    }
+                    if event.modifiers.contains(KeyModifiers::CONTROL) {
+                        match event.code {
+                            Key::Char('p') | Key::Char('P') => {
+                                self.toggle_playback();
+                                return;
+                            },
+                            Key::Char('f') | Key::Char('F') => {
+                                self.speed_up_playback();
+                                return;
+                            },
+                            Key::Char('b') | Key::Char('B') => {
+                                self.slow_down_playback();
+                                return;
+                            },
+                            _ => {},
+                        }
+                    }
+                    if matches!(event.code, Key::Char('e') | Key::Char('E'))
+                        && event.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        let peer_id = self.selected_peer_id();
+                        match self.export_machine(peer_id, ".") {
+                            Ok(path) => {
+                                log::info!(
+                                    "<$> Exported the machine of peer {} to {}",
+                                    peer_id,
+                                    path.display(),
+                                );
+                            },
+                            Err(error) => log::error!("<$> {:?}", error),
+                        }
+                        return;
+                    }
+                }
+
                 self.logs_widget.process_event(&event);
                 self.info_widget.process_event(&event, &self.simulation);
                 self.control_widget.process_event(
@@ -281,13 +612,17 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
                     let action = SimulationAction::SendCommand {
                         peer_id: Some(peer_id),
                         client_id: *client_id,
+                        request_id: None,
                         command,
                     };
                     if let Err(error) = self.simulation.perform(action) {
                         log::error!("<$> {:?}", error)
                     }
 
-                    let client = self.simulation.client(*client_id);
+                    let client = self
+                        .simulation
+                        .client(*client_id)
+                        .expect("Client exists in the simulation");
                     let mut buffered_transmits = client.buffered_client_transmits().iter();
                     if let Some(transmit) = buffered_transmits.next() {
                         assert!(buffered_transmits.next().is_none());
@@ -319,13 +654,17 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
                     let action = SimulationAction::SendQuery {
                         peer_id: Some(peer_id),
                         client_id: *client_id,
+                        request_id: None,
                         query,
                     };
                     if let Err(error) = self.simulation.perform(action) {
                         log::error!("<$> {:?}", error)
                     }
 
-                    let client = self.simulation.client(*client_id);
+                    let client = self
+                        .simulation
+                        .client(*client_id)
+                        .expect("Client exists in the simulation");
                     let mut buffered_transmits = client.buffered_client_transmits().iter();
                     if let Some(transmit) = buffered_transmits.next() {
                         assert!(buffered_transmits.next().is_none());
@@ -343,9 +682,136 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Debugger<A, C
                     self.state = DebuggerState::Debugging;
                 }
             },
+            DebuggerState::SpecifyingWatch { input_widget } => {
+                input_widget.on_user_event(event);
+                if let Some(query) = input_widget.finalize() {
+                    log::info!("<$> Watching `{:?}` query", query);
+                    self.watched_query = Some(query);
+                    self.state = DebuggerState::Debugging;
+                }
+            },
         }
     }
 
+    fn resize_panel(percentage: u16, grow: bool) -> u16 {
+        if grow {
+            (percentage + Self::PANEL_RESIZE_STEP).min(Self::MAX_PANEL_PERCENTAGE.max(percentage))
+        } else {
+            percentage
+                .saturating_sub(Self::PANEL_RESIZE_STEP)
+                .max(Self::MIN_PANEL_PERCENTAGE.min(percentage))
+        }
+    }
+
+    fn transmit_replies(&mut self) {
+        for peer_index in 0..self.simulation.number_of_peers() {
+            let peer_id = PeerId(peer_index + 1);
+            let peer = self.simulation.peer(peer_id).expect("Peer exists in the simulation");
+
+            let peer_replies = peer
+                .buffered_peer_transmits()
+                .iter()
+                .filter(|transmit| transmit.message().is_reply())
+                .map(|transmit| {
+                    SimulationAction::TransmitPeerReply {
+                        peer_id,
+                        replied_peer_id_and_request_id: (transmit.peer_id(), transmit.request_id()),
+                    }
+                });
+            let client_replies = peer
+                .buffered_client_transmits()
+                .iter()
+                .filter(|transmit| transmit.message().is_reply())
+                .map(|transmit| {
+                    SimulationAction::TransmitClientReply {
+                        peer_id,
+                        replied_client_id_and_request_id: (
+                            transmit.client_id(),
+                            transmit.request_id(),
+                        ),
+                    }
+                });
+
+            let actions = peer_replies.chain(client_replies).collect::<Vec<_>>();
+            for action in actions {
+                if let Err(error) = self.simulation.perform(action) {
+                    log::error!("<$> {:?}", error)
+                }
+            }
+        }
+    }
+
+    fn on_auto_step(&mut self) {
+        if !self.playing || !matches!(self.state, DebuggerState::Debugging) {
+            return;
+        }
+        if let Some(last_auto_step) = self.last_auto_step
+            && last_auto_step.elapsed() < self.playback_interval
+        {
+            return;
+        }
+        self.last_auto_step = Some(Instant::now());
+
+        match self.auto_step() {
+            Ok(true) => {},
+            Ok(false) => {
+                log::info!("<$> Nothing left to play back");
+                self.toggle_playback();
+            },
+            Err(error) => {
+                log::error!("<$> {:?}", error);
+                self.toggle_playback();
+            },
+        }
+    }
+
+    fn next_pending_transmit(&self) -> Option<SimulationAction<A>> {
+        for peer_index in 0..self.simulation.number_of_peers() {
+            let peer_id = PeerId(peer_index + 1);
+            let peer = self.simulation.peer(peer_id).expect("Peer exists in the simulation");
+            if let Some(transmit) = peer.buffered_peer_transmits().front() {
+                return Some(
+                    if transmit.message().is_request() {
+                        SimulationAction::TransmitPeerRequest {
+                            peer_id,
+                            request_id: transmit.request_id(),
+                        }
+                    } else {
+                        SimulationAction::TransmitPeerReply {
+                            peer_id,
+                            replied_peer_id_and_request_id: (
+                                transmit.peer_id(),
+                                transmit.request_id(),
+                            ),
+                        }
+                    },
+                );
+            }
+        }
+        for client_index in 0..self.simulation.number_of_clients() {
+            let client_id = ClientId(client_index + 1);
+            let client =
+                self.simulation.client(client_id).expect("Client exists in the simulation");
+            if let Some(transmit) = client.buffered_client_transmits().front() {
+                return Some(SimulationAction::TransmitClientRequest {
+                    client_id,
+                    request_id: transmit.request_id(),
+                });
+            }
+        }
+        for peer_index in 0..self.simulation.number_of_peers() {
+            let peer_id = PeerId(peer_index + 1);
+            let peer = self.simulation.peer(peer_id).expect("Peer exists in the simulation");
+            if let Some(transmit) = peer.buffered_client_transmits().front() {
+                return Some(SimulationAction::TransmitClientReply {
+                    peer_id,
+                    replied_client_id_and_request_id: (transmit.client_id(), transmit.request_id()),
+                });
+            }
+        }
+        None
+    }
+
     fn on_redraw(&mut self, terminal: &mut Terminal<impl Backend>) -> anyhow::Result<()> {
         terminal.draw(|frame| {
             frame.render_widget(&mut *self, frame.area());
@@ -358,16 +824,79 @@ impl<A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Widget
     for &mut Debugger<A, CW, QW>
 {
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        let [debugger_area, log_area] =
-            Layout::vertical([Constraint::Fill(50), Constraint::Fill(50)]).areas(area);
-
-        let [info_area, control_area] =
-            Layout::horizontal([Constraint::Fill(70), Constraint::Fill(30)]).areas(debugger_area);
+        let [debugger_area, log_area] = Layout::vertical([
+            Constraint::Fill(100 - self.logs_percentage),
+            Constraint::Fill(self.logs_percentage),
+        ])
+        .areas(area);
+
+        let [info_area, control_area] = Layout::horizontal([
+            Constraint::Fill(self.info_percentage),
+            Constraint::Fill(100 - self.info_percentage),
+        ])
+        .areas(debugger_area);
+
+        let (info_area, watch_area) = match &self.watched_query {
+            Some(_) => {
+                let [info_area, watch_area] =
+                    Layout::vertical([Constraint::Fill(100), Constraint::Length(4)])
+                        .areas(info_area);
+                (info_area, Some(watch_area))
+            },
+            None => (info_area, None),
+        };
 
         self.logs_widget.render(log_area, buffer);
         self.info_widget.renderer(&self.simulation).render(info_area, buffer);
+        if let (Some(query), Some(watch_area)) = (&self.watched_query, watch_area) {
+            let peer = self
+                .simulation
+                .peer(self.info_widget.main_tab_selection.peer_id())
+                .expect("Peer exists in the simulation");
+            WatchWidget { query, peer }.render(watch_area, buffer);
+        }
         self.control_widget
-            .renderer(&self.state, &self.info_widget, &self.simulation)
+            .renderer(
+                &self.state,
+                &self.info_widget,
+                &self.simulation,
+                self.semi_automatic,
+                self.playing.then_some(self.playback_interval),
+            )
             .render(control_area, buffer);
+
+        if self.show_help {
+            HelpWidget.render(area, buffer);
+        }
     }
 }
+
+/// Initializes the logger which the logs panel of the debugger displays.
+///
+/// Logs are appended to the log file, if provided. Initializing the logger before starting
+/// the debugger makes the logs emitted in the meantime appear in the logs panel.
+/// Subsequent calls, including the one in [Debugger::start], have no effect.
+pub fn init_logger(log_file: Option<&Path>) -> anyhow::Result<()> {
+    static INITIALIZED: Mutex<bool> = Mutex::new(false);
+
+    let mut initialized = INITIALIZED.lock().unwrap_or_else(PoisonError::into_inner);
+    if *initialized {
+        return Ok(());
+    }
+
+    tui_logger::init_logger(LevelFilter::Trace)?;
+    tui_logger::set_default_level(LevelFilter::Trace);
+    if let Some(log_file) = log_file {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .with_context(|| format!("Failed to open the log file at {}", log_file.display()))?;
+        tui_logger::set_log_file(
+            LoggerFile::new(&log_file.to_string_lossy()).output_file(false).output_line(false),
+        );
+    }
+
+    *initialized = true;
+    Ok(())
+}
@@ -4,4 +4,5 @@ use crate::*;
 pub enum DebuggerEvent {
     UserEvent(Event),
     Redraw,
+    AutoStep,
 }
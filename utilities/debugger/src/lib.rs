@@ -6,10 +6,17 @@ mod event;
 mod widgets;
 
 #[doc(inline)]
-pub use debugger::{
-    CommandWidget,
-    Debugger,
-    QueryWidget,
+pub use {
+    debugger::{
+        init_logger,
+        CommandWidget,
+        Debugger,
+        QueryWidget,
+    },
+    widgets::{
+        GenericCommandWidget,
+        GenericQueryWidget,
+    },
 };
 
 pub(crate) use {
@@ -20,10 +27,12 @@ pub(crate) use {
         },
         widgets::{
             ControlWidget,
+            HelpWidget,
             InfoWidget,
             LogsWidget,
             MainTabSelection,
             ScrollWidget,
+            WatchWidget,
         },
     },
     anyhow::Context,
@@ -62,6 +71,7 @@ pub(crate) use {
             Block,
             BorderType,
             Borders,
+            Clear,
             List,
             ListState,
             Padding,
@@ -73,15 +83,32 @@ pub(crate) use {
         },
     },
     std::{
+        collections::VecDeque,
+        fs::{
+            self,
+            OpenOptions,
+        },
         io,
+        path::{
+            Path,
+            PathBuf,
+        },
         str::Chars,
-        sync::mpsc,
+        sync::{
+            mpsc,
+            Mutex,
+            PoisonError,
+        },
         thread,
-        time::Duration,
+        time::{
+            Duration,
+            Instant,
+        },
     },
     tui_logger::{
         ExtLogRecord,
         LogFormatter,
+        TuiLoggerFile as LoggerFile,
         TuiLoggerWidget as LoggerWidget,
         TuiWidgetEvent as LoggerEvent,
         TuiWidgetState as LoggerState,
@@ -5,13 +5,17 @@ const FOLLOWER_ACTIONS: &[Action] = &[
     Action::ApplyCommittedEntries,
     Action::SendCommand,
     Action::SendQuery,
+    Action::WatchQuery,
 ];
 
 const CANDIDATE_ACTIONS: &[Action] = &[
     Action::TriggerElectionTimeout,
+    Action::RetransmitVoteRequests,
+    Action::GrantSimulatedVote,
     Action::ApplyCommittedEntries,
     Action::SendCommand,
     Action::SendQuery,
+    Action::WatchQuery,
 ];
 
 const LEADER_ACTIONS: &[Action] = &[
@@ -19,25 +23,35 @@ const LEADER_ACTIONS: &[Action] = &[
     Action::ApplyCommittedEntries,
     Action::SendCommand,
     Action::SendQuery,
+    Action::WatchQuery,
+    Action::StepDown,
 ];
 
 #[derive(Clone, Copy)]
 enum Action {
     TriggerElectionTimeout,
+    RetransmitVoteRequests,
+    GrantSimulatedVote,
     TriggerHeartbeatTimeout,
+    StepDown,
     ApplyCommittedEntries,
     SendCommand,
     SendQuery,
+    WatchQuery,
 }
 
 impl Action {
     pub fn list_view(&self) -> &'static str {
         match self {
             Action::TriggerElectionTimeout => "Trigger Election Timeout",
+            Action::RetransmitVoteRequests => "Retransmit Vote Requests",
+            Action::GrantSimulatedVote => "Grant Simulated Vote",
             Action::TriggerHeartbeatTimeout => "Trigger Heartbeat Timeout",
+            Action::StepDown => "Step Down",
             Action::ApplyCommittedEntries => "Apply Committed Entries",
             Action::SendCommand => "Send Command",
             Action::SendQuery => "Query",
+            Action::WatchQuery => "Watch",
         }
     }
 }
@@ -49,7 +63,9 @@ enum OperationSelection {
 
 impl OperationSelection {
     fn go_up<A: RaftApplication>(&mut self, info_widget: &InfoWidget, simulation: &Simulation<A>) {
-        let peer = simulation.peer(info_widget.main_tab_selection.peer_id());
+        let peer = simulation
+            .peer(info_widget.main_tab_selection.peer_id())
+            .expect("Peer exists in the simulation");
         match self {
             OperationSelection::Action { selected, actions } => {
                 if *selected > 0 {
@@ -86,7 +102,9 @@ impl OperationSelection {
         info_widget: &InfoWidget,
         simulation: &Simulation<A>,
     ) {
-        let peer = simulation.peer(info_widget.main_tab_selection.peer_id());
+        let peer = simulation
+            .peer(info_widget.main_tab_selection.peer_id())
+            .expect("Peer exists in the simulation");
         let transmit_count =
             peer.buffered_client_transmits().len() + peer.buffered_peer_transmits().len();
         match self {
@@ -133,6 +151,22 @@ impl OperationSelection {
                             log::error!("<$> {:?}", error)
                         }
                     },
+                    Action::RetransmitVoteRequests => {
+                        log::info!("<$> Retransmitting lost vote requests of peer {}", peer_id);
+                        if let Err(error) =
+                            simulation.perform(SimulationAction::RetransmitVoteRequests { peer_id })
+                        {
+                            log::error!("<$> {:?}", error)
+                        }
+                    },
+                    Action::GrantSimulatedVote => {
+                        log::info!("<$> Granting a simulated vote to peer {}", peer_id);
+                        if let Err(error) =
+                            simulation.perform(SimulationAction::GrantSimulatedVote { peer_id })
+                        {
+                            log::error!("<$> {:?}", error)
+                        }
+                    },
                     Action::TriggerHeartbeatTimeout => {
                         log::info!("<$> Triggering heartbeat timeout of peer {}", peer_id);
                         if let Err(error) =
@@ -141,16 +175,28 @@ impl OperationSelection {
                             log::error!("<$> {:?}", error)
                         }
                     },
+                    Action::StepDown => {
+                        log::info!("<$> Forcing peer {} to step down", peer_id);
+                        if let Err(error) =
+                            simulation.perform(SimulationAction::StepDown { peer_id })
+                        {
+                            log::error!("<$> {:?}", error)
+                        }
+                    },
 
                     Action::ApplyCommittedEntries => {
                         log::info!(
                             "<$> Applying committed entries of peer {} to its machine",
                             peer_id,
                         );
-                        if let Err(error) = simulation
+                        match simulation
                             .perform(SimulationAction::ApplyCommitted { peer_id: Some(peer_id) })
                         {
-                            log::error!("<$> {:?}", error)
+                            Ok(outcome) if outcome.applied_results().is_empty() => {
+                                log::info!("<$> Peer {} has no committed entries to apply", peer_id)
+                            },
+                            Ok(_) => {},
+                            Err(error) => log::error!("<$> {:?}", error),
                         }
                     },
 
@@ -166,10 +212,14 @@ impl OperationSelection {
                             selection: 0,
                         };
                     },
+                    Action::WatchQuery => {
+                        *debugger_state =
+                            DebuggerState::SpecifyingWatch { input_widget: Default::default() };
+                    },
                 }
             },
             OperationSelection::Transmit { selected } => {
-                let peer = simulation.peer(peer_id);
+                let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
 
                 let new_transmit_count = peer.buffered_client_transmits().len()
                     + peer.buffered_peer_transmits().len()
@@ -182,8 +232,6 @@ impl OperationSelection {
                     let transmits = peer.buffered_client_transmits();
                     let transmit = transmits.get(*selected).unwrap();
 
-                    assert!(transmit.message().is_reply());
-
                     let replied_client_id = transmit.client_id();
                     let request_id = transmit.request_id();
 
@@ -254,7 +302,8 @@ impl OperationSelection {
 
                 if is_client_transmit {
                     let client_id = client_id.unwrap();
-                    let client = simulation.client(client_id);
+                    let client =
+                        simulation.client(client_id).expect("Client exists in the simulation");
 
                     let mut buffered_transmits = client.buffered_client_transmits().iter();
                     if let Some(transmit) = buffered_transmits.next() {
@@ -281,6 +330,7 @@ pub struct ControlWidget {
 
     message_vertical_scroll: usize,
     message_horizontal_scroll: usize,
+    message_as_json: bool,
 }
 
 impl ControlWidget {
@@ -294,6 +344,7 @@ impl ControlWidget {
 
             message_vertical_scroll: 0,
             message_horizontal_scroll: 0,
+            message_as_json: false,
         }
     }
 }
@@ -309,7 +360,9 @@ impl ControlWidget {
         if self.previous_main_tab_selection != info_widget.main_tab_selection {
             self.previous_main_tab_selection = info_widget.main_tab_selection;
 
-            let peer = simulation.peer(info_widget.main_tab_selection.peer_id());
+            let peer = simulation
+                .peer(info_widget.main_tab_selection.peer_id())
+                .expect("Peer exists in the simulation");
             let actions = match peer.role() {
                 Role::Follower(_) => FOLLOWER_ACTIONS,
                 Role::Candidate(_) => CANDIDATE_ACTIONS,
@@ -343,10 +396,15 @@ impl ControlWidget {
                 Key::Char('d') | Key::Char('D') if event.modifiers.contains(KeyModifiers::ALT) => {
                     self.message_horizontal_scroll += 1;
                 },
+                Key::Char('j') | Key::Char('J') if event.modifiers.contains(KeyModifiers::ALT) => {
+                    self.message_as_json = !self.message_as_json;
+                    self.message_vertical_scroll = 0;
+                    self.message_horizontal_scroll = 0;
+                },
 
-                Key::F(n @ 1..=4) => {
+                Key::F(n @ 1..=6) => {
                     let peer_id = info_widget.main_tab_selection.peer_id();
-                    let peer = simulation.peer(peer_id);
+                    let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
 
                     let actions = match peer.role() {
                         Role::Follower(_) => FOLLOWER_ACTIONS,
@@ -360,7 +418,7 @@ impl ControlWidget {
 
                 Key::Char(n @ 'a'..='z') if event.modifiers.is_empty() => {
                     let peer_id = info_widget.main_tab_selection.peer_id();
-                    let peer = simulation.peer(peer_id);
+                    let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
 
                     let selected = (n as usize) - ('a' as usize);
                     if selected
@@ -372,7 +430,7 @@ impl ControlWidget {
                 },
                 Key::Char(n @ 'A'..='Z') if event.modifiers == KeyModifiers::SHIFT => {
                     let peer_id = info_widget.main_tab_selection.peer_id();
-                    let peer = simulation.peer(peer_id);
+                    let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
 
                     let selected = (n as usize) - ('A' as usize);
                     if selected
@@ -393,7 +451,7 @@ impl ControlWidget {
                 Key::Delete => {
                     if let OperationSelection::Transmit { selected } = &self.operation_selection {
                         let peer_id = info_widget.main_tab_selection.peer_id();
-                        let peer = simulation.peer(peer_id);
+                        let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
 
                         let new_transmit_count = peer.buffered_client_transmits().len()
                             + peer.buffered_peer_transmits().len()
@@ -404,8 +462,6 @@ impl ControlWidget {
                             let transmits = peer.buffered_client_transmits();
                             let transmit = transmits.get(*selected).unwrap();
 
-                            assert!(transmit.message().is_reply());
-
                             let replied_client_id = transmit.client_id();
                             let request_id = transmit.request_id();
 
@@ -486,8 +542,17 @@ impl ControlWidget {
         debugger_state: &'debugger DebuggerState<A, CW, QW>,
         info_widget: &'debugger InfoWidget,
         simulation: &'debugger Simulation<A>,
+        semi_automatic: bool,
+        playback_interval: Option<Duration>,
     ) -> ControlWidgetRenderer<'debugger, A, CW, QW> {
-        ControlWidgetRenderer { debugger_state, info_widget, control_widget: self, simulation }
+        ControlWidgetRenderer {
+            debugger_state,
+            info_widget,
+            control_widget: self,
+            simulation,
+            semi_automatic,
+            playback_interval,
+        }
     }
 }
 
@@ -501,13 +566,18 @@ pub struct ControlWidgetRenderer<
     info_widget: &'debugger InfoWidget,
     control_widget: &'debugger mut ControlWidget,
     simulation: &'debugger Simulation<A>,
+    semi_automatic: bool,
+    playback_interval: Option<Duration>,
 }
 
 impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Widget
     for &mut ControlWidgetRenderer<'debugger, A, CW, QW>
 {
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        let peer = self.simulation.peer(self.info_widget.main_tab_selection.peer_id());
+        let peer = self
+            .simulation
+            .peer(self.info_widget.main_tab_selection.peer_id())
+            .expect("Peer exists in the simulation");
 
         let [action_area, transmit_area, message_area] =
             Layout::vertical([Constraint::Length(6), Constraint::Length(8), Constraint::Fill(100)])
@@ -528,71 +598,76 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                 OperationSelection::Transmit { selected } => Some(selected),
             };
 
+            // Borders and padding take 3 columns, shortcuts take 4 and 1 is left as margin.
+            let width = (transmit_area.width as usize).saturating_sub(8);
+            let now = transmit_clock();
+
             let transmits = peer
                 .buffered_client_transmits()
                 .iter()
                 .map(|transmit| {
-                    match transmit.message() {
-                        ClientMessage::CommandRequest(_) | ClientMessage::QueryRequest(_) => {
-                            unreachable!()
-                        },
+                    let target = format!("of Client {}", transmit.client_id());
+                    let display = match transmit.message() {
+                        ClientMessage::CommandRequest(_)
+                        | ClientMessage::QueryRequest(_)
+                        | ClientMessage::FindLeaderRequest(_) => unreachable!(),
 
                         ClientMessage::CommandReply(_) => {
-                            format!(
-                                "(CommandReply) #{} of Client {}",
-                                transmit.request_id(),
-                                transmit.client_id(),
-                            )
+                            TransmitDisplay::reply("CommandReply", Color::Green)
                         },
                         ClientMessage::QueryReply(_) => {
-                            format!(
-                                "(QueryReply) #{} of Client {}",
-                                transmit.request_id(),
-                                transmit.client_id(),
-                            )
+                            TransmitDisplay::reply("QueryReply", Color::LightGreen)
                         },
-                    }
+                        ClientMessage::FindLeaderReply(_) => {
+                            TransmitDisplay::reply("FindLeaderReply", Color::LightBlue)
+                        },
+                    };
+                    let age = now.saturating_sub(transmit.created_at());
+                    (display, transmit.request_id(), age, target)
                 })
                 .chain(peer.buffered_peer_transmits().iter().map(|transmit| {
-                    match transmit.message() {
+                    let display = match transmit.message() {
                         PeerMessage::RequestVoteRequest(_) => {
-                            format!(
-                                "(RequestVoteRequest) #{} to Peer {}",
-                                transmit.request_id(),
-                                transmit.peer_id(),
-                            )
+                            TransmitDisplay::request("RequestVoteRequest", Color::Magenta)
                         },
                         PeerMessage::RequestVoteReply(_) => {
-                            format!(
-                                "(RequestVoteReply) #{} of Peer {}",
-                                transmit.request_id(),
-                                transmit.peer_id(),
-                            )
+                            TransmitDisplay::reply("RequestVoteReply", Color::LightMagenta)
                         },
                         PeerMessage::AppendEntriesRequest(_) => {
-                            format!(
-                                "(AppendEntriesRequest) #{} to Peer {}",
-                                transmit.request_id(),
-                                transmit.peer_id(),
-                            )
+                            TransmitDisplay::request("AppendEntriesRequest", Color::Cyan)
                         },
                         PeerMessage::AppendEntriesReply(_) => {
-                            format!(
-                                "(AppendEntriesReply) #{} of Peer {}",
-                                transmit.request_id(),
-                                transmit.peer_id(),
-                            )
+                            TransmitDisplay::reply("AppendEntriesReply", Color::LightCyan)
                         },
-                    }
+                    };
+                    let target = if display.is_request {
+                        format!("to Peer {}", transmit.peer_id())
+                    } else {
+                        format!("of Peer {}", transmit.peer_id())
+                    };
+                    let age = now.saturating_sub(transmit.created_at());
+                    (display, transmit.request_id(), age, target)
                 }))
                 .enumerate()
-                .map(|(i, display)| {
+                .map(|(i, (display, request_id, age, target))| {
                     let mut style = Style::default();
                     if selected == Some(i) {
                         style = style.reversed();
                     }
 
                     let shortcut = ('a' as usize) + i;
+                    let glyph = if display.is_request { "↑ " } else { "↓ " };
+                    let kind = format!("({})", display.kind);
+                    let request_id = format!(" #{request_id}");
+                    let age = format!(" ~{age}");
+
+                    let used = glyph.chars().count()
+                        + kind.chars().count()
+                        + request_id.chars().count()
+                        + age.chars().count()
+                        + target.chars().count();
+                    let gap = " ".repeat(width.saturating_sub(used).max(1));
+
                     let spans = vec![
                         Span::styled(
                             if shortcut < 'z' as usize {
@@ -602,18 +677,37 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                             },
                             Style::default().yellow(),
                         ),
-                        Span::styled(display, style),
+                        Span::styled(glyph, style.fg(display.color)),
+                        Span::styled(kind, style.fg(display.color)),
+                        Span::styled(request_id, style),
+                        Span::styled(age, style.dark_gray()),
+                        Span::styled(gap, style),
+                        Span::styled(target, style),
                     ];
                     Line::from(spans)
                 });
-            let transmit_list = List::new(transmits).block(
-                Block::bordered()
-                    .borders(Borders::ALL)
-                    .padding(Padding::left(1))
-                    .title(" Awaiting Transmits ")
-                    .title_style(Style::default().fg(Color::Green))
-                    .border_type(BorderType::Rounded),
-            );
+            let mut transmit_block = Block::bordered()
+                .borders(Borders::ALL)
+                .padding(Padding::left(1))
+                .title(" Awaiting Transmits ")
+                .title_style(Style::default().fg(Color::Green))
+                .border_type(BorderType::Rounded);
+            if self.semi_automatic {
+                transmit_block = transmit_block.title_top(
+                    Line::styled(" Auto Replies ", Style::default().fg(Color::Yellow))
+                        .right_aligned(),
+                );
+            }
+            if let Some(playback_interval) = self.playback_interval {
+                transmit_block = transmit_block.title_top(
+                    Line::styled(
+                        format!(" Playing Every {playback_interval:?} "),
+                        Style::default().fg(Color::Yellow),
+                    )
+                    .right_aligned(),
+                );
+            }
+            let transmit_list = List::new(transmits).block(transmit_block);
             let mut transmit_list_state =
                 ListState::default().with_selected(match self.control_widget.operation_selection {
                     OperationSelection::Action { .. } => None,
@@ -645,11 +739,27 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                         let transmit = transmits.get(*selected).unwrap();
 
                         match transmit.message() {
-                            ClientMessage::CommandRequest(_) | ClientMessage::QueryRequest(_) => {
-                                unreachable!()
+                            ClientMessage::CommandRequest(_)
+                            | ClientMessage::QueryRequest(_)
+                            | ClientMessage::FindLeaderRequest(_) => unreachable!(),
+                            _ if self.control_widget.message_as_json => {
+                                serde_json::to_string_pretty(transmit.message()).unwrap_or_else(
+                                    |error| format!("Unable to serialize the message ({error})"),
+                                )
+                            },
+                            ClientMessage::CommandReply(message) => {
+                                match message.result() {
+                                    Ok(result) => result.summary(),
+                                    Err(error) => format!("Error: {error}"),
+                                }
                             },
-                            ClientMessage::CommandReply(message) => format!("{message:#?}"),
-                            ClientMessage::QueryReply(message) => format!("{message:#?}"),
+                            ClientMessage::QueryReply(message) => {
+                                match message.result() {
+                                    Ok(result) => result.summary(),
+                                    Err(error) => format!("Error: {error}"),
+                                }
+                            },
+                            ClientMessage::FindLeaderReply(message) => format!("{message:#?}"),
                         }
                     } else {
                         let selected = *selected - peer.buffered_client_transmits().len();
@@ -658,6 +768,11 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                         let transmit = transmits.get(selected).unwrap();
 
                         match transmit.message() {
+                            _ if self.control_widget.message_as_json => {
+                                serde_json::to_string_pretty(transmit.message()).unwrap_or_else(
+                                    |error| format!("Unable to serialize the message ({error})"),
+                                )
+                            },
                             PeerMessage::RequestVoteRequest(message) => format!("{message:#?}"),
                             PeerMessage::RequestVoteReply(message) => format!("{message:#?}"),
                             PeerMessage::AppendEntriesRequest(message) => {
@@ -673,7 +788,13 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                 block: Block::bordered()
                     .borders(Borders::ALL)
                     .padding(Padding::left(1))
-                    .title(" Message ")
+                    .title(
+                        if self.control_widget.message_as_json {
+                            " Message (JSON) "
+                        } else {
+                            " Message "
+                        },
+                    )
                     .title_style(Style::default().fg(Color::Green))
                     .border_type(BorderType::Rounded),
                 content: &message,
@@ -685,6 +806,22 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
     }
 }
 
+struct TransmitDisplay {
+    kind: &'static str,
+    color: Color,
+    is_request: bool,
+}
+
+impl TransmitDisplay {
+    fn request(kind: &'static str, color: Color) -> Self {
+        Self { kind, color, is_request: true }
+    }
+
+    fn reply(kind: &'static str, color: Color) -> Self {
+        Self { kind, color, is_request: false }
+    }
+}
+
 pub struct ActionWidget<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> {
     simulation: &'debugger Simulation<A>,
     debugger_state: &'debugger DebuggerState<A, CW, QW>,
@@ -755,10 +892,44 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
                             style = style.reversed();
                         }
 
-                        let spans = vec![
+                        let client = self
+                            .simulation
+                            .client(ClientId(client_id))
+                            .expect("Client exists in the simulation");
+                        let outstanding = |request_ids: Vec<RequestId>| {
+                            request_ids
+                                .iter()
+                                .map(|request_id| request_id.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        };
+
+                        let mut spans = vec![
                             Span::styled(format!("<{client_id}> "), Style::default().magenta()),
                             Span::styled(format!("Via Client {client_id}"), style),
                         ];
+                        if !client.outstanding_commands().is_empty() {
+                            spans.push(Span::styled(
+                                format!(
+                                    " (awaiting commands {})",
+                                    outstanding(
+                                        client.outstanding_commands().keys().copied().collect()
+                                    ),
+                                ),
+                                Style::default().dark_gray(),
+                            ));
+                        }
+                        if !client.outstanding_queries().is_empty() {
+                            spans.push(Span::styled(
+                                format!(
+                                    " (awaiting queries {})",
+                                    outstanding(
+                                        client.outstanding_queries().keys().copied().collect()
+                                    ),
+                                ),
+                                Style::default().dark_gray(),
+                            ));
+                        }
                         Line::from(spans)
                     }))
                     .block(
@@ -795,6 +966,9 @@ impl<'debugger, A: RaftApplication, CW: CommandWidget<A>, QW: QueryWidget<A>> Wi
             DebuggerState::SpecifyingQuery { input_widget, .. } => {
                 input_widget.renderer().render(area, buffer);
             },
+            DebuggerState::SpecifyingWatch { input_widget } => {
+                input_widget.renderer().render(area, buffer);
+            },
         }
     }
 }
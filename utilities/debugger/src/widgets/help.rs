@@ -0,0 +1,85 @@
+use crate::*;
+
+const HELP_SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "General",
+        &[
+            ("? / F12", "Toggle this help"),
+            ("Esc", "Go back or exit"),
+            ("Backspace", "Unwatch the watched query"),
+            ("Ctrl+Up / Ctrl+Down", "Grow or shrink the logs panel"),
+            ("Ctrl+Right / Ctrl+Left", "Grow or shrink the info panel"),
+            ("Ctrl+R", "Toggle transmitting replies automatically"),
+            ("Ctrl+P", "Toggle playback"),
+            ("Ctrl+F / Ctrl+B", "Speed up or slow down playback"),
+            ("Ctrl+E", "Export the machine of the selected peer"),
+        ],
+    ),
+    (
+        "Info",
+        &[
+            ("Left / Right", "Select the previous or the next peer"),
+            ("1-9", "Select a peer"),
+            ("Tab / Shift+Tab", "Select the next or the previous details tab"),
+            ("Ctrl+W / Ctrl+S", "Select a log entry or scroll the details vertically"),
+            ("Ctrl+A / Ctrl+D", "Scroll the details horizontally"),
+        ],
+    ),
+    (
+        "Control",
+        &[
+            ("Up / Down", "Select the previous or the next operation"),
+            ("F1-F6", "Select an action"),
+            ("a-z / A-Z", "Select a buffered transmit"),
+            ("Enter", "Perform the selected operation"),
+            ("Delete", "Drop the selected transmit"),
+            ("Alt+W / Alt+S", "Scroll the selected message vertically"),
+            ("Alt+A / Alt+D", "Scroll the selected message horizontally"),
+            ("Alt+J", "Toggle showing the selected message as JSON"),
+        ],
+    ),
+    ("Logs", &[("Mouse Wheel", "Scroll the logs")]),
+];
+
+pub struct HelpWidget;
+
+impl Widget for HelpWidget {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let key_width = HELP_SECTIONS
+            .iter()
+            .flat_map(|(_, bindings)| bindings.iter())
+            .map(|(keys, _)| keys.len())
+            .max()
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        for (index, (section, bindings)) in HELP_SECTIONS.iter().enumerate() {
+            if index != 0 {
+                lines.push(Line::default());
+            }
+            lines.push(Line::styled(*section, Style::default().yellow()));
+            for (keys, description) in bindings.iter() {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{keys:key_width$}  "), Style::default().magenta()),
+                    Span::raw(*description),
+                ]));
+            }
+        }
+
+        let height = (lines.len() as u16) + 2;
+        let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+        let [area] = Layout::horizontal([Constraint::Length(80)]).flex(Flex::Center).areas(area);
+
+        Clear.render(area, buffer);
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .borders(Borders::ALL)
+                    .padding(Padding::left(1))
+                    .title(" Help ")
+                    .title_style(Style::default().fg(Color::Green))
+                    .border_type(BorderType::Rounded),
+            )
+            .render(area, buffer);
+    }
+}
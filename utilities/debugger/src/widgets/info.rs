@@ -48,7 +48,8 @@ impl MainTabSelection {
         }
 
         let new_peer_id = PeerId(new_tab_index + 1);
-        details_tab_selection.reset(simulation.peer(new_peer_id));
+        details_tab_selection
+            .reset(simulation.peer(new_peer_id).expect("Peer exists in the simulation"));
 
         *self = MainTabSelection(PeerId(new_tab_index + 1));
     }
@@ -94,12 +95,42 @@ impl DetailsTabSelection {
         }
     }
 
+    fn go_next_tab<A: RaftApplication>(&mut self, peer: &Peer<A>) {
+        *self = match self {
+            DetailsTabSelection::Log { .. } => {
+                DetailsTabSelection::Machine { vertical_scroll: 0, horizontal_scroll: 0 }
+            },
+            DetailsTabSelection::Machine { .. } => {
+                DetailsTabSelection::Snapshot {
+                    machine_vertical_scroll: 0,
+                    machine_horizontal_scroll: 0,
+                }
+            },
+            DetailsTabSelection::Snapshot { .. } => DetailsTabSelection::last_log(peer),
+        };
+    }
+
+    fn go_previous_tab<A: RaftApplication>(&mut self, peer: &Peer<A>) {
+        *self = match self {
+            DetailsTabSelection::Log { .. } => {
+                DetailsTabSelection::Snapshot {
+                    machine_vertical_scroll: 0,
+                    machine_horizontal_scroll: 0,
+                }
+            },
+            DetailsTabSelection::Machine { .. } => DetailsTabSelection::last_log(peer),
+            DetailsTabSelection::Snapshot { .. } => {
+                DetailsTabSelection::Machine { vertical_scroll: 0, horizontal_scroll: 0 }
+            },
+        };
+    }
+
     fn go_up<A: RaftApplication>(&mut self, simulation: &Simulation<A>, peer_id: PeerId) {
         match self {
             DetailsTabSelection::Log { selected } => {
                 if let Some(selected) = selected {
                     if *selected == 0 {
-                        let peer = simulation.peer(peer_id);
+                        let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
                         *selected = peer.log().len() - 1;
                     } else {
                         *selected = selected.saturating_sub(1);
@@ -119,7 +150,7 @@ impl DetailsTabSelection {
         match self {
             DetailsTabSelection::Log { selected } => {
                 if let Some(selected) = selected {
-                    let peer = simulation.peer(peer_id);
+                    let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
                     if *selected == peer.log().len() - 1 {
                         *selected = 0;
                     } else {
@@ -170,15 +201,17 @@ pub struct InfoWidget {
 }
 
 impl InfoWidget {
-    pub fn new<A: RaftApplication>(simulation: &Simulation<A>) -> Self {
+    pub fn new<A: RaftApplication>(simulation: &Simulation<A>, initial_peer_id: PeerId) -> Self {
         Self {
             main_tabs: (1..=simulation.number_of_peers())
                 .map(|peer_id| format!("Peer {peer_id}"))
                 .collect(),
-            main_tab_selection: MainTabSelection(PeerId(1)),
+            main_tab_selection: MainTabSelection(initial_peer_id),
 
             details_tabs: vec!["Log".to_owned(), "Machine".to_owned(), "Snapshot".to_owned()],
-            details_tab_selection: DetailsTabSelection::last_log(simulation.peer(PeerId(1))),
+            details_tab_selection: DetailsTabSelection::last_log(
+                simulation.peer(initial_peer_id).expect("Peer exists in the simulation"),
+            ),
         }
     }
 }
@@ -217,25 +250,18 @@ impl InfoWidget {
                 },
 
                 Key::Tab => {
-                    match self.details_tab_selection {
-                        DetailsTabSelection::Log { .. } => {
-                            self.details_tab_selection = DetailsTabSelection::Machine {
-                                vertical_scroll: 0,
-                                horizontal_scroll: 0,
-                            };
-                        },
-                        DetailsTabSelection::Machine { .. } => {
-                            self.details_tab_selection = DetailsTabSelection::Snapshot {
-                                machine_vertical_scroll: 0,
-                                machine_horizontal_scroll: 0,
-                            };
-                        },
-                        DetailsTabSelection::Snapshot { .. } => {
-                            self.details_tab_selection = DetailsTabSelection::last_log(
-                                simulation.peer(self.main_tab_selection.peer_id()),
-                            );
-                        },
-                    }
+                    self.details_tab_selection.go_next_tab(
+                        simulation
+                            .peer(self.main_tab_selection.peer_id())
+                            .expect("Peer exists in the simulation"),
+                    );
+                },
+                Key::BackTab => {
+                    self.details_tab_selection.go_previous_tab(
+                        simulation
+                            .peer(self.main_tab_selection.peer_id())
+                            .expect("Peer exists in the simulation"),
+                    );
                 },
 
                 Key::Char(n @ '1'..='9') => {
@@ -269,8 +295,11 @@ pub struct InfoWidgetRenderer<'debugger, A: RaftApplication> {
 
 impl<'debugger, A: RaftApplication> Widget for &mut InfoWidgetRenderer<'debugger, A> {
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        let block =
-            Block::bordered().padding(Padding::new(1, 1, 1, 1)).border_type(BorderType::Rounded);
+        let block = Block::bordered()
+            .padding(Padding::new(1, 1, 1, 1))
+            .border_type(BorderType::Rounded)
+            .title_top(Line::from(format!(" {} ", A::NAME)).right_aligned())
+            .title_style(Style::default().fg(Color::Blue));
 
         let inner_area = block.inner(area);
         match self.info_widget.main_tab_selection {
@@ -302,7 +331,7 @@ pub struct PeerWidget<'debugger, A: RaftApplication> {
 
 impl<'debugger, A: RaftApplication> Widget for &mut PeerWidget<'debugger, A> {
     fn render(self, area: Rect, buffer: &mut Buffer) {
-        let peer = self.simulation.peer(self.peer_id);
+        let peer = self.simulation.peer(self.peer_id).expect("Peer exists in the simulation");
 
         let [others_area, _, role_area] =
             Layout::horizontal([Constraint::Fill(75), Constraint::Length(1), Constraint::Fill(25)])
@@ -317,7 +346,7 @@ impl<'debugger, A: RaftApplication> Widget for &mut PeerWidget<'debugger, A> {
         let mut details_widget = DetailsWidget { info_widget: self.info_widget, peer };
         details_widget.render(details_area, buffer);
 
-        let mut role_widget = RoleWidget { role: peer.role() };
+        let mut role_widget = RoleWidget { role: peer.role(), quorum_status: peer.quorum_status() };
         role_widget.render(role_area, buffer);
     }
 }
@@ -403,21 +432,24 @@ impl<'debugger, A: RaftApplication> Widget for &mut DetailsWidget<'debugger, A>
         let inner_area = block.inner(area);
         match &mut self.info_widget.details_tab_selection {
             DetailsTabSelection::Log { selected } => {
-                let entries = self.peer.log().iter().map(|entry| {
-                    let is_applied = entry.index() <= self.peer.last_applied();
-                    let is_committed = entry.index() <= self.peer.commit_index();
+                let entries = self.peer.annotated_log().map(|(entry, status)| {
                     let spans = vec![
                         Span::styled(
                             format!("[{}] ", entry.index()),
-                            if is_applied {
-                                Style::default().green()
-                            } else if is_committed {
-                                Style::default().yellow()
-                            } else {
-                                Style::default().red()
+                            match status {
+                                EntryStatus::Applied => Style::default().green(),
+                                EntryStatus::Committed => Style::default().yellow(),
+                                EntryStatus::Uncommitted => Style::default().red(),
                             },
                         ),
                         Span::styled(format!("({}) ", entry.term()), Style::default().cyan()),
+                        Span::styled(
+                            entry
+                                .sequence()
+                                .map(|sequence| format!("#{sequence} "))
+                                .unwrap_or_default(),
+                            Style::default().dark_gray(),
+                        ),
                         Span::raw(format!("{:?}", entry.command())),
                     ];
                     Line::from(spans)
@@ -468,10 +500,14 @@ impl<'debugger, A: RaftApplication> Widget for &mut DetailsWidget<'debugger, A>
                     Layout::vertical([Constraint::Length(3), Constraint::Fill(100)])
                         .areas(inner_area);
 
-                let [last_included_index_area, last_included_term_area] =
-                    Layout::horizontal([Constraint::Length(34), Constraint::Length(35)])
-                        .flex(Flex::Center)
-                        .areas(last_included_area);
+                let [last_included_index_area, last_included_term_area, applied_since_area] =
+                    Layout::horizontal([
+                        Constraint::Length(23),
+                        Constraint::Length(23),
+                        Constraint::Length(23),
+                    ])
+                    .flex(Flex::Center)
+                    .areas(last_included_area);
 
                 let [machine_area] = Layout::horizontal([Constraint::Length(69)])
                     .flex(Flex::Center)
@@ -499,11 +535,31 @@ impl<'debugger, A: RaftApplication> Widget for &mut DetailsWidget<'debugger, A>
                     )
                     .render(last_included_term_area, buffer);
 
-                let machine = format!("{:#?}", self.peer.snapshot().machine());
+                // Entries applied to the live machine after the snapshot was taken.
+                let applied_since = self
+                    .peer
+                    .last_applied()
+                    .0
+                    .saturating_sub(self.peer.snapshot().last_included_index().0);
+                Paragraph::new(format!("{applied_since} entries"))
+                    .alignment(Alignment::Center)
+                    .block(
+                        Block::bordered()
+                            .border_type(BorderType::Rounded)
+                            .title(" Applied Since ")
+                            .title_alignment(Alignment::Center)
+                            .title_style(Style::default().fg(Color::Blue)),
+                    )
+                    .render(applied_since_area, buffer);
+
+                let machine = diff_lines(
+                    &format!("{:#?}", self.peer.snapshot().machine()),
+                    &format!("{:#?}", self.peer.machine()),
+                );
                 let mut scroll_widget = ScrollWidget {
                     block: Block::bordered()
                         .border_type(BorderType::Rounded)
-                        .title(" Machine ")
+                        .title(" Machine (- Snapshot / + Live) ")
                         .title_alignment(Alignment::Center)
                         .title_style(Style::default().fg(Color::Blue)),
                     content: &machine,
@@ -530,6 +586,7 @@ impl<'debugger, A: RaftApplication> Widget for &mut DetailsWidget<'debugger, A>
 
 pub struct RoleWidget<'debugger, A: RaftApplication> {
     role: &'debugger Role<A>,
+    quorum_status: Option<(usize, usize)>,
 }
 
 impl<'debugger, A: RaftApplication> Widget for &mut RoleWidget<'debugger, A> {
@@ -624,16 +681,25 @@ impl<'debugger, A: RaftApplication> Widget for &mut RoleWidget<'debugger, A> {
                     .map(|(peer_id, next_index)| format!("Peer {peer_id} -> {next_index}"))
                     .collect::<Vec<_>>();
 
-                let [role_area, next_index_area, match_index_area] = Layout::vertical([
-                    Constraint::Length(3),
-                    Constraint::Length((next_index_lines.len() + 2) as u16),
-                    Constraint::Length((match_index_lines.len() + 2) as u16),
-                ])
-                .areas(inner_area);
+                let [role_area, initial_noop_area, quorum_area, next_index_area, match_index_area] =
+                    Layout::vertical([
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Length(3),
+                        Constraint::Length((next_index_lines.len() + 2) as u16),
+                        Constraint::Length((match_index_lines.len() + 2) as u16),
+                    ])
+                    .areas(inner_area);
 
                 let [role_area] = Layout::horizontal([Constraint::Length(20)])
                     .flex(Flex::Center)
                     .areas(role_area);
+                let [initial_noop_area] = Layout::horizontal([Constraint::Length(20)])
+                    .flex(Flex::Center)
+                    .areas(initial_noop_area);
+                let [quorum_area] = Layout::horizontal([Constraint::Length(20)])
+                    .flex(Flex::Center)
+                    .areas(quorum_area);
                 let [next_index_area] = Layout::horizontal([Constraint::Length(20)])
                     .flex(Flex::Center)
                     .areas(next_index_area);
@@ -652,6 +718,36 @@ impl<'debugger, A: RaftApplication> Widget for &mut RoleWidget<'debugger, A> {
                     )
                     .render(role_area, buffer);
 
+                let initial_noop_status =
+                    if leader_state.initial_noop_committed() { "Committed" } else { "Pending" };
+                Paragraph::new(initial_noop_status)
+                    .alignment(Alignment::Center)
+                    .block(
+                        Block::bordered()
+                            .border_type(BorderType::Rounded)
+                            .title(" Initial No-op ")
+                            .title_alignment(Alignment::Center)
+                            .title_style(Style::default().fg(Color::Blue)),
+                    )
+                    .render(initial_noop_area, buffer);
+
+                if let Some((replicated, majority)) = self.quorum_status {
+                    let quorum_color =
+                        if replicated >= majority { Color::Green } else { Color::Red };
+                    Paragraph::new(format!("{replicated} / {majority}"))
+                        .alignment(Alignment::Center)
+                        .style(Style::default().fg(quorum_color))
+                        .block(
+                            Block::bordered()
+                                .border_type(BorderType::Rounded)
+                                .title(" Quorum ")
+                                .title_alignment(Alignment::Center)
+                                .title_style(Style::default().fg(Color::Blue))
+                                .border_style(Style::default().fg(Color::Reset)),
+                        )
+                        .render(quorum_area, buffer);
+                }
+
                 Paragraph::new(next_index_lines.join("\n"))
                     .block(
                         Block::bordered()
@@ -679,3 +775,41 @@ impl<'debugger, A: RaftApplication> Widget for &mut RoleWidget<'debugger, A> {
         block.render(area, buffer);
     }
 }
+
+/// Annotates the lines of two texts with the changes from the former to the latter.
+///
+/// Lines only in the former are prefixed with `- `, lines only in the latter are prefixed
+/// with `+ `, and common lines are prefixed with two spaces, in the order of a longest
+/// common subsequence of the lines.
+fn diff_lines(before: &str, after: &str) -> String {
+    let before = before.lines().collect::<Vec<_>>();
+    let after = after.lines().collect::<Vec<_>>();
+
+    let mut common = vec![vec![0; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(before.len().max(after.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            lines.push(format!("  {}", before[i]));
+            i += 1;
+            j += 1;
+        } else if j == after.len() || (i < before.len() && common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(format!("- {}", before[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", after[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,304 @@
+use crate::*;
+
+/// [CommandWidget] built from the [InputSchema]s of the commands of a [RaftApplication].
+///
+/// It's usable with any application describing its commands with [RaftCommand::schemas].
+pub struct GenericCommandWidget<A: RaftApplication> {
+    widget: SchemaWidget<A::Command>,
+}
+
+impl<A: RaftApplication> Default for GenericCommandWidget<A> {
+    fn default() -> Self {
+        Self {
+            widget: SchemaWidget::new(
+                A::Command::schemas(),
+                A::Command::from_inputs,
+                " Sending Command... ",
+                "Commanding",
+            ),
+        }
+    }
+}
+
+impl<A: RaftApplication> CommandWidget<A> for GenericCommandWidget<A> {
+    fn on_user_event(&mut self, event: Event) {
+        self.widget.on_user_event(event);
+    }
+
+    fn back(&self) -> Option<Self> {
+        self.widget.back().map(|widget| Self { widget })
+    }
+
+    fn renderer(&self) -> impl Widget {
+        SchemaWidgetRendered { widget: &self.widget }
+    }
+
+    fn finalize(&mut self) -> Option<A::Command> {
+        self.widget.finalize()
+    }
+}
+
+/// [QueryWidget] built from the [InputSchema]s of the queries of a [RaftApplication].
+///
+/// It's usable with any application describing its queries with [RaftQuery::schemas].
+pub struct GenericQueryWidget<A: RaftApplication> {
+    widget: SchemaWidget<A::Query>,
+}
+
+impl<A: RaftApplication> Default for GenericQueryWidget<A> {
+    fn default() -> Self {
+        Self {
+            widget: SchemaWidget::new(
+                A::Query::schemas(),
+                A::Query::from_inputs,
+                " Querying... ",
+                "Querying",
+            ),
+        }
+    }
+}
+
+impl<A: RaftApplication> QueryWidget<A> for GenericQueryWidget<A> {
+    fn on_user_event(&mut self, event: Event) {
+        self.widget.on_user_event(event);
+    }
+
+    fn back(&self) -> Option<Self> {
+        self.widget.back().map(|widget| Self { widget })
+    }
+
+    fn renderer(&self) -> impl Widget {
+        SchemaWidgetRendered { widget: &self.widget }
+    }
+
+    fn finalize(&mut self) -> Option<A::Query> {
+        self.widget.finalize()
+    }
+}
+
+
+enum SchemaWidgetState<T> {
+    Selecting { selection: usize },
+    Entering { selection: usize, inputs: Vec<String> },
+    Finalized { value: Option<T> },
+}
+
+struct SchemaWidget<T> {
+    schemas: &'static [InputSchema],
+    build: fn(&str, &[String]) -> Option<T>,
+    selecting_title: &'static str,
+    entering_title: &'static str,
+    state: SchemaWidgetState<T>,
+}
+
+impl<T> SchemaWidget<T> {
+    fn new(
+        schemas: &'static [InputSchema],
+        build: fn(&str, &[String]) -> Option<T>,
+        selecting_title: &'static str,
+        entering_title: &'static str,
+    ) -> Self {
+        Self {
+            schemas,
+            build,
+            selecting_title,
+            entering_title,
+            state: SchemaWidgetState::Selecting { selection: 0 },
+        }
+    }
+
+    fn with_state(&self, state: SchemaWidgetState<T>) -> Self {
+        Self {
+            schemas: self.schemas,
+            build: self.build,
+            selecting_title: self.selecting_title,
+            entering_title: self.entering_title,
+            state,
+        }
+    }
+
+    fn select(&mut self, selection: usize) {
+        let schema = &self.schemas[selection];
+        if schema.inputs().is_empty() {
+            if let Some(value) = (self.build)(schema.name(), &[]) {
+                self.state = SchemaWidgetState::Finalized { value: Some(value) };
+            }
+        } else {
+            self.state = SchemaWidgetState::Entering { selection, inputs: vec![String::new()] };
+        }
+    }
+
+    fn on_user_event(&mut self, event: Event) {
+        let Event::Key(event) = event else {
+            return;
+        };
+        match &mut self.state {
+            SchemaWidgetState::Selecting { selection } => {
+                if self.schemas.is_empty() {
+                    return;
+                }
+                match event.code {
+                    Key::Enter => {
+                        let selection = *selection;
+                        self.select(selection);
+                    },
+
+                    Key::Up => {
+                        if *selection == 0 {
+                            *selection = self.schemas.len() - 1;
+                        } else {
+                            *selection -= 1;
+                        }
+                    },
+                    Key::Down => {
+                        if *selection == self.schemas.len() - 1 {
+                            *selection = 0;
+                        } else {
+                            *selection += 1;
+                        }
+                    },
+
+                    Key::Char(char) => {
+                        if let Some(digit) = char.to_digit(10)
+                            && digit >= 1
+                            && (digit as usize) <= self.schemas.len()
+                        {
+                            self.select(digit as usize - 1);
+                        }
+                    },
+
+                    _ => {},
+                }
+            },
+
+            SchemaWidgetState::Entering { selection, inputs } => {
+                let input = inputs.last_mut().unwrap();
+                match event.code {
+                    Key::Char(char) => {
+                        input.push(char);
+                    },
+                    Key::Backspace => {
+                        input.pop();
+                    },
+                    Key::Enter => {
+                        let schema = &self.schemas[*selection];
+                        if inputs.len() < schema.inputs().len() {
+                            inputs.push(String::new());
+                        } else if let Some(value) = (self.build)(schema.name(), inputs) {
+                            self.state = SchemaWidgetState::Finalized { value: Some(value) };
+                        }
+                    },
+
+                    _ => {},
+                }
+            },
+
+            SchemaWidgetState::Finalized { .. } => unreachable!(),
+        }
+    }
+
+    fn back(&self) -> Option<Self> {
+        match &self.state {
+            SchemaWidgetState::Selecting { .. } => None,
+
+            SchemaWidgetState::Entering { selection, inputs } => {
+                Some(self.with_state(
+                    if inputs.len() == 1 {
+                        SchemaWidgetState::Selecting { selection: *selection }
+                    } else {
+                        SchemaWidgetState::Entering {
+                            selection: *selection,
+                            inputs: inputs[..inputs.len() - 1].to_vec(),
+                        }
+                    },
+                ))
+            },
+
+            SchemaWidgetState::Finalized { .. } => unreachable!(),
+        }
+    }
+
+    fn finalize(&mut self) -> Option<T> {
+        if let SchemaWidgetState::Finalized { value } = &mut self.state {
+            value.take()
+        } else {
+            None
+        }
+    }
+}
+
+struct SchemaWidgetRendered<'debugger, T> {
+    widget: &'debugger SchemaWidget<T>,
+}
+
+impl<'debugger, T> Widget for SchemaWidgetRendered<'debugger, T> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        match &self.widget.state {
+            SchemaWidgetState::Selecting { selection } => {
+                let action_list =
+                    List::new(self.widget.schemas.iter().enumerate().map(|(index, schema)| {
+                        let mut style = Style::default();
+                        if index == *selection {
+                            style = style.reversed();
+                        }
+
+                        let spans = vec![
+                            Span::styled(format!("<{}> ", index + 1), Style::default().magenta()),
+                            Span::styled(schema.name(), style),
+                        ];
+                        Line::from(spans)
+                    }))
+                    .block(
+                        Block::bordered()
+                            .borders(Borders::ALL)
+                            .padding(Padding::left(1))
+                            .title(self.widget.selecting_title)
+                            .title_style(Style::default().fg(Color::Green))
+                            .border_type(BorderType::Rounded),
+                    );
+                let mut action_list_state = ListState::default().with_selected(Some(*selection));
+                let mut vertical_scroll_state =
+                    ScrollbarState::new(action_list.len()).position(*selection);
+
+                StatefulWidget::render(action_list, area, buffer, &mut action_list_state);
+                StatefulWidget::render(
+                    Scrollbar::new(ScrollbarOrientation::VerticalRight).symbols(ScrollbarSet {
+                        track: "│",
+                        thumb: "║",
+                        begin: "╮",
+                        end: "╯",
+                    }),
+                    area,
+                    buffer,
+                    &mut vertical_scroll_state,
+                );
+            },
+
+            SchemaWidgetState::Entering { selection, inputs } => {
+                let schema = &self.widget.schemas[*selection];
+                Paragraph::new({
+                    let spans = vec![
+                        Span::styled(
+                            format!("{}: ", schema.inputs()[inputs.len() - 1]),
+                            Style::default().magenta(),
+                        ),
+                        Span::raw(inputs.last().unwrap().as_str()),
+                        Span::raw("█"),
+                    ];
+                    Line::from(spans)
+                })
+                .block(
+                    Block::bordered()
+                        .borders(Borders::ALL)
+                        .padding(Padding::left(1))
+                        .title(format!(" {} {}... ", self.widget.entering_title, schema.name()))
+                        .title_style(Style::default().fg(Color::Green))
+                        .border_type(BorderType::Rounded),
+                )
+                .render(area, buffer);
+            },
+
+            SchemaWidgetState::Finalized { .. } => unreachable!(),
+        }
+    }
+}
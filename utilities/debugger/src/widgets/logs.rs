@@ -21,9 +21,9 @@ impl LogsWidget {
     }
 }
 
-impl Default for LogsWidget {
-    fn default() -> Self {
-        let logger_state = LoggerState::new().set_default_display_level(LevelFilter::Debug);
+impl LogsWidget {
+    pub fn new(display_level: LevelFilter) -> Self {
+        let logger_state = LoggerState::new().set_default_display_level(display_level);
         logger_state.transition(LoggerEvent::HideKey);
         Self { logger_state }
     }
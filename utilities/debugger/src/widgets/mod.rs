@@ -1,14 +1,23 @@
 mod control;
+mod help;
 mod info;
+mod input;
 mod logs;
 mod scroll;
+mod watch;
 
 pub use {
     control::ControlWidget,
+    help::HelpWidget,
     info::{
         InfoWidget,
         MainTabSelection,
     },
+    input::{
+        GenericCommandWidget,
+        GenericQueryWidget,
+    },
     logs::LogsWidget,
     scroll::ScrollWidget,
+    watch::WatchWidget,
 };
@@ -0,0 +1,31 @@
+use crate::*;
+
+pub struct WatchWidget<'debugger, A: RaftApplication> {
+    pub(crate) query: &'debugger A::Query,
+    pub(crate) peer: &'debugger Peer<A>,
+}
+
+impl<'debugger, A: RaftApplication> Widget for &mut WatchWidget<'debugger, A> {
+    fn render(self, area: Rect, buffer: &mut Buffer) {
+        let result = self.peer.machine().query(self.query);
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Query  ", Style::default().yellow()),
+                Span::raw(format!("{:?}", self.query)),
+            ]),
+            Line::from(vec![
+                Span::styled("Result ", Style::default().yellow()),
+                Span::raw(format!("{:?}", result)),
+            ]),
+        ])
+        .block(
+            Block::bordered()
+                .borders(Borders::ALL)
+                .padding(Padding::left(1))
+                .title(format!(" Watch (Peer {}) ", self.peer.id()))
+                .title_style(Style::default().fg(Color::Green))
+                .border_type(BorderType::Rounded),
+        )
+        .render(area, buffer);
+    }
+}
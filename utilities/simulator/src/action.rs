@@ -6,6 +6,12 @@ pub enum Action<A: RaftApplication> {
     TimeoutElection { peer_id: PeerId },
     /// Triggers election timeout of multiple [Peer]s.
     TimeoutElections { peer_ids: Vec<PeerId> },
+    /// Retransmits lost vote requests of a candidate [Peer].
+    RetransmitVoteRequests { peer_id: PeerId },
+    /// Grants a simulated vote to a candidate [Peer] without routing an actual reply.
+    ///
+    /// The vote is granted on behalf of the first peer the candidate is awaiting a vote from.
+    GrantSimulatedVote { peer_id: PeerId },
 
     /// Transmits a peer request of a [Peer].
     TransmitPeerRequest { peer_id: PeerId, request_id: RequestId },
@@ -33,16 +39,49 @@ pub enum Action<A: RaftApplication> {
     /// Triggers heartbeat timeout of a [Peer].
     TimeoutHeartbeat { peer_id: PeerId },
 
+    /// Advances the logical time of the [Simulation](crate::Simulation) by some ticks.
+    AdvanceTime { ticks: usize },
+
+    /// Forces a [Peer] that is the leader to step down to become a follower without a leader.
+    StepDown { peer_id: PeerId },
+
+    /// Corrupts the persistent state of a [Peer] behind its back.
+    ///
+    /// It's meant to be followed by [Action::RestartPeer] to test recovering from the corruption.
+    CorruptStorage { peer_id: PeerId, kind: StorageCorruption },
+    /// Restarts a [Peer] by reconstructing it from its storage.
+    ///
+    /// Everything that isn't persisted, such as the role and the commit index, is lost.
+    RestartPeer { peer_id: PeerId },
+
     /// Applies committed [LogEntry]s of a [Peer] to its [Machine].
     ///
     /// If `peer_id` is `None`, applies committed entries of all peers.
+    /// Results are captured in [Simulation::applied_results](crate::Simulation::applied_results).
     ApplyCommitted { peer_id: Option<PeerId> },
 
     /// Sends a [Command](RaftCommand) from a [Client].
-    SendCommand { client_id: ClientId, peer_id: Option<PeerId>, command: A::Command },
+    ///
+    /// If `request_id` is `None`, the next request id of the client is used.
+    SendCommand {
+        client_id: ClientId,
+        peer_id: Option<PeerId>,
+        request_id: Option<RequestId>,
+        command: A::Command,
+    },
 
     /// Sends a [Query](RaftQuery) from a [Client].
-    SendQuery { client_id: ClientId, peer_id: Option<PeerId>, query: A::Query },
+    ///
+    /// If `request_id` is `None`, the next request id of the client is used.
+    SendQuery {
+        client_id: ClientId,
+        peer_id: Option<PeerId>,
+        request_id: Option<RequestId>,
+        query: A::Query,
+    },
+
+    /// Asks every [Peer] for the leader they know from a [Client].
+    FindLeader { client_id: ClientId },
 
     /// Transmits a client request to a [Peer].
     TransmitClientRequest { client_id: ClientId, request_id: RequestId },
@@ -61,6 +100,59 @@ pub enum Action<A: RaftApplication> {
     /// `simulation.replay_peers`. Every single [Peer] property is checked, so [Action::Check]
     /// also verifies that non-updated fields are left untouched.
     ///
+    /// Before checking peers individually, it also verifies that
+    /// at most one of `simulation.peers` is the leader of each term.
+    ///
     /// [Action::Check] needs `simulation.enable_check(replay_peer_storages)` to work.
     Check { updates: Vec<Update<A>> },
 }
+
+impl<A: RaftApplication> Action<A> {
+    /// Gets the name of the action for logs and diagnostics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::TimeoutElection { .. } => "TimeoutElection",
+            Action::TimeoutElections { .. } => "TimeoutElections",
+            Action::RetransmitVoteRequests { .. } => "RetransmitVoteRequests",
+            Action::GrantSimulatedVote { .. } => "GrantSimulatedVote",
+
+            Action::TransmitPeerRequest { .. } => "TransmitRequest",
+            Action::TransmitPeerRequests { .. } => "TransmitRequests",
+
+            Action::DropPeerRequest { .. } => "DropPeerRequest",
+            Action::DropPeerRequests { .. } => "DropPeerRequests",
+
+            Action::TransmitPeerReply { .. } => "TransmitReply",
+            Action::TransmitPeerReplies { .. } => "TransmitReplies",
+            Action::DropPeerReply { .. } => "DropPeerReply",
+            Action::DropPeerReplies { .. } => "DropPeerReplies",
+
+            Action::TimeoutHeartbeat { .. } => "TimeoutHeartbeat",
+            Action::AdvanceTime { .. } => "AdvanceTime",
+            Action::StepDown { .. } => "StepDown",
+            Action::CorruptStorage { .. } => "CorruptStorage",
+            Action::RestartPeer { .. } => "RestartPeer",
+            Action::ApplyCommitted { .. } => "ApplyCommitted",
+
+            Action::SendCommand { .. } => "SendCommand",
+            Action::SendQuery { .. } => "SendQuery",
+            Action::FindLeader { .. } => "FindLeader",
+
+            Action::TransmitClientRequest { .. } => "TransmitClientRequest",
+            Action::TransmitClientReply { .. } => "TransmitClientReply",
+            Action::DropClientReply { .. } => "DropClientReply",
+
+            Action::Check { .. } => "Check",
+        }
+    }
+}
+
+/// A corruption of the persistent state of a [Peer] performed by [Action::CorruptStorage].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StorageCorruption {
+    /// Truncates the log down to a log index, losing the entry at it and the entries after it
+    /// as [RaftStorage::truncate_log] does.
+    TruncateLog { down_to: LogIndex },
+    /// Forgets the vote of the peer in its current term.
+    ClearVotedFor,
+}
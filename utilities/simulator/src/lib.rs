@@ -2,17 +2,26 @@
 #![doc = include_str!("../README.md")]
 
 mod action;
+mod outcome;
 mod simulation;
 mod update;
 
 #[doc(inline)]
 pub use {
-    action::Action,
+    action::{
+        Action,
+        StorageCorruption,
+    },
+    outcome::{
+        PerformOutcome,
+        PlannedAction,
+    },
     simulation::Simulation,
     update::Update,
 };
 
 pub(crate) use {
+    crate::outcome::Observation,
     anyhow::Context,
     rafty::prelude::*,
     std::{
@@ -22,5 +31,6 @@ pub(crate) use {
             VecDeque,
         },
         fmt::Debug,
+        path::Path,
     },
 };
@@ -0,0 +1,218 @@
+use crate::*;
+
+/// Summary of the changes a single [Action] caused in a [Simulation].
+#[derive(Debug)]
+pub struct PerformOutcome<A: RaftApplication> {
+    created_peer_transmits: Vec<(PeerId, PeerTransmit<A>)>,
+    consumed_peer_transmits: Vec<(PeerId, PeerTransmit<A>)>,
+    created_client_transmits: Vec<ClientTransmit<A>>,
+    consumed_client_transmits: Vec<ClientTransmit<A>>,
+    role_changes: Vec<(PeerId, RoleTransition)>,
+    applied_results: Vec<(PeerId, LogIndex, A::CommandResult)>,
+}
+
+impl<A: RaftApplication> PerformOutcome<A> {
+    /// Gets the peer transmits buffered by the action alongside the peers buffering them.
+    pub fn created_peer_transmits(&self) -> &[(PeerId, PeerTransmit<A>)] {
+        &self.created_peer_transmits
+    }
+
+    /// Gets the peer transmits transmitted or dropped by the action
+    /// alongside the peers they were buffered in.
+    pub fn consumed_peer_transmits(&self) -> &[(PeerId, PeerTransmit<A>)] {
+        &self.consumed_peer_transmits
+    }
+
+    /// Gets the client transmits buffered by the action.
+    ///
+    /// Requests are buffered in clients and replies are buffered in peers.
+    pub fn created_client_transmits(&self) -> &[ClientTransmit<A>] {
+        &self.created_client_transmits
+    }
+
+    /// Gets the client transmits transmitted or dropped by the action.
+    pub fn consumed_client_transmits(&self) -> &[ClientTransmit<A>] {
+        &self.consumed_client_transmits
+    }
+
+    /// Gets the role changes caused by the action.
+    pub fn role_changes(&self) -> &[(PeerId, RoleTransition)] {
+        &self.role_changes
+    }
+
+    /// Gets the results of the commands applied by the action
+    /// alongside the peers applying them and the indices of their log entries.
+    pub fn applied_results(&self) -> &[(PeerId, LogIndex, A::CommandResult)] {
+        &self.applied_results
+    }
+
+    /// Gets whether the action didn't cause any observable change.
+    pub fn is_empty(&self) -> bool {
+        self.created_peer_transmits.is_empty()
+            && self.consumed_peer_transmits.is_empty()
+            && self.created_client_transmits.is_empty()
+            && self.consumed_client_transmits.is_empty()
+            && self.role_changes.is_empty()
+            && self.applied_results.is_empty()
+    }
+}
+
+/// An [Action] planned by [Simulation::plan] alongside whether it's legal to perform.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlannedAction {
+    pub(crate) index: usize,
+    pub(crate) name: &'static str,
+    pub(crate) legality: Result<(), String>,
+}
+
+impl PlannedAction {
+    /// Gets the position of the action in the plan.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the name of the action.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Gets whether the action is legal to perform, or the reason it's not.
+    pub fn legality(&self) -> &Result<(), String> {
+        &self.legality
+    }
+
+    /// Gets whether the action is legal to perform.
+    pub fn is_legal(&self) -> bool {
+        self.legality.is_ok()
+    }
+}
+
+/// Observable state of a [Simulation] used to compute a [PerformOutcome].
+pub(crate) struct Observation<A: RaftApplication> {
+    peer_transmits: Vec<(PeerId, PeerTransmit<A>)>,
+    client_transmits: Vec<ClientTransmit<A>>,
+    roles: Vec<(PeerId, RoleKind, Term)>,
+    applied_results: Vec<usize>,
+}
+
+impl<A: RaftApplication> Observation<A> {
+    /// Observes the current state of a simulation.
+    pub(crate) fn of(simulation: &Simulation<A>) -> Self {
+        let mut peer_transmits = Vec::new();
+        let mut client_transmits = Vec::new();
+        let mut roles = Vec::with_capacity(simulation.number_of_peers());
+        let mut applied_results = Vec::with_capacity(simulation.number_of_peers());
+
+        for peer_id in (1..=simulation.number_of_peers()).map(PeerId) {
+            let peer = simulation.peer(peer_id).expect("Peer exists in the simulation");
+            peer_transmits.extend(
+                peer.buffered_peer_transmits().iter().map(|transmit| (peer_id, transmit.clone())),
+            );
+            client_transmits.extend(peer.buffered_client_transmits().iter().cloned());
+            roles.push((peer_id, peer.role().kind(), peer.current_term()));
+            applied_results.push(
+                simulation.applied_results(peer_id).expect("Peer exists in the simulation").len(),
+            );
+        }
+        for client_id in (1..=simulation.number_of_clients()).map(ClientId) {
+            let client = simulation.client(client_id).expect("Client exists in the simulation");
+            client_transmits.extend(client.buffered_client_transmits().iter().cloned());
+        }
+
+        Self { peer_transmits, client_transmits, roles, applied_results }
+    }
+
+    /// Computes the outcome of the changes from this observation to the current state of a simulation.
+    pub(crate) fn outcome(self, simulation: &Simulation<A>) -> PerformOutcome<A> {
+        let applied_results = self
+            .applied_results
+            .iter()
+            .enumerate()
+            .flat_map(|(peer_index, applied_before)| {
+                let peer_id = PeerId(peer_index + 1);
+                simulation.applied_results(peer_id).expect("Peer exists in the simulation")
+                    [*applied_before..]
+                    .iter()
+                    .map(move |(index, result)| (peer_id, *index, result.clone()))
+            })
+            .collect();
+
+        let later = Self::of(simulation);
+        fn peer_transmit_key<A: RaftApplication>(
+            (sender, transmit): &(PeerId, PeerTransmit<A>),
+        ) -> (PeerId, PeerId, RequestId, bool) {
+            (*sender, transmit.peer_id(), transmit.request_id(), transmit.message().is_request())
+        }
+        fn client_transmit_key<A: RaftApplication>(
+            transmit: &ClientTransmit<A>,
+        ) -> (ClientId, PeerId, RequestId, bool) {
+            (
+                transmit.client_id(),
+                transmit.peer_id(),
+                transmit.request_id(),
+                transmit.message().is_request(),
+            )
+        }
+
+        let earlier_peer_transmit_keys =
+            self.peer_transmits.iter().map(peer_transmit_key).collect::<BTreeSet<_>>();
+        let later_peer_transmit_keys =
+            later.peer_transmits.iter().map(peer_transmit_key).collect::<BTreeSet<_>>();
+
+        let earlier_client_transmit_keys =
+            self.client_transmits.iter().map(client_transmit_key).collect::<BTreeSet<_>>();
+        let later_client_transmit_keys =
+            later.client_transmits.iter().map(client_transmit_key).collect::<BTreeSet<_>>();
+
+        let created_peer_transmits = later
+            .peer_transmits
+            .into_iter()
+            .filter(|transmit| !earlier_peer_transmit_keys.contains(&peer_transmit_key(transmit)))
+            .collect();
+        let consumed_peer_transmits = self
+            .peer_transmits
+            .into_iter()
+            .filter(|transmit| !later_peer_transmit_keys.contains(&peer_transmit_key(transmit)))
+            .collect();
+
+        let created_client_transmits = later
+            .client_transmits
+            .into_iter()
+            .filter(|transmit| {
+                !earlier_client_transmit_keys.contains(&client_transmit_key(transmit))
+            })
+            .collect();
+        let consumed_client_transmits = self
+            .client_transmits
+            .into_iter()
+            .filter(|transmit| !later_client_transmit_keys.contains(&client_transmit_key(transmit)))
+            .collect();
+
+        let role_changes = self
+            .roles
+            .into_iter()
+            .zip(later.roles)
+            .filter(|((_, earlier_kind, earlier_term), (_, later_kind, later_term))| {
+                earlier_kind != later_kind
+                    || (*later_kind == RoleKind::Candidate && earlier_term != later_term)
+            })
+            .map(|((peer_id, earlier_kind, _), (_, later_kind, later_term))| {
+                let transition = RoleTransition::builder()
+                    .term(later_term)
+                    .from(earlier_kind)
+                    .to(later_kind)
+                    .build();
+                (peer_id, transition)
+            })
+            .collect();
+
+        PerformOutcome {
+            created_peer_transmits,
+            consumed_peer_transmits,
+            created_client_transmits,
+            consumed_client_transmits,
+            role_changes,
+            applied_results,
+        }
+    }
+}
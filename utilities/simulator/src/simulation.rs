@@ -6,6 +6,12 @@ pub struct Simulation<A: RaftApplication> {
     consistency: Consistency,
     peers: Vec<Peer<A>>,
     replay_peers: Vec<Peer<A>>,
+    applied_results: Vec<Vec<(LogIndex, A::CommandResult)>>,
+    time: usize,
+    step_log_level: Option<log::Level>,
+    check_request_ids: bool,
+    check_invariants: bool,
+    corrupted_peer_ids: BTreeSet<PeerId>,
 }
 
 impl<A: RaftApplication> Simulation<A> {
@@ -23,7 +29,7 @@ impl<A: RaftApplication> Simulation<A> {
 
         let clients = (1..=number_of_clients)
             .map(ClientId)
-            .map(|client_id| Client::new(client_id, cluster.clone()))
+            .map(|client_id| Client::new(client_id, cluster.clone(), consistency))
             .collect();
 
         let mut peers = Vec::with_capacity(cluster.len());
@@ -32,7 +38,48 @@ impl<A: RaftApplication> Simulation<A> {
             peers.push(Peer::<A>::new(peer_id, cluster.clone(), consistency, initial_storage));
         }
 
-        Ok(Self { clients, consistency, peers, replay_peers: vec![] })
+        let applied_results = vec![vec![]; peers.len()];
+        Ok(Self {
+            clients,
+            consistency,
+            peers,
+            replay_peers: vec![],
+            applied_results,
+            time: 0,
+            step_log_level: Some(log::Level::Debug),
+            check_request_ids: true,
+            check_invariants: cfg!(debug_assertions),
+            corrupted_peer_ids: BTreeSet::new(),
+        })
+    }
+
+    /// Creates a simulation from the persistent data of peers.
+    ///
+    /// Storages are loaded from the data directories using `load_storage`, in order, so the
+    /// storage of peer 1 is loaded from the first directory. Peers start as followers with
+    /// the term, the log and the snapshot in their storages.
+    pub fn from_snapshot_files<P: AsRef<Path>>(
+        consistency: Consistency,
+        data_directories: impl IntoIterator<Item = P>,
+        number_of_clients: usize,
+        mut load_storage: impl FnMut(&Path) -> anyhow::Result<A::Storage>,
+    ) -> anyhow::Result<Self> {
+        let mut peer_storages = Vec::new();
+        for (peer_index, data_directory) in data_directories.into_iter().enumerate() {
+            let data_directory = data_directory.as_ref();
+            let storage = load_storage(data_directory).with_context(|| {
+                format!(
+                    "Failed to load the storage of Peer {} from {}",
+                    peer_index + 1,
+                    data_directory.display(),
+                )
+            })?;
+            peer_storages.push(storage);
+        }
+        if peer_storages.is_empty() {
+            return Err(anyhow::anyhow!("Cannot create a simulation without data directories"));
+        }
+        Self::new(consistency, peer_storages, number_of_clients)
     }
 
     /// Enables support for [Action::Check] using replay storages.
@@ -58,6 +105,54 @@ impl<A: RaftApplication> Simulation<A> {
     }
 }
 
+impl<A: RaftApplication> Simulation<A>
+where
+    A::Storage: Clone,
+{
+    /// Creates an independent copy of the simulation to explore a different scenario.
+    ///
+    /// Peers, clients and their storages are cloned deeply, so actions performed
+    /// on either simulation afterwards don't affect the other one.
+    pub fn clone_for_fork(&self) -> Self {
+        Self {
+            clients: self.clients.clone(),
+            consistency: self.consistency,
+            peers: self.peers.clone(),
+            replay_peers: self.replay_peers.clone(),
+            applied_results: self.applied_results.clone(),
+            time: self.time,
+            step_log_level: self.step_log_level,
+            check_request_ids: self.check_request_ids,
+            check_invariants: self.check_invariants,
+            corrupted_peer_ids: self.corrupted_peer_ids.clone(),
+        }
+    }
+
+    /// Plans a sequence of actions without performing them in the simulation.
+    ///
+    /// Actions are validated and performed in order on a fork of the simulation, so the legality
+    /// of each action takes the actions before it into account. Illegal actions are not performed
+    /// on the fork. Each planned action is logged with the step log level of the simulation.
+    pub fn plan(&self, actions: impl Iterator<Item = Action<A>>) -> Vec<PlannedAction> {
+        let mut fork = self.clone_for_fork();
+        let mut plan = Vec::new();
+        for (index, action) in actions.enumerate() {
+            let name = action.name();
+            let legality = fork.perform(action).map(|_| ()).map_err(|error| format!("{error:#}"));
+            if let Some(level) = self.step_log_level {
+                match &legality {
+                    Ok(()) => log::log!(level, "Plan #{} ({}) | Legal", index, name),
+                    Err(reason) => {
+                        log::log!(level, "Plan #{} ({}) | Illegal: {}", index, name, reason)
+                    },
+                }
+            }
+            plan.push(PlannedAction { index, name, legality });
+        }
+        plan
+    }
+}
+
 impl<A: RaftApplication> Simulation<A> {
     /// Gets the number of clients in the simulation.
     pub fn number_of_clients(&self) -> usize {
@@ -69,109 +164,250 @@ impl<A: RaftApplication> Simulation<A> {
         self.peers.len()
     }
 
-    /// Gets the peer with the given identifier within the simulation.
-    pub fn peer(&self, peer_id: PeerId) -> &Peer<A> {
-        &self.peers[peer_id.0 - 1]
+    /// Gets the number of peers in each role as `(followers, candidates, leaders)`.
+    ///
+    /// Every peer is in exactly one role:
+    /// ```
+    /// # use rafty::prelude::RaftApplication;
+    /// # use rafty_simulator::Simulation;
+    /// # fn x<A: RaftApplication>(simulation: &Simulation<A>) {
+    /// let (followers, candidates, leaders) = simulation.peer_role_counts();
+    /// assert_eq!(followers + candidates + leaders, simulation.number_of_peers());
+    /// # }
+    /// ```
+    pub fn peer_role_counts(&self) -> (usize, usize, usize) {
+        let mut counts = (0, 0, 0);
+        for peer in self.peers.iter() {
+            match peer.role().kind() {
+                RoleKind::Follower => counts.0 += 1,
+                RoleKind::Candidate => counts.1 += 1,
+                RoleKind::Leader => counts.2 += 1,
+            }
+        }
+        counts
     }
 
-    /// Gets the peer with the given identifier within the simulation mutably.
-    pub fn peer_mut(&mut self, peer_id: PeerId) -> &mut Peer<A> {
-        &mut self.peers[peer_id.0 - 1]
+    /// Gets the peer with the given identifier within the simulation,
+    /// or `None` if it doesn't exist.
+    pub fn peer(&self, peer_id: PeerId) -> Option<&Peer<A>> {
+        peer_id.0.checked_sub(1).and_then(|index| self.peers.get(index))
     }
 
-    /// Gets the client with the given identifier within the simulation.
-    pub fn client(&self, client_id: ClientId) -> &Client<A> {
-        &self.clients[client_id.0 - 1]
+    /// Gets the peer with the given identifier within the simulation mutably,
+    /// or `None` if it doesn't exist.
+    pub fn peer_mut(&mut self, peer_id: PeerId) -> Option<&mut Peer<A>> {
+        peer_id.0.checked_sub(1).and_then(|index| self.peers.get_mut(index))
     }
 
-    /// Gets the client with the given identifier within the simulation mutably.
-    pub fn client_mut(&mut self, client_id: ClientId) -> &mut Client<A> {
-        &mut self.clients[client_id.0 - 1]
+    /// Gets the results of the commands applied by [Action::ApplyCommitted] on the peer
+    /// with the given identifier, alongside the indices of their log entries, in order,
+    /// or `None` if the peer doesn't exist.
+    pub fn applied_results(&self, peer_id: PeerId) -> Option<&[(LogIndex, A::CommandResult)]> {
+        peer_id
+            .0
+            .checked_sub(1)
+            .and_then(|index| self.applied_results.get(index))
+            .map(Vec::as_slice)
     }
-}
 
-impl<A: RaftApplication> Simulation<A> {
-    /// Runs a sequence of actions in the simulation.
-    pub fn run(&mut self, actions: impl Iterator<Item = Action<A>>) -> anyhow::Result<()> {
-        for (index, action) in actions.enumerate() {
-            let action_name = match action {
-                Action::TimeoutElection { .. } => "TimeoutElection",
-                Action::TimeoutElections { .. } => "TimeoutElections",
+    /// Gets the logical time of the simulation in ticks.
+    ///
+    /// It starts from zero and only moves forward with [Action::AdvanceTime].
+    pub fn time(&self) -> usize {
+        self.time
+    }
 
-                Action::TransmitPeerRequest { .. } => "TransmitRequest",
-                Action::TransmitPeerRequests { .. } => "TransmitRequests",
+    /// Sets the level the state of the peers is logged with after each action in [Simulation::run].
+    ///
+    /// It's [log::Level::Debug] by default and `None` disables the logs.
+    pub fn set_step_log_level(&mut self, step_log_level: Option<log::Level>) {
+        self.step_log_level = step_log_level;
+    }
 
-                Action::DropPeerRequest { .. } => "DropPeerRequest",
-                Action::DropPeerRequests { .. } => "DropPeerRequests",
+    /// Sets whether [Action::Check] compares the request ids of the buffered peer transmits.
+    ///
+    /// It's enabled by default. Disabling it makes checks compare the contents of the transmits
+    /// only, so they don't depend on the request counters of the peers.
+    pub fn set_check_request_ids(&mut self, check_request_ids: bool) {
+        self.check_request_ids = check_request_ids;
+    }
 
-                Action::TransmitPeerReply { .. } => "TransmitReply",
-                Action::TransmitPeerReplies { .. } => "TransmitReplies",
-                Action::DropPeerReply { .. } => "DropPeerReply",
-                Action::DropPeerReplies { .. } => "DropPeerReplies",
+    /// Sets whether [Simulation::perform] checks that `last_applied <= commit_index <= last_log_index`
+    /// holds for every peer after each action, failing with the summary of the offending peer.
+    ///
+    /// It's enabled by default in debug builds. Peers with corrupted storages are not checked
+    /// until they're restarted, as corruption breaks the invariant on purpose.
+    pub fn set_check_invariants(&mut self, check_invariants: bool) {
+        self.check_invariants = check_invariants;
+    }
 
-                Action::TimeoutHeartbeat { .. } => "TimeoutHeartbeat",
-                Action::ApplyCommitted { .. } => "ApplyCommitted",
+    /// Gets the client with the given identifier within the simulation,
+    /// or `None` if it doesn't exist.
+    pub fn client(&self, client_id: ClientId) -> Option<&Client<A>> {
+        client_id.0.checked_sub(1).and_then(|index| self.clients.get(index))
+    }
 
-                Action::SendCommand { .. } => "SendCommand",
-                Action::SendQuery { .. } => "SendQuery",
+    /// Gets the client with the given identifier within the simulation mutably,
+    /// or `None` if it doesn't exist.
+    pub fn client_mut(&mut self, client_id: ClientId) -> Option<&mut Client<A>> {
+        client_id.0.checked_sub(1).and_then(|index| self.clients.get_mut(index))
+    }
+}
 
-                Action::TransmitClientRequest { .. } => "TransmitClientRequest",
-                Action::TransmitClientReply { .. } => "TransmitClientReply",
-                Action::DropClientReply { .. } => "DropClientReply",
+impl<A: RaftApplication> Simulation<A> {
+    /// Exports the state of the simulation as a [Graphviz](https://graphviz.org) graph in DOT.
+    ///
+    /// Nodes are the peers labeled with their role, term and commit index,
+    /// and edges are the buffered peer transmits labeled with their message and request id.
+    pub fn export_dot(&self) -> String {
+        let mut dot = String::from("digraph Simulation {\n");
+        for peer_id in (1..=self.number_of_peers()).map(PeerId) {
+            let peer = &self.peers[peer_id.0 - 1];
+            dot.push_str(&format!(
+                "    peer{} [label=\"Peer {}\\n{}\\nterm={}\\ncommit={}\"];\n",
+                peer_id,
+                peer_id,
+                peer.role().kind(),
+                peer.current_term(),
+                peer.commit_index(),
+            ));
+        }
+        for peer_id in (1..=self.number_of_peers()).map(PeerId) {
+            for transmit in self.peers[peer_id.0 - 1].buffered_peer_transmits() {
+                let message = match transmit.message() {
+                    PeerMessage::RequestVoteRequest(_) => "RequestVoteRequest",
+                    PeerMessage::RequestVoteReply(_) => "RequestVoteReply",
+                    PeerMessage::AppendEntriesRequest(_) => "AppendEntriesRequest",
+                    PeerMessage::AppendEntriesReply(_) => "AppendEntriesReply",
+                };
+                dot.push_str(&format!(
+                    "    peer{} -> peer{} [label=\"{} #{}\"];\n",
+                    peer_id,
+                    transmit.peer_id(),
+                    message,
+                    transmit.request_id(),
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
 
-                Action::Check { .. } => "Check",
-            };
+impl<A: RaftApplication> Simulation<A> {
+    /// Runs a sequence of actions in the simulation.
+    pub fn run(&mut self, actions: impl Iterator<Item = Action<A>>) -> anyhow::Result<()> {
+        for (index, action) in actions.enumerate() {
+            let action_name = action.name();
             self.perform(action)
                 .with_context(|| format!("Failed to run Action #{index} ({action_name})"))?;
+            if let Some(level) = self.step_log_level {
+                for peer in self.peers.iter() {
+                    log::log!(
+                        level,
+                        "Action #{} ({}) | {}",
+                        index,
+                        action_name,
+                        peer.debug_summary()
+                    );
+                }
+            }
         }
         Ok(())
     }
 
-    /// Performs a single action in the simulation.
-    pub fn perform(&mut self, action: Action<A>) -> anyhow::Result<()> {
+    /// Performs a single action in the simulation and summarizes the changes it caused.
+    pub fn perform(&mut self, action: Action<A>) -> anyhow::Result<PerformOutcome<A>> {
+        let before = Observation::of(self);
+        let action_name = action.name();
+        self.perform_action(action)?;
+        for peer in self.peers.iter_mut() {
+            let peer_id = peer.id();
+            peer.persist_buffered_peer_transmits().with_context(|| {
+                format!("Failed to persist the buffered transmits of Peer {peer_id}")
+            })?;
+        }
+        if self.check_invariants {
+            for peer in self.peers.iter() {
+                if self.corrupted_peer_ids.contains(&peer.id()) {
+                    continue;
+                }
+                let last_log_index = peer
+                    .log()
+                    .last()
+                    .map(|entry| entry.index())
+                    .unwrap_or(peer.storage().snapshot_metadata().0);
+                if peer.last_applied() > peer.commit_index() || peer.commit_index() > last_log_index
+                {
+                    return Err(anyhow::anyhow!(
+                        "Peer {} violates `last_applied <= commit_index <= last_log_index` \
+                        after {} ({})",
+                        peer.id(),
+                        action_name,
+                        peer.debug_summary(),
+                    ));
+                }
+            }
+        }
+
+        let outcome = before.outcome(self);
+        if let Some(level) = self.step_log_level {
+            for (peer_id, index, result) in outcome.applied_results() {
+                log::log!(
+                    level,
+                    "Peer {} applied log entry {} resulting in `{:?}`",
+                    peer_id,
+                    index,
+                    result,
+                );
+            }
+        }
+        Ok(outcome)
+    }
+
+    fn perform_action(&mut self, action: Action<A>) -> anyhow::Result<()> {
+        self.validate_action(&action).map_err(|error| anyhow::anyhow!(error))?;
         match action {
             Action::TimeoutElection { peer_id } => {
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 peer.trigger_election_timeout();
             },
             Action::TimeoutElections { peer_ids } => {
                 for peer_id in peer_ids {
-                    let raft = self.peer_mut(peer_id);
+                    let raft = &mut self.peers[peer_id.0 - 1];
                     raft.trigger_election_timeout();
                 }
             },
+            Action::RetransmitVoteRequests { peer_id } => {
+                let peer = &mut self.peers[peer_id.0 - 1];
+                peer.retransmit_vote_requests();
+            },
+            Action::GrantSimulatedVote { peer_id } => {
+                let peer = &mut self.peers[peer_id.0 - 1];
+                peer.grant_simulated_vote();
+            },
 
             Action::TransmitPeerRequest { peer_id, request_id } => {
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_request() && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(transmit) => {
-                                let target_peer = self.peer_mut(transmit.peer_id());
-                                target_peer.receive_peer_message(
-                                    peer_id,
-                                    transmit.request_id(),
-                                    transmit.into_message(),
-                                );
-                            },
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot transmit {} of {} as it doesn't exist",
-                            request_id,
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(transmit) => {
+                        let target_peer = &mut self.peers[transmit.peer_id().0 - 1];
+                        target_peer.receive_peer_message(
                             peer_id,
-                        ));
+                            transmit.request_id(),
+                            transmit.into_message(),
+                        );
                     },
+                    None => unreachable!(),
                 }
             },
             Action::TransmitPeerRequests { peer_id, request_ids } => {
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
                 let mut ordered_transmits = BTreeMap::new();
@@ -194,13 +430,8 @@ impl<A: RaftApplication> Simulation<A> {
                     }
                 }
 
-                let mut non_existing_request_ids =
-                    request_ids.iter().copied().collect::<BTreeSet<_>>();
-
                 for transmit in ordered_transmits.into_values() {
-                    non_existing_request_ids.remove(&transmit.request_id());
-
-                    let target_peer = self.peer_mut(transmit.peer_id());
+                    let target_peer = &mut self.peers[transmit.peer_id().0 - 1];
                     target_peer.receive_peer_message(
                         peer_id,
                         transmit.request_id(),
@@ -208,81 +439,28 @@ impl<A: RaftApplication> Simulation<A> {
                     );
                 }
 
-                if !non_existing_request_ids.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "Cannot transmit requests {:?} of {} as they don't exist",
-                        non_existing_request_ids,
-                        peer_id,
-                    ));
-                }
-
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 *peer.buffered_peer_transmits_mut() = new_buffered_transmits;
             },
 
             Action::DropPeerRequest { peer_id, request_id } => {
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_request() && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(_) => {},
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot drop request {} of {} as it doesn't exist",
-                            request_id,
-                            peer_id,
-                        ));
-                    },
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(_) => {},
+                    None => unreachable!(),
                 }
             },
             Action::DropPeerRequests { peer_id, request_ids } => {
-                let peer = self.peer_mut(peer_id);
-                let buffered_transmits = peer.buffered_peer_transmits_mut();
-
-                let mut ordered_transmits = BTreeMap::new();
-
-                let mut new_buffered_transmits = VecDeque::with_capacity(buffered_transmits.len());
-                for transmit in std::mem::take(buffered_transmits) {
-                    if !transmit.message().is_request() {
-                        new_buffered_transmits.push_back(transmit);
-                        continue;
-                    }
-
-                    let request_id = transmit.request_id();
-                    match request_ids.iter().position(|candidate| *candidate == request_id) {
-                        Some(position) => {
-                            ordered_transmits.insert(position, transmit);
-                        },
-                        None => {
-                            new_buffered_transmits.push_back(transmit);
-                        },
-                    }
-                }
-
-                let mut non_existing_request_ids =
-                    request_ids.iter().copied().collect::<BTreeSet<_>>();
-
-                for transmit in ordered_transmits.into_values() {
-                    non_existing_request_ids.remove(&transmit.request_id());
-                }
-
-                if !non_existing_request_ids.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "Cannot drop requests {:?} of {} as they don't exist",
-                        non_existing_request_ids,
-                        peer_id,
-                    ));
-                }
-
-                let peer = self.peer_mut(peer_id);
-                *peer.buffered_peer_transmits_mut() = new_buffered_transmits;
+                let peer = &mut self.peers[peer_id.0 - 1];
+                peer.buffered_peer_transmits_mut().retain(|transmit| {
+                    !transmit.message().is_request()
+                        || !request_ids.contains(&transmit.request_id())
+                });
             },
 
             Action::TransmitPeerReply {
@@ -291,42 +469,31 @@ impl<A: RaftApplication> Simulation<A> {
             } => {
                 let (replied_peer_id, request_id) = replied_peer_and_request_id;
 
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_reply()
                         && transmit.peer_id() == replied_peer_id
                         && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(transmit) => {
-                                let target_peer = self.peer_mut(transmit.peer_id());
-                                target_peer.receive_peer_message(
-                                    peer_id,
-                                    transmit.request_id(),
-                                    transmit.into_message(),
-                                );
-                            },
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot transmit the reply of {} of {} from {} as it doesn't exist",
-                            request_id,
-                            replied_peer_id,
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(transmit) => {
+                        let target_peer = &mut self.peers[transmit.peer_id().0 - 1];
+                        target_peer.receive_peer_message(
                             peer_id,
-                        ));
+                            transmit.request_id(),
+                            transmit.into_message(),
+                        );
                     },
+                    None => unreachable!(),
                 }
             },
             Action::TransmitPeerReplies {
                 peer_id,
                 replied_peer_ids_and_request_ids: replied_peer_and_request_ids,
             } => {
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
                 let mut ordered_transmits = BTreeMap::new();
@@ -353,14 +520,8 @@ impl<A: RaftApplication> Simulation<A> {
                     }
                 }
 
-                let mut non_existing_replied_peer_and_request_ids =
-                    replied_peer_and_request_ids.iter().copied().collect::<BTreeSet<_>>();
-
                 for transmit in ordered_transmits.into_values() {
-                    non_existing_replied_peer_and_request_ids
-                        .remove(&(transmit.peer_id(), transmit.request_id()));
-
-                    let target_peer = self.peer_mut(transmit.peer_id());
+                    let target_peer = &mut self.peers[transmit.peer_id().0 - 1];
                     target_peer.receive_peer_message(
                         peer_id,
                         transmit.request_id(),
@@ -368,117 +529,110 @@ impl<A: RaftApplication> Simulation<A> {
                     );
                 }
 
-                if !non_existing_replied_peer_and_request_ids.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "Cannot transmit replies {:?} of {} as they don't exist",
-                        non_existing_replied_peer_and_request_ids,
-                        peer_id,
-                    ));
-                }
-
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 *peer.buffered_peer_transmits_mut() = new_buffered_transmits;
             },
 
             Action::DropPeerReply { peer_id, replied_peer_id_and_request_id } => {
                 let (replied_peer_id, request_id) = replied_peer_id_and_request_id;
 
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_peer_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_reply()
                         && transmit.peer_id() == replied_peer_id
                         && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(_) => {},
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot drop the reply of {} of {} from {} as it doesn't exist",
-                            request_id,
-                            replied_peer_id,
-                            peer_id,
-                        ));
-                    },
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(_) => {},
+                    None => unreachable!(),
                 }
             },
             Action::DropPeerReplies { peer_id, replied_peer_ids_and_request_ids } => {
-                let peer = self.peer_mut(peer_id);
-                let buffered_transmits = peer.buffered_peer_transmits_mut();
-
-                let mut ordered_transmits = BTreeMap::new();
-
-                let mut new_buffered_transmits = VecDeque::with_capacity(buffered_transmits.len());
-                for transmit in std::mem::take(buffered_transmits) {
-                    if !transmit.message().is_reply() {
-                        new_buffered_transmits.push_back(transmit);
-                        continue;
-                    }
-
-                    let peer_id = transmit.peer_id();
-                    let request_id = transmit.request_id();
-                    match replied_peer_ids_and_request_ids
-                        .iter()
-                        .position(|candidate| *candidate == (peer_id, request_id))
-                    {
-                        Some(position) => {
-                            ordered_transmits.insert(position, transmit);
-                        },
-                        None => {
-                            new_buffered_transmits.push_back(transmit);
-                        },
-                    }
-                }
-
-                let mut non_existing_replied_peer_and_request_ids =
-                    replied_peer_ids_and_request_ids.iter().copied().collect::<BTreeSet<_>>();
+                let peer = &mut self.peers[peer_id.0 - 1];
+                peer.buffered_peer_transmits_mut().retain(|transmit| {
+                    !transmit.message().is_reply()
+                        || !replied_peer_ids_and_request_ids
+                            .contains(&(transmit.peer_id(), transmit.request_id()))
+                });
+            },
 
-                for transmit in ordered_transmits.into_values() {
-                    non_existing_replied_peer_and_request_ids
-                        .remove(&(transmit.peer_id(), transmit.request_id()));
+            Action::TimeoutHeartbeat { peer_id } => {
+                let peer = &mut self.peers[peer_id.0 - 1];
+                peer.trigger_heartbeat_timeout();
+            },
+            Action::AdvanceTime { ticks } => {
+                self.time += ticks;
+                for peer in self.peers.iter_mut() {
+                    peer.advance_time(ticks);
                 }
-
-                if !non_existing_replied_peer_and_request_ids.is_empty() {
-                    return Err(anyhow::anyhow!(
-                        "Cannot drop replies {:?} of {} as they don't exist",
-                        non_existing_replied_peer_and_request_ids,
-                        peer_id,
-                    ));
+            },
+            Action::StepDown { peer_id } => {
+                self.peers[peer_id.0 - 1].step_down();
+            },
+            Action::CorruptStorage { peer_id, kind } => {
+                let peer = &mut self.peers[peer_id.0 - 1];
+                match kind {
+                    StorageCorruption::TruncateLog { down_to } => {
+                        let log = peer
+                            .storage()
+                            .log()
+                            .iter()
+                            .filter(|entry| entry.index() < down_to)
+                            .cloned()
+                            .collect();
+                        peer.set_log(log).with_context(|| {
+                            format!("Failed to truncate the log of Peer {peer_id}")
+                        })?;
+                    },
+                    StorageCorruption::ClearVotedFor => {
+                        peer.set_voted_for(None).with_context(|| {
+                            format!("Failed to clear the voted for of Peer {peer_id}")
+                        })?;
+                    },
                 }
-
-                let peer = self.peer_mut(peer_id);
-                *peer.buffered_peer_transmits_mut() = new_buffered_transmits;
+                self.corrupted_peer_ids.insert(peer_id);
             },
+            Action::RestartPeer { peer_id } => {
+                let peer = self.peers.remove(peer_id.0 - 1);
+                let cluster = peer.cluster().clone();
 
-            Action::TimeoutHeartbeat { peer_id } => {
-                let peer = self.peer_mut(peer_id);
-                peer.trigger_heartbeat_timeout();
+                let mut restarted_peer =
+                    Peer::new(peer_id, cluster, self.consistency, peer.into_storage());
+                restarted_peer.advance_time(self.time);
+
+                self.peers.insert(peer_id.0 - 1, restarted_peer);
+                self.corrupted_peer_ids.remove(&peer_id);
             },
             Action::ApplyCommitted { peer_id } => {
                 if let Some(peer_id) = peer_id {
-                    let peer = self.peer_mut(peer_id);
-                    peer.apply_committed();
+                    let results = self.peers[peer_id.0 - 1].apply_committed();
+                    self.applied_results[peer_id.0 - 1].extend(results);
                 } else {
-                    for peer in self.peers.iter_mut() {
-                        peer.apply_committed();
+                    for (peer, applied_results) in
+                        self.peers.iter_mut().zip(self.applied_results.iter_mut())
+                    {
+                        applied_results.extend(peer.apply_committed());
                     }
                 }
             },
 
-            Action::SendCommand { client_id, peer_id, command } => {
+            Action::SendCommand { client_id, peer_id, request_id, command } => {
                 let client = &mut self.clients[client_id.0 - 1];
-                if let Err(error) = client.command(command.clone(), peer_id) {
+                let result = match request_id {
+                    Some(request_id) => {
+                        client.command_with_request_id(request_id, command.clone(), peer_id)
+                    },
+                    None => client.command(command.clone(), peer_id),
+                };
+                if let Err(error) = result {
                     return Err(anyhow::anyhow!(
-                        "Cannot send `{:?}` command from client {}{}: {}",
+                        "Cannot send `{:?}` command from Client {}{}: {}",
                         command,
                         client_id,
                         if let Some(peer_id) = peer_id {
-                            format!(" to peer {peer_id}")
+                            format!(" to Peer {peer_id}")
                         } else {
                             String::new()
                         },
@@ -486,15 +640,21 @@ impl<A: RaftApplication> Simulation<A> {
                     ));
                 }
             },
-            Action::SendQuery { client_id, peer_id, query } => {
+            Action::SendQuery { client_id, peer_id, request_id, query } => {
                 let client = &mut self.clients[client_id.0 - 1];
-                if let Err(error) = client.query(query.clone(), peer_id) {
+                let result = match request_id {
+                    Some(request_id) => {
+                        client.query_with_request_id(request_id, query.clone(), peer_id)
+                    },
+                    None => client.query(query.clone(), peer_id),
+                };
+                if let Err(error) = result {
                     return Err(anyhow::anyhow!(
-                        "Cannot send `{:?}` query from client {}{}: {}",
+                        "Cannot send `{:?}` query from Client {}{}: {}",
                         query,
                         client_id,
                         if let Some(peer_id) = peer_id {
-                            format!(" to peer {peer_id}")
+                            format!(" to Peer {peer_id}")
                         } else {
                             String::new()
                         },
@@ -502,107 +662,81 @@ impl<A: RaftApplication> Simulation<A> {
                     ));
                 }
             },
+            Action::FindLeader { client_id } => {
+                let client = &mut self.clients[client_id.0 - 1];
+                if let Err(error) = client.find_leader() {
+                    return Err(anyhow::anyhow!(
+                        "Cannot find the leader from Client {}: {}",
+                        client_id,
+                        error,
+                    ));
+                }
+            },
 
             Action::TransmitClientRequest { client_id, request_id } => {
-                let client = self.client_mut(client_id);
+                let client = &mut self.clients[client_id.0 - 1];
                 let buffered_transmits = client.buffered_client_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_request() && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(transmit) => {
-                                let target_peer = self.peer_mut(transmit.peer_id());
-                                target_peer.receive_client_message(
-                                    client_id,
-                                    transmit.request_id(),
-                                    transmit.into_message(),
-                                );
-                            },
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot transmit {} of client {} as it doesn't exist",
-                            request_id,
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(transmit) => {
+                        let target_peer = &mut self.peers[transmit.peer_id().0 - 1];
+                        target_peer.receive_client_message(
                             client_id,
-                        ));
+                            transmit.request_id(),
+                            transmit.into_message(),
+                        );
                     },
+                    None => unreachable!(),
                 }
             },
             Action::TransmitClientReply { peer_id, replied_client_id_and_request_id } => {
                 let (replied_client_id, request_id) = replied_client_id_and_request_id;
 
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_client_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_reply()
                         && transmit.client_id() == replied_client_id
                         && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(transmit) => {
-                                let target_client = &mut self.clients[replied_client_id.0 - 1];
-                                target_client.receive_reply(
-                                    peer_id,
-                                    transmit.request_id(),
-                                    transmit.into_message(),
-                                );
-                            },
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot transmit the reply of #{} of client {} \
-                                from peer {} as it doesn't exist",
-                            request_id,
-                            replied_client_id,
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(transmit) => {
+                        let target_client = &mut self.clients[replied_client_id.0 - 1];
+                        target_client.receive_reply(
                             peer_id,
-                        ));
+                            transmit.request_id(),
+                            transmit.into_message(),
+                        );
                     },
+                    None => unreachable!(),
                 }
             },
             Action::DropClientReply { peer_id, replied_client_id_and_request_id } => {
                 let (replied_client_id, request_id) = replied_client_id_and_request_id;
 
-                let peer = self.peer_mut(peer_id);
+                let peer = &mut self.peers[peer_id.0 - 1];
                 let buffered_transmits = peer.buffered_client_transmits_mut();
 
-                match buffered_transmits.iter().position(|transmit| {
+                let position = buffered_transmits.iter().position(|transmit| {
                     transmit.message().is_reply()
                         && transmit.client_id() == replied_client_id
                         && transmit.request_id() == request_id
-                }) {
-                    Some(position) => {
-                        match buffered_transmits.remove(position) {
-                            Some(_) => {},
-                            None => unreachable!(),
-                        }
-                    },
-                    None => {
-                        return Err(anyhow::anyhow!(
-                            "Cannot drop the reply of #{} of client {} \
-                                from peer {} as it doesn't exist",
-                            request_id,
-                            replied_client_id,
-                            peer_id,
-                        ));
-                    },
+                });
+                match position.and_then(|position| buffered_transmits.remove(position)) {
+                    Some(_) => {},
+                    None => unreachable!(),
                 }
             },
 
             Action::Check { updates } => {
-                if self.replay_peers.is_empty() {
-                    return Err(anyhow::anyhow!("Checks are not enabled"));
-                }
                 for update in updates {
                     update.apply_to(&mut self.replay_peers)?;
                 }
+                self.check_leaders()?;
                 for peer_id in 1..=self.peers.len() {
                     self.check(PeerId(peer_id))?;
                 }
@@ -613,6 +747,523 @@ impl<A: RaftApplication> Simulation<A> {
 }
 
 impl<A: RaftApplication> Simulation<A> {
+    /// Elects a peer as the leader and commits its initial no-op entry.
+    ///
+    /// It times out the election of the peer, transmits its vote requests and their replies,
+    /// then transmits its initial append entries requests and their replies. Other transmits
+    /// in the simulation are left untouched.
+    pub fn elect(&mut self, peer_id: PeerId) -> anyhow::Result<()> {
+        self.validate_peer_id(peer_id).map_err(|error| anyhow::anyhow!(error))?;
+
+        let term = self.peers[peer_id.0 - 1].current_term().next();
+        if let Some(peer) = self.peers.iter().find(|peer| peer.current_term() > term) {
+            return Err(anyhow::anyhow!(
+                "Cannot elect Peer {} for term {} as Peer {} is in a higher term {}",
+                peer_id,
+                term,
+                peer.id(),
+                peer.current_term(),
+            ));
+        }
+
+        self.perform(Action::TimeoutElection { peer_id })?;
+
+        let candidate = &self.peers[peer_id.0 - 1];
+        let vote_requests = candidate
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| matches!(transmit.message(), PeerMessage::RequestVoteRequest(_)))
+            .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+            .collect::<Vec<_>>();
+        self.perform(Action::TransmitPeerRequests {
+            peer_id,
+            request_ids: vote_requests.iter().map(|(_, request_id)| *request_id).collect(),
+        })?;
+        for (voter_id, request_id) in vote_requests {
+            self.perform(Action::TransmitPeerReply {
+                peer_id: voter_id,
+                replied_peer_id_and_request_id: (peer_id, request_id),
+            })?;
+        }
+
+        if !self.peers[peer_id.0 - 1].role().is_leader() {
+            return Err(anyhow::anyhow!(
+                "Cannot elect Peer {} for term {} as it didn't receive the majority of the votes",
+                peer_id,
+                term,
+            ));
+        }
+
+        let append_entries_requests = self.peers[peer_id.0 - 1]
+            .buffered_peer_transmits()
+            .iter()
+            .filter(|transmit| matches!(transmit.message(), PeerMessage::AppendEntriesRequest(_)))
+            .map(|transmit| (transmit.peer_id(), transmit.request_id()))
+            .collect::<Vec<_>>();
+        self.perform(Action::TransmitPeerRequests {
+            peer_id,
+            request_ids: append_entries_requests
+                .iter()
+                .map(|(_, request_id)| *request_id)
+                .collect(),
+        })?;
+        for (follower_id, request_id) in append_entries_requests {
+            self.perform(Action::TransmitPeerReply {
+                peer_id: follower_id,
+                replied_peer_id_and_request_id: (peer_id, request_id),
+            })?;
+        }
+
+        if !self.peers[peer_id.0 - 1].can_serve_linearizable_read() {
+            return Err(anyhow::anyhow!(
+                "Cannot elect Peer {} for term {} as its initial no-op entry is not committed",
+                peer_id,
+                term,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Transmits every buffered transmit in the simulation until none remain.
+    ///
+    /// Each round transmits the buffered peer requests and replies of every peer in peer order,
+    /// followed by the buffered client requests of every client and the buffered client replies
+    /// of every peer if `include_client_transmits` is set. Transmits created during a round are
+    /// transmitted in the next round. Time is not advanced, so no new heartbeats are sent.
+    ///
+    /// Returns the number of transmits performed, or an error if the simulation doesn't quiesce
+    /// within `max_steps` transmits.
+    pub fn transmit_all_pending(
+        &mut self,
+        include_client_transmits: bool,
+        max_steps: usize,
+    ) -> anyhow::Result<usize> {
+        let mut steps = 0;
+        loop {
+            let mut actions = Vec::new();
+            for peer in self.peers.iter() {
+                for transmit in peer.buffered_peer_transmits() {
+                    actions.push(
+                        if transmit.message().is_request() {
+                            Action::TransmitPeerRequest {
+                                peer_id: peer.id(),
+                                request_id: transmit.request_id(),
+                            }
+                        } else {
+                            Action::TransmitPeerReply {
+                                peer_id: peer.id(),
+                                replied_peer_id_and_request_id: (
+                                    transmit.peer_id(),
+                                    transmit.request_id(),
+                                ),
+                            }
+                        },
+                    );
+                }
+            }
+            if include_client_transmits {
+                for client in self.clients.iter() {
+                    for transmit in client.buffered_client_transmits() {
+                        if transmit.message().is_request() {
+                            actions.push(Action::TransmitClientRequest {
+                                client_id: client.id(),
+                                request_id: transmit.request_id(),
+                            });
+                        }
+                    }
+                }
+                for peer in self.peers.iter() {
+                    for transmit in peer.buffered_client_transmits() {
+                        if transmit.message().is_reply() {
+                            actions.push(Action::TransmitClientReply {
+                                peer_id: peer.id(),
+                                replied_client_id_and_request_id: (
+                                    transmit.client_id(),
+                                    transmit.request_id(),
+                                ),
+                            });
+                        }
+                    }
+                }
+            }
+
+            if actions.is_empty() {
+                return Ok(steps);
+            }
+            for action in actions {
+                if steps == max_steps {
+                    return Err(anyhow::anyhow!(
+                        "Cannot transmit all pending transmits within {} steps",
+                        max_steps,
+                    ));
+                }
+                self.perform(action)?;
+                steps += 1;
+            }
+        }
+    }
+
+    /// Verifies that the machines of the peers agree on the applied state.
+    ///
+    /// Peers which applied up to the same index must have equal machines, and every entry
+    /// applied by more than one peer must be the same across them, so the machines of the peers
+    /// are prefix-consistent views of the same state. Entries compacted into snapshots are
+    /// covered by the machine comparison only.
+    pub fn verify_machines(&self) -> anyhow::Result<()> {
+        for (first_index, first) in self.peers.iter().enumerate() {
+            for second in self.peers.iter().skip(first_index + 1) {
+                let applied = first.last_applied().min(second.last_applied());
+                for index in (1..=applied.0).map(LogIndex) {
+                    let (Some(first_entry), Some(second_entry)) =
+                        (first.log().entry(index), second.log().entry(index))
+                    else {
+                        continue;
+                    };
+                    if first_entry.term() != second_entry.term()
+                        || first_entry.command() != second_entry.command()
+                    {
+                        return Err(anyhow::anyhow!(
+                            "\nPeer {} and Peer {} applied different entries at index {}\n\
+                            Peer {}: {:?}\nPeer {}: {:?}\n",
+                            first.id(),
+                            second.id(),
+                            index,
+                            first.id(),
+                            first_entry,
+                            second.id(),
+                            second_entry,
+                        ));
+                    }
+                }
+
+                if first.last_applied() == second.last_applied()
+                    && first.machine() != second.machine()
+                {
+                    let first_machine = format!("{:#?}", first.machine());
+                    let second_machine = format!("{:#?}", second.machine());
+                    let (path, first_machine, second_machine) =
+                        first_difference(&first_machine, &second_machine);
+                    return Err(anyhow::anyhow!(
+                        "\nMachines of Peer {} and Peer {} diverge at index {}{}\n\
+                        Peer {}: {}\nPeer {}: {}\n",
+                        first.id(),
+                        second.id(),
+                        applied,
+                        if path.is_empty() { String::new() } else { format!(" at `{path}`") },
+                        first.id(),
+                        first_machine,
+                        second.id(),
+                        second_machine,
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<A: RaftApplication> Simulation<A> {
+    /// Checks whether an action can be performed in the current state of the simulation.
+    ///
+    /// [Simulation::perform] validates actions with it before performing them, so an action
+    /// which passes it can only fail due to the peers or the clients rejecting it. Contents of
+    /// the updates in [Action::Check] are not validated.
+    pub fn validate_action(&self, action: &Action<A>) -> Result<(), String> {
+        match action {
+            Action::TimeoutElection { peer_id }
+            | Action::RetransmitVoteRequests { peer_id }
+            | Action::TimeoutHeartbeat { peer_id }
+            | Action::CorruptStorage { peer_id, .. }
+            | Action::RestartPeer { peer_id } => {
+                self.validate_peer_id(*peer_id)?;
+            },
+            Action::AdvanceTime { ticks } => {
+                if *ticks == 0 {
+                    return Err("Time cannot be advanced by zero ticks".to_owned());
+                }
+            },
+            Action::StepDown { peer_id } => {
+                self.validate_peer_id(*peer_id)?;
+                if !self.peers[peer_id.0 - 1].role().is_leader() {
+                    return Err(format!("Peer {} is not the leader", peer_id));
+                }
+            },
+            Action::GrantSimulatedVote { peer_id } => {
+                self.validate_peer_id(*peer_id)?;
+                match self.peers[peer_id.0 - 1].role() {
+                    Role::Candidate(candidate_state)
+                        if !candidate_state.vote_requests().is_empty() => {},
+                    Role::Candidate(_) => {
+                        return Err(format!("Peer {} is not awaiting any votes", peer_id));
+                    },
+                    _ => return Err(format!("Peer {} is not a candidate", peer_id)),
+                }
+            },
+            Action::TimeoutElections { peer_ids } => {
+                for peer_id in peer_ids {
+                    self.validate_peer_id(*peer_id)?;
+                }
+            },
+
+            Action::TransmitPeerRequest { peer_id, request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                if !self.has_peer_request(*peer_id, *request_id) {
+                    return Err(format!(
+                        "Cannot transmit request {} of Peer {} as it doesn't exist",
+                        request_id, peer_id,
+                    ));
+                }
+                self.validate_peer_request_targets(*peer_id, &[*request_id])?;
+            },
+            Action::TransmitPeerRequests { peer_id, request_ids } => {
+                self.validate_peer_id(*peer_id)?;
+                let non_existing_request_ids = request_ids
+                    .iter()
+                    .copied()
+                    .filter(|request_id| !self.has_peer_request(*peer_id, *request_id))
+                    .collect::<BTreeSet<_>>();
+                if !non_existing_request_ids.is_empty() {
+                    return Err(format!(
+                        "Cannot transmit requests {:?} of Peer {} as they don't exist",
+                        non_existing_request_ids, peer_id,
+                    ));
+                }
+                self.validate_peer_request_targets(*peer_id, request_ids)?;
+            },
+
+            Action::DropPeerRequest { peer_id, request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                if !self.has_peer_request(*peer_id, *request_id) {
+                    return Err(format!(
+                        "Cannot drop request {} of Peer {} as it doesn't exist",
+                        request_id, peer_id,
+                    ));
+                }
+            },
+            Action::DropPeerRequests { peer_id, request_ids } => {
+                self.validate_peer_id(*peer_id)?;
+                let non_existing_request_ids = request_ids
+                    .iter()
+                    .copied()
+                    .filter(|request_id| !self.has_peer_request(*peer_id, *request_id))
+                    .collect::<BTreeSet<_>>();
+                if !non_existing_request_ids.is_empty() {
+                    return Err(format!(
+                        "Cannot drop requests {:?} of Peer {} as they don't exist",
+                        non_existing_request_ids, peer_id,
+                    ));
+                }
+            },
+
+            Action::TransmitPeerReply { peer_id, replied_peer_id_and_request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                let (replied_peer_id, request_id) = *replied_peer_id_and_request_id;
+                self.validate_peer_id(replied_peer_id)?;
+                if !self.has_peer_reply(*peer_id, replied_peer_id, request_id) {
+                    return Err(format!(
+                        "Cannot transmit the reply of request {} of Peer {} from Peer {} as it doesn't exist",
+                        request_id, replied_peer_id, peer_id,
+                    ));
+                }
+            },
+            Action::TransmitPeerReplies { peer_id, replied_peer_ids_and_request_ids } => {
+                self.validate_peer_id(*peer_id)?;
+                for (replied_peer_id, _) in replied_peer_ids_and_request_ids {
+                    self.validate_peer_id(*replied_peer_id)?;
+                }
+                let non_existing_replied_peer_and_request_ids = replied_peer_ids_and_request_ids
+                    .iter()
+                    .copied()
+                    .filter(|(replied_peer_id, request_id)| {
+                        !self.has_peer_reply(*peer_id, *replied_peer_id, *request_id)
+                    })
+                    .collect::<BTreeSet<_>>();
+                if !non_existing_replied_peer_and_request_ids.is_empty() {
+                    return Err(format!(
+                        "Cannot transmit replies {:?} of Peer {} as they don't exist",
+                        non_existing_replied_peer_and_request_ids, peer_id,
+                    ));
+                }
+            },
+
+            Action::DropPeerReply { peer_id, replied_peer_id_and_request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                let (replied_peer_id, request_id) = *replied_peer_id_and_request_id;
+                if !self.has_peer_reply(*peer_id, replied_peer_id, request_id) {
+                    return Err(format!(
+                        "Cannot drop the reply of request {} of Peer {} from Peer {} as it doesn't exist",
+                        request_id, replied_peer_id, peer_id,
+                    ));
+                }
+            },
+            Action::DropPeerReplies { peer_id, replied_peer_ids_and_request_ids } => {
+                self.validate_peer_id(*peer_id)?;
+                let non_existing_replied_peer_and_request_ids = replied_peer_ids_and_request_ids
+                    .iter()
+                    .copied()
+                    .filter(|(replied_peer_id, request_id)| {
+                        !self.has_peer_reply(*peer_id, *replied_peer_id, *request_id)
+                    })
+                    .collect::<BTreeSet<_>>();
+                if !non_existing_replied_peer_and_request_ids.is_empty() {
+                    return Err(format!(
+                        "Cannot drop replies {:?} of Peer {} as they don't exist",
+                        non_existing_replied_peer_and_request_ids, peer_id,
+                    ));
+                }
+            },
+
+            Action::ApplyCommitted { peer_id } => {
+                if let Some(peer_id) = peer_id {
+                    self.validate_peer_id(*peer_id)?;
+                }
+            },
+
+            Action::SendCommand { client_id, peer_id, request_id, .. }
+            | Action::SendQuery { client_id, peer_id, request_id, .. } => {
+                self.validate_client_id(*client_id)?;
+                if let Some(peer_id) = peer_id {
+                    self.validate_peer_id(*peer_id)?;
+                }
+                if let Some(request_id) = request_id
+                    && self.clients[client_id.0 - 1].is_request_id_used(*request_id)
+                {
+                    return Err(format!(
+                        "Request {} is already used by Client {}",
+                        request_id, client_id,
+                    ));
+                }
+            },
+            Action::FindLeader { client_id } => {
+                self.validate_client_id(*client_id)?;
+            },
+
+            Action::TransmitClientRequest { client_id, request_id } => {
+                self.validate_client_id(*client_id)?;
+                let client = &self.clients[client_id.0 - 1];
+                let Some(transmit) = client.buffered_client_transmits().iter().find(|transmit| {
+                    transmit.message().is_request() && transmit.request_id() == *request_id
+                }) else {
+                    return Err(format!(
+                        "Cannot transmit request {} of Client {} as it doesn't exist",
+                        request_id, client_id,
+                    ));
+                };
+                self.validate_peer_id(transmit.peer_id())?;
+            },
+            Action::TransmitClientReply { peer_id, replied_client_id_and_request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                let (replied_client_id, request_id) = *replied_client_id_and_request_id;
+                self.validate_client_id(replied_client_id)?;
+                if !self.has_client_reply(*peer_id, replied_client_id, request_id) {
+                    return Err(format!(
+                        "Cannot transmit the reply of request {} of Client {} \
+                            from Peer {} as it doesn't exist",
+                        request_id, replied_client_id, peer_id,
+                    ));
+                }
+            },
+            Action::DropClientReply { peer_id, replied_client_id_and_request_id } => {
+                self.validate_peer_id(*peer_id)?;
+                let (replied_client_id, request_id) = *replied_client_id_and_request_id;
+                if !self.has_client_reply(*peer_id, replied_client_id, request_id) {
+                    return Err(format!(
+                        "Cannot drop the reply of request {} of Client {} \
+                            from Peer {} as it doesn't exist",
+                        request_id, replied_client_id, peer_id,
+                    ));
+                }
+            },
+
+            Action::Check { .. } => {
+                if self.replay_peers.is_empty() {
+                    return Err("Checks are not enabled".to_owned());
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+impl<A: RaftApplication> Simulation<A> {
+    fn validate_peer_id(&self, peer_id: PeerId) -> Result<(), String> {
+        if peer_id.0 == 0 || peer_id.0 > self.peers.len() {
+            return Err(format!("Peer {} doesn't exist", peer_id));
+        }
+        Ok(())
+    }
+
+    fn validate_client_id(&self, client_id: ClientId) -> Result<(), String> {
+        if client_id.0 == 0 || client_id.0 > self.clients.len() {
+            return Err(format!("Client {} doesn't exist", client_id));
+        }
+        Ok(())
+    }
+
+    fn validate_peer_request_targets(
+        &self,
+        peer_id: PeerId,
+        request_ids: &[RequestId],
+    ) -> Result<(), String> {
+        for transmit in self.peers[peer_id.0 - 1].buffered_peer_transmits() {
+            if transmit.message().is_request() && request_ids.contains(&transmit.request_id()) {
+                self.validate_peer_id(transmit.peer_id())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn has_peer_request(&self, peer_id: PeerId, request_id: RequestId) -> bool {
+        self.peers[peer_id.0 - 1]
+            .buffered_peer_transmits()
+            .iter()
+            .any(|transmit| transmit.message().is_request() && transmit.request_id() == request_id)
+    }
+
+    fn has_peer_reply(
+        &self,
+        peer_id: PeerId,
+        replied_peer_id: PeerId,
+        request_id: RequestId,
+    ) -> bool {
+        self.peers[peer_id.0 - 1].buffered_peer_transmits().iter().any(|transmit| {
+            transmit.message().is_reply()
+                && transmit.peer_id() == replied_peer_id
+                && transmit.request_id() == request_id
+        })
+    }
+
+    fn has_client_reply(
+        &self,
+        peer_id: PeerId,
+        replied_client_id: ClientId,
+        request_id: RequestId,
+    ) -> bool {
+        self.peers[peer_id.0 - 1].buffered_client_transmits().iter().any(|transmit| {
+            transmit.message().is_reply()
+                && transmit.client_id() == replied_client_id
+                && transmit.request_id() == request_id
+        })
+    }
+}
+
+impl<A: RaftApplication> Simulation<A> {
+    fn check_leaders(&self) -> anyhow::Result<()> {
+        let mut leaders = BTreeMap::<Term, PeerId>::new();
+        for peer in self.peers.iter().filter(|peer| peer.role().is_leader()) {
+            if let Some(other_leader_id) = leaders.insert(peer.current_term(), peer.id()) {
+                return Err(anyhow::anyhow!(
+                    "Peer {} and Peer {} are both leaders of term {}",
+                    other_leader_id,
+                    peer.id(),
+                    peer.current_term(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn check(&mut self, peer_id: PeerId) -> anyhow::Result<()> {
         let actual = &mut self.peers[peer_id.0 - 1];
         let expected = &mut self.replay_peers[peer_id.0 - 1];
@@ -624,15 +1275,15 @@ impl<A: RaftApplication> Simulation<A> {
             actual: T,
         ) -> anyhow::Result<()> {
             if actual != expected {
-                let expected_title = format!("Expected {property} of Peer {peer_id}");
-                let actual_title = format!("Actual {property} of Peer {peer_id}");
+                let expected = format!("{expected:#?}");
+                let actual = format!("{actual:#?}");
+                let (path, expected, actual) = first_difference(&expected, &actual);
                 return Err(anyhow::anyhow!(
-                    "\n{}\n{}\n{:#?}\n\n{}\n{}\n{:#?}\n",
-                    expected_title,
-                    "-".repeat(expected_title.len()),
+                    "\n{} of Peer {} differs{}\nExpected: {}\nActual:   {}\n",
+                    property,
+                    peer_id,
+                    if path.is_empty() { String::new() } else { format!(" at `{path}`") },
                     expected,
-                    actual_title,
-                    "-".repeat(actual_title.len()),
                     actual,
                 ));
             }
@@ -663,6 +1314,10 @@ impl<A: RaftApplication> Simulation<A> {
         let actual_last_applied = actual.last_applied();
         check_equality("Last Applied", peer_id, expected_last_applied, actual_last_applied)?;
 
+        let expected_cluster = expected.cluster();
+        let actual_cluster = actual.cluster();
+        check_equality("Cluster", peer_id, expected_cluster, actual_cluster)?;
+
         let expected_role = expected.role();
         let actual_role = actual.role();
         check_equality("Role", peer_id, expected_role, actual_role)?;
@@ -673,6 +1328,15 @@ impl<A: RaftApplication> Simulation<A> {
 
         let expected_buffered_peer_transmits = expected.buffered_peer_transmits();
         let actual_buffered_peer_transmits = actual.buffered_peer_transmits();
+        if !self.check_request_ids
+            && expected_buffered_peer_transmits.len() == actual_buffered_peer_transmits.len()
+            && expected_buffered_peer_transmits
+                .iter()
+                .zip(actual_buffered_peer_transmits)
+                .all(|(expected, actual)| expected.eq_ignoring_request_id(actual))
+        {
+            return Ok(());
+        }
         check_equality(
             "Buffered Peer Transmits",
             peer_id,
@@ -683,3 +1347,62 @@ impl<A: RaftApplication> Simulation<A> {
         Ok(())
     }
 }
+
+/// Finds the first differing line of two pretty debug representations.
+///
+/// Returns the path of the differing field, made of the names of the enclosing fields, types and
+/// keys, alongside the expected and the actual values of it.
+fn first_difference(expected: &str, actual: &str) -> (String, String, String) {
+    fn label(line: &str) -> &str {
+        let line = line.trim().trim_end_matches(',');
+        let line = line.trim_end_matches(['{', '(', '[']).trim_end();
+        line.trim_end_matches(':')
+    }
+
+    fn value(line: Option<&str>) -> String {
+        match line {
+            Some(line) => {
+                let line = line.trim().trim_end_matches(',');
+                let value = match line.split_once(": ") {
+                    Some((_, value)) => value,
+                    None => line,
+                };
+                value.trim_end_matches(['{', '(', '[']).trim_end().to_owned()
+            },
+            None => "<missing>".to_owned(),
+        }
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+
+    let mut path = Vec::new();
+    loop {
+        let expected_line = expected_lines.next();
+        let actual_line = actual_lines.next();
+        if expected_line == actual_line {
+            match expected_line {
+                Some(line) => {
+                    let trimmed = line.trim().trim_end_matches(',');
+                    if trimmed.starts_with(['}', ')', ']']) {
+                        path.pop();
+                    }
+                    if trimmed.ends_with(['{', '(', '[']) {
+                        path.push(label(line).to_owned());
+                    }
+                },
+                None => return (String::new(), expected.to_owned(), actual.to_owned()),
+            }
+            continue;
+        }
+
+        let differing_line = expected_line.or(actual_line).unwrap_or_default();
+        if differing_line.contains(": ") || differing_line.trim().ends_with(['{', '(', '[']) {
+            let key = label(differing_line.split_once(": ").map(|(key, _)| key).unwrap_or(""));
+            if !key.is_empty() {
+                path.push(key.to_owned());
+            }
+        }
+        return (path.join(" > "), value(expected_line), value(actual_line));
+    }
+}
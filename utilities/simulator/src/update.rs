@@ -59,6 +59,12 @@ impl<A: RaftApplication> Update<A> {
         self
     }
 
+    /// Sets the cluster of the peer.
+    pub fn set_cluster(mut self, new_cluster: Cluster) -> Self {
+        self.changes.push(Change::SetCluster { new_cluster });
+        self
+    }
+
     /// Sets the role of the peer.
     pub fn set_role(mut self, new_role: Role<A>) -> Self {
         self.changes.push(Change::SetRole { new_role });
@@ -107,13 +113,16 @@ impl<A: RaftApplication> Update<A> {
 impl<A: RaftApplication> Update<A> {
     /// Applies the update on a cluster of peers.
     pub fn apply_to(self, peers: &mut [Peer<A>]) -> anyhow::Result<()> {
-        let peer = &mut peers[self.peer_id.0 - 1];
+        let peer = match self.peer_id.0.checked_sub(1).and_then(|index| peers.get_mut(index)) {
+            Some(peer) => peer,
+            None => return Err(anyhow::anyhow!("\nPeer {} to update doesn't exist", self.peer_id)),
+        };
         for change in self.changes {
             match change {
                 Change::SetTerm { new_term } => {
                     peer.set_current_term(new_term).with_context(|| {
                         format!(
-                            "\nUnable to set the expected Term of {} in its storage",
+                            "\nUnable to set the expected Term of Peer {} in its storage",
                             self.peer_id,
                         )
                     })?;
@@ -121,7 +130,7 @@ impl<A: RaftApplication> Update<A> {
                 Change::SetVotedFor { new_voted_for } => {
                     peer.set_voted_for(new_voted_for).with_context(|| {
                         format!(
-                            "\nUnable to set the expected Voted For of {} in its storage",
+                            "\nUnable to set the expected Voted For of Peer {} in its storage",
                             self.peer_id,
                         )
                     })?;
@@ -129,7 +138,7 @@ impl<A: RaftApplication> Update<A> {
                 Change::SetLog { new_log } => {
                     peer.set_log(new_log).with_context(|| {
                         format!(
-                            "\nUnable to set the expected Log of {} in its storage",
+                            "\nUnable to set the expected Log of Peer {} in its storage",
                             self.peer_id,
                         )
                     })?;
@@ -137,7 +146,7 @@ impl<A: RaftApplication> Update<A> {
                 Change::SetSnapshot { new_snapshot } => {
                     peer.set_snapshot(new_snapshot).with_context(|| {
                         format!(
-                            "\nUnable to set the expected Snapshot of {} in its storage",
+                            "\nUnable to set the expected Snapshot of Peer {} in its storage",
                             self.peer_id,
                         )
                     })?;
@@ -148,6 +157,9 @@ impl<A: RaftApplication> Update<A> {
                 Change::SetLastApplied { new_last_applied } => {
                     peer.set_last_applied(new_last_applied);
                 },
+                Change::SetCluster { new_cluster } => {
+                    peer.set_cluster(new_cluster);
+                },
                 Change::SetRole { new_role } => {
                     peer.set_role(new_role);
                 },
@@ -174,6 +186,7 @@ enum Change<A: RaftApplication> {
     SetSnapshot { new_snapshot: Snapshot<A> },
     SetCommitIndex { new_commit_index: LogIndex },
     SetLastApplied { new_last_applied: LogIndex },
+    SetCluster { new_cluster: Cluster },
     SetRole { new_role: Role<A> },
     SetMachine { new_machine: A::Machine },
     SetBufferedPeerTransmits { new_transmits: VecDeque<PeerTransmit<A>> },